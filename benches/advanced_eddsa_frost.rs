@@ -5,10 +5,11 @@ use rand_core::SeedableRng;
 
 mod bench_utils;
 use crate::bench_utils::{
-    analyze_received_sizes, ed25519_prepare_sign, PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
+    analyze_received_sizes, ed25519_prepare_sign, report_round_count, PreparedOutputs,
+    MAX_MALICIOUS, SAMPLE_SIZE,
 };
 use threshold_signatures::{
-    frost::eddsa::{sign::sign_v1, SignatureOption},
+    frost::eddsa::{sign::sign_v1, SignatureRoleOutput},
     participants::Participant,
     protocol::Protocol,
     test_utils::{
@@ -17,7 +18,7 @@ use threshold_signatures::{
     ReconstructionLowerBound,
 };
 
-type PreparedSimulatedSig = PreparedOutputs<SignatureOption>;
+type PreparedSimulatedSig = PreparedOutputs<SignatureRoleOutput>;
 
 fn threshold() -> ReconstructionLowerBound {
     ReconstructionLowerBound::from(*MAX_MALICIOUS + 1)
@@ -28,6 +29,7 @@ fn bench_sign(c: &mut Criterion) {
     let num = threshold().value();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
 
     let mut group = c.benchmark_group("sign");
     group.sample_size(*SAMPLE_SIZE);
@@ -39,6 +41,7 @@ fn bench_sign(c: &mut Criterion) {
                     let preps = prepare_simulated_sign(threshold());
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -47,6 +50,7 @@ fn bench_sign(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 criterion_group!(benches, bench_sign);
@@ -74,10 +78,11 @@ fn prepare_simulated_sign(threshold: ReconstructionLowerBound) -> PreparedSimula
         real_participant,
         real_participant,
         keygen_out,
+        None,
         preps.message,
         rng,
     )
-    .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+    .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureRoleOutput>>)
     .expect("Signing should succeed");
 
     // now preparing the simulator
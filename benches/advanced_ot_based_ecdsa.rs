@@ -9,7 +9,7 @@ use rand_core::SeedableRng;
 mod bench_utils;
 use crate::bench_utils::{
     analyze_received_sizes, ot_ecdsa_prepare_presign, ot_ecdsa_prepare_sign,
-    ot_ecdsa_prepare_triples, PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
+    ot_ecdsa_prepare_triples, report_round_count, PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
 };
 
 use threshold_signatures::{
@@ -20,7 +20,7 @@ use threshold_signatures::{
             triples::{generate_triple_many, TriplePub, TripleShare},
             PresignArguments, PresignOutput,
         },
-        SignatureOption,
+        MessageHash, SignatureOption,
     },
     participants::Participant,
     protocol::Protocol,
@@ -48,6 +48,7 @@ fn bench_triples(c: &mut Criterion) {
     let num = participants_num();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
 
     let mut group = c.benchmark_group("triples");
     group.sample_size(*SAMPLE_SIZE);
@@ -59,6 +60,7 @@ fn bench_triples(c: &mut Criterion) {
                     let preps = prepare_simulated_triples(num);
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -67,6 +69,7 @@ fn bench_triples(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 /// Benches the presigning protocol
@@ -74,6 +77,7 @@ fn bench_presign(c: &mut Criterion) {
     let num = participants_num();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
 
     let threshold = threshold();
     let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -91,6 +95,7 @@ fn bench_presign(c: &mut Criterion) {
                     let preps = prepare_simulated_presign(&two_triples);
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -99,6 +104,7 @@ fn bench_presign(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 /// Benches the signing protocol
@@ -106,6 +112,7 @@ fn bench_sign(c: &mut Criterion) {
     let num = participants_num();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
     let threshold = threshold();
     let mut rng = MockCryptoRng::seed_from_u64(42);
     let preps = ot_ecdsa_prepare_triples(num, threshold, &mut rng);
@@ -126,6 +133,7 @@ fn bench_sign(c: &mut Criterion) {
                     let preps = prepare_simulated_sign(&result, threshold, pk);
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -134,6 +142,7 @@ fn bench_sign(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 criterion_group!(benches, bench_triples, bench_presign, bench_sign);
@@ -211,6 +220,7 @@ fn prepare_simulated_presign(
             triple1: (share1, pub1),
             keygen_out,
             threshold,
+            record_provenance: false,
         },
     )
     .map(|presig| Box::new(presig) as Box<dyn Protocol<Output = PresignOutput>>)
@@ -251,7 +261,9 @@ pub fn prepare_simulated_sign(
         real_participant,
         preps.derived_pk,
         preps.presig,
-        preps.msg_hash,
+        preps.presign_id,
+        0,
+        MessageHash::from_prehashed(preps.msg_hash.to_bytes().into()),
     )
     .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
     .expect("Simulated signing should succeed");
@@ -7,13 +7,13 @@ use rand_core::SeedableRng;
 
 mod bench_utils;
 use crate::bench_utils::{
-    analyze_received_sizes, robust_ecdsa_prepare_presign, robust_ecdsa_prepare_sign,
-    PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
+    analyze_received_sizes, report_round_count, robust_ecdsa_prepare_presign,
+    robust_ecdsa_prepare_sign, PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
 };
 use threshold_signatures::{
     ecdsa::{
         robust_ecdsa::{presign::presign, sign::sign, PresignArguments, PresignOutput},
-        SignatureOption,
+        MessageHash, SignatureOption,
     },
     participants::Participant,
     protocol::Protocol,
@@ -35,6 +35,7 @@ fn bench_presign(c: &mut Criterion) {
     let num = participants_num();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
 
     let mut group = c.benchmark_group("presign");
     group.sample_size(*SAMPLE_SIZE);
@@ -46,6 +47,7 @@ fn bench_presign(c: &mut Criterion) {
                     let preps = prepare_simulate_presign(num);
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -54,6 +56,7 @@ fn bench_presign(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 /// Benches the signing protocol
@@ -61,6 +64,7 @@ fn bench_sign(c: &mut Criterion) {
     let num = participants_num();
     let max_malicious = *MAX_MALICIOUS;
     let mut sizes = Vec::with_capacity(*SAMPLE_SIZE);
+    let mut rounds = 0;
 
     let mut rng = MockCryptoRng::seed_from_u64(42);
     let preps = robust_ecdsa_prepare_presign(num, &mut rng);
@@ -77,6 +81,7 @@ fn bench_sign(c: &mut Criterion) {
                     let preps = prepare_simulated_sign(&result, max_malicious, pk);
                     // collecting data sizes
                     sizes.push(preps.simulator.get_view_size());
+                    rounds = preps.simulator.round_count();
                     preps
                 },
                 |preps| run_simulated_protocol(preps.participant, preps.protocol, preps.simulator),
@@ -85,6 +90,7 @@ fn bench_sign(c: &mut Criterion) {
         },
     );
     analyze_received_sizes(&sizes, true);
+    report_round_count(rounds);
 }
 
 criterion_group!(benches, bench_presign, bench_sign);
@@ -123,6 +129,7 @@ fn prepare_simulate_presign(num_participants: usize) -> PreparedPresig {
         PresignArguments {
             keygen_out,
             max_malicious: (*MAX_MALICIOUS).into(),
+            active_security: true,
         },
         real_participant_rng, // provide the exact same randomness
     )
@@ -163,7 +170,9 @@ fn prepare_simulated_sign(
         real_participant,
         preps.derived_pk,
         preps.presig,
-        preps.msg_hash,
+        preps.presign_id,
+        0,
+        MessageHash::from_prehashed(preps.msg_hash.to_bytes().into()),
     )
     .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
     .expect("Presignature should succeed");
@@ -18,7 +18,7 @@ use threshold_signatures::{
             self,
             triples::{generate_triple_many, TriplePub, TripleShare},
         },
-        robust_ecdsa, Scalar,
+        robust_ecdsa, MessageHash, Scalar,
     },
     frost::eddsa,
     participants::Participant,
@@ -65,6 +65,7 @@ pub struct PreparedSig<RerandomizedPresignOutput> {
     )>,
     pub index: usize,
     pub presig: RerandomizedPresignOutput,
+    pub presign_id: ecdsa::PresignId,
     pub derived_pk: AffinePoint,
     pub msg_hash: Scalar,
 }
@@ -115,6 +116,12 @@ pub fn analyze_received_sizes(
     (min, max, avg, median, variance, std_dev)
 }
 
+/// Prints the number of communication rounds seen by a participant,
+/// from which the latency can be estimated as rounds times the round-trip time
+pub fn report_round_count(rounds: usize) {
+    println!("Communication rounds: {rounds}");
+}
+
 /********************* OT Based ECDSA *********************/
 /// Used to prepare ot based ecdsa triples for benchmarking
 pub fn ot_ecdsa_prepare_triples<R: CryptoRngCore + SeedableRng + Send + 'static>(
@@ -177,6 +184,7 @@ pub fn ot_ecdsa_prepare_presign<R: CryptoRngCore + SeedableRng + Send + 'static>
                 triple1: (share1, pub1[0].clone()),
                 keygen_out,
                 threshold,
+                record_provenance: false,
             },
         )
         .expect("Presigning should succeed");
@@ -212,6 +220,8 @@ pub fn ot_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
         .to_element()
         .to_affine();
 
+    // every participant holds the same presignature id
+    let presign_id = result[0].1.id;
     let result = result
         .iter()
         .map(|(p, presig)| {
@@ -236,7 +246,9 @@ pub fn ot_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
             p,
             derived_pk,
             presignature,
-            msg_hash,
+            presign_id,
+            0,
+            MessageHash::from_prehashed(msg_hash.to_bytes().into()),
         )
         .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = ecdsa::SignatureOption>>)
         .expect("Signing should succeed");
@@ -246,6 +258,7 @@ pub fn ot_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
         protocols,
         index,
         presig: result[index].1.clone(),
+        presign_id,
         derived_pk,
         msg_hash,
     }
@@ -297,6 +310,7 @@ pub fn robust_ecdsa_prepare_presign<R: CryptoRngCore + SeedableRng + Send + 'sta
             robust_ecdsa::PresignArguments {
                 keygen_out: keygen_out.clone(),
                 max_malicious: (*MAX_MALICIOUS).into(),
+                active_security: true,
             },
             rng_p,
         )
@@ -334,6 +348,8 @@ pub fn robust_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
         .to_element()
         .to_affine();
 
+    // every participant holds the same presignature id
+    let presign_id = result[0].1.id;
     let result = result
         .iter()
         .map(|(p, presig)| {
@@ -358,7 +374,9 @@ pub fn robust_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
             p,
             derived_pk,
             presignature,
-            msg_hash,
+            presign_id,
+            0,
+            MessageHash::from_prehashed(msg_hash.to_bytes().into()),
         )
         .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = ecdsa::SignatureOption>>)
         .expect("Signing should succeed");
@@ -368,6 +386,7 @@ pub fn robust_ecdsa_prepare_sign<R: CryptoRngCore + SeedableRng>(
         protocols,
         index: coordinator_index,
         presig: result[coordinator_index].1.clone(),
+        presign_id,
         derived_pk,
         msg_hash,
     }
@@ -392,7 +411,7 @@ pub fn ed25519_prepare_sign<R: CryptoRngCore + SeedableRng + Send + 'static>(
 
     let mut protocols: Vec<(
         Participant,
-        Box<dyn Protocol<Output = eddsa::SignatureOption>>,
+        Box<dyn Protocol<Output = eddsa::SignatureRoleOutput>>,
     )> = Vec::with_capacity(participants.len());
 
     let mut message: [u8; 32] = [0u8; 32];
@@ -407,10 +426,11 @@ pub fn ed25519_prepare_sign<R: CryptoRngCore + SeedableRng + Send + 'static>(
             *p,
             coordinator,
             keygen_out.clone(),
+            None,
             message.clone(),
             rng_p,
         )
-        .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = eddsa::SignatureOption>>)
+        .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = eddsa::SignatureRoleOutput>>)
         .expect("Signing should succeed");
         protocols.push((*p, protocol));
     }
@@ -426,7 +446,7 @@ pub fn ed25519_prepare_sign<R: CryptoRngCore + SeedableRng + Send + 'static>(
 pub struct FrostEd25519Sig {
     pub protocols: Vec<(
         Participant,
-        Box<dyn Protocol<Output = eddsa::SignatureOption>>,
+        Box<dyn Protocol<Output = eddsa::SignatureRoleOutput>>,
     )>,
     pub index: usize,
     pub key_packages: Vec<(Participant, eddsa::KeygenOutput)>,
@@ -446,10 +466,8 @@ pub fn prepare_ckd<R: CryptoRngCore + SeedableRng + Send + 'static>(
     let coordinator_index = rng.gen_range(0..num_participants);
     let coordinator = participants[coordinator_index];
 
-    let mut protocols: Vec<(
-        Participant,
-        Box<dyn Protocol<Output = ckd::CKDOutputOption>>,
-    )> = Vec::with_capacity(participants.len());
+    let mut protocols: Vec<(Participant, Box<dyn Protocol<Output = ckd::CKDRoleOutput>>)> =
+        Vec::with_capacity(participants.len());
 
     let mut app_id: [u8; 32] = [0u8; 32];
     rng.fill_bytes(&mut app_id);
@@ -468,9 +486,10 @@ pub fn prepare_ckd<R: CryptoRngCore + SeedableRng + Send + 'static>(
             keygen_out.clone(),
             app_id.clone(),
             app_pk,
+            ckd::CkdParams::default(),
             rng_p,
         )
-        .map(|ckd| Box::new(ckd) as Box<dyn Protocol<Output = ckd::CKDOutputOption>>)
+        .map(|ckd| Box::new(ckd) as Box<dyn Protocol<Output = ckd::CKDRoleOutput>>)
         .expect("Ckd should succeed");
         protocols.push((*p, protocol));
     }
@@ -485,10 +504,7 @@ pub fn prepare_ckd<R: CryptoRngCore + SeedableRng + Send + 'static>(
 }
 
 pub struct PreparedCkdPackage {
-    pub protocols: Vec<(
-        Participant,
-        Box<dyn Protocol<Output = ckd::CKDOutputOption>>,
-    )>,
+    pub protocols: Vec<(Participant, Box<dyn Protocol<Output = ckd::CKDRoleOutput>>)>,
     pub index: usize,
     pub key_packages: Vec<(Participant, ckd::KeygenOutput)>,
     pub app_id: ckd::AppId,
@@ -8,7 +8,7 @@ use crate::bench_utils::{
     analyze_received_sizes, prepare_ckd, PreparedOutputs, MAX_MALICIOUS, SAMPLE_SIZE,
 };
 use threshold_signatures::{
-    confidential_key_derivation::{protocol::ckd, CKDOutputOption},
+    confidential_key_derivation::{protocol::ckd, CKDRoleOutput, CkdParams},
     participants::Participant,
     protocol::Protocol,
     test_utils::{
@@ -17,7 +17,7 @@ use threshold_signatures::{
     ReconstructionLowerBound,
 };
 
-type PreparedSimulatedCkd = PreparedOutputs<CKDOutputOption>;
+type PreparedSimulatedCkd = PreparedOutputs<CKDRoleOutput>;
 
 fn threshold() -> ReconstructionLowerBound {
     ReconstructionLowerBound::from(*MAX_MALICIOUS + 1)
@@ -72,9 +72,10 @@ fn prepare_simulated_ckd(threshold: ReconstructionLowerBound) -> PreparedSimulat
         keygen_out,
         preps.app_id,
         preps.app_pk,
+        CkdParams::default(),
         rng,
     )
-    .map(|ckd| Box::new(ckd) as Box<dyn Protocol<Output = CKDOutputOption>>)
+    .map(|ckd| Box::new(ckd) as Box<dyn Protocol<Output = CKDRoleOutput>>)
     .expect("Ckd should succeed");
 
     // now preparing the simulator
@@ -0,0 +1,66 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use frost_secp256k1::Secp256K1Sha256;
+use rand::{RngCore, SeedableRng};
+use std::hint::black_box;
+use threshold_signatures::{
+    keygen,
+    participants::Participant,
+    protocol::Protocol,
+    test_utils::{
+        generate_participants, run_keygen, run_protocol_and_take_snapshots, MockCryptoRng,
+        Simulator,
+    },
+    KeygenOutput,
+};
+
+const NUM_PARTICIPANTS: usize = 64;
+
+/// Benches the key generation protocol run by all the participants
+/// Enable the `rayon` feature to compare with the parallel share evaluation and validation
+fn bench_keygen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dkg");
+    group.sample_size(10);
+    let participants = generate_participants(NUM_PARTICIPANTS);
+    let threshold = NUM_PARTICIPANTS / 3 + 1;
+
+    group.bench_function(
+        format!("keygen_secp256k1_PARTICIPANTS_{NUM_PARTICIPANTS}_THRESHOLD_{threshold}"),
+        |b| {
+            let mut rng = MockCryptoRng::seed_from_u64(42);
+            b.iter(|| {
+                black_box(run_keygen::<Secp256K1Sha256, _>(
+                    &participants,
+                    threshold,
+                    &mut rng,
+                ))
+            });
+        },
+    );
+    println!(
+        "Communication rounds: {}",
+        keygen_round_count(&participants, threshold)
+    );
+}
+
+/// Counts the communication rounds of the key generation seen by one participant
+fn keygen_round_count(participants: &[Participant], threshold: usize) -> usize {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let mut protocols: Vec<(
+        Participant,
+        Box<dyn Protocol<Output = KeygenOutput<Secp256K1Sha256>>>,
+    )> = Vec::with_capacity(participants.len());
+    for p in participants {
+        let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+        let protocol = keygen::<Secp256K1Sha256>(participants, *p, threshold, rng_p)
+            .expect("Keygen should be initialized");
+        protocols.push((*p, Box::new(protocol)));
+    }
+    let (_, snapshot) = run_protocol_and_take_snapshots(protocols).expect("Keygen should succeed");
+    participants
+        .first()
+        .and_then(|p| Simulator::new(*p, snapshot))
+        .map_or(0, |simulator| simulator.round_count())
+}
+
+criterion_group!(benches, bench_keygen);
+criterion_main!(benches);
@@ -0,0 +1,126 @@
+#!/usr/bin/env python3
+"""Generates the known-answer test vectors consumed by `src/kat.rs`.
+
+The expected signatures are computed with a standalone implementation of
+secp256k1 ECDSA, sharing no code with the crate. Only the standard library
+is required. The FROST vectors are the published ones of RFC 9591 instead.
+
+Usage: python3 scripts/generate-kat-vectors.py
+"""
+
+import hashlib
+import json
+import os
+
+OUT_DIR = os.path.join(os.path.dirname(__file__), "..", "src", "kat")
+
+
+def seeded_scalar(label, modulus):
+    """Derives a nonzero scalar from a label, so that vectors are reproducible."""
+    digest = hashlib.sha512(b"threshold-signatures kat " + label.encode()).digest()
+    return int.from_bytes(digest, "big") % (modulus - 1) + 1
+
+
+def eval_poly(coefficients, x, modulus):
+    return sum(c * pow(x, k, modulus) for k, c in enumerate(coefficients)) % modulus
+
+
+# ------------------------------------------------------------------ secp256k1
+P = 2**256 - 2**32 - 977
+N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+G = (
+    0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+    0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8,
+)
+
+
+def k256_add(a, b):
+    if a is None:
+        return b
+    if b is None:
+        return a
+    if a[0] == b[0] and (a[1] + b[1]) % P == 0:
+        return None
+    if a == b:
+        lam = 3 * a[0] * a[0] * pow(2 * a[1], -1, P) % P
+    else:
+        lam = (b[1] - a[1]) * pow(b[0] - a[0], -1, P) % P
+    x = (lam * lam - a[0] - b[0]) % P
+    return (x, (lam * (a[0] - x) - a[1]) % P)
+
+
+def k256_mul(k, point):
+    result = None
+    while k:
+        if k & 1:
+            result = k256_add(result, point)
+        point = k256_add(point, point)
+        k >>= 1
+    return result
+
+
+def k256_compress(point):
+    return (bytes([2 + (point[1] & 1)]) + point[0].to_bytes(32, "big")).hex()
+
+
+def k256_scalar(x):
+    return x.to_bytes(32, "big").hex()
+
+
+def robust_ecdsa_vector(name, message, participants, max_malicious, coordinator):
+    x = seeded_scalar(name + " secret key", N)
+    k = seeded_scalar(name + " nonce", N)
+    h = int.from_bytes(hashlib.sha256(message).digest(), "big") % N
+
+    big_r = k256_mul(k, G)
+    r = big_r[0] % N
+    s = pow(k, -1, N) * (h + r * x) % N
+    s = min(s, N - s)
+
+    # alpha and beta share k^-1 and x k^-1 with degree max_malicious,
+    # e shares 0 with degree 2 max_malicious
+    k_inv = pow(k, -1, N)
+    alpha = [k_inv] + [seeded_scalar(f"{name} alpha {i}", N) for i in range(max_malicious)]
+    beta = [x * k_inv % N] + [seeded_scalar(f"{name} beta {i}", N) for i in range(max_malicious)]
+    e = [0] + [seeded_scalar(f"{name} e {i}", N) for i in range(2 * max_malicious)]
+
+    shares = []
+    for p in participants:
+        # participant p is associated with the scalar p + 1
+        shares.append(
+            {
+                "id": p,
+                "alpha": k256_scalar(eval_poly(alpha, p + 1, N)),
+                "beta": k256_scalar(eval_poly(beta, p + 1, N)),
+                "e": k256_scalar(eval_poly(e, p + 1, N)),
+            }
+        )
+
+    return {
+        "name": name,
+        "max_malicious": max_malicious,
+        "coordinator": coordinator,
+        "public_key": k256_compress(k256_mul(x, G)),
+        "message": message.hex(),
+        "msg_hash": k256_scalar(h),
+        "big_r": k256_compress(big_r),
+        "shares": shares,
+        "signature": {"r": k256_scalar(r), "s": k256_scalar(s)},
+    }
+
+
+def write(file_name, vectors):
+    with open(os.path.join(OUT_DIR, file_name), "w") as f:
+        json.dump(vectors, f, indent=2)
+        f.write("\n")
+
+
+if __name__ == "__main__":
+    write(
+        "robust_ecdsa.json",
+        [
+            robust_ecdsa_vector("3 participants", b"Hello from Near", [0, 1, 2], 1, 0),
+            robust_ecdsa_vector("5 participants", b"", [0, 1, 2, 3, 4], 2, 3),
+            robust_ecdsa_vector("7 participants", b"\x00" * 64, [0, 2, 4, 6, 8, 10, 12], 3, 12),
+        ],
+    )
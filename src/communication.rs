@@ -0,0 +1,394 @@
+//! Analytical communication cost of the protocols.
+//!
+//! The figures are derived from the messages sent in each round, counting the raw size
+//! of their content: 33 bytes per compressed secp256k1 point, 32 bytes per scalar or hash,
+//! plus the routing header of every message. Encoding the messages adds a small overhead
+//! on top of these figures.
+use crate::errors::InitializationError;
+use crate::protocol::internal::MESSAGE_HEADER_LEN;
+use crate::thresholds::Scheme;
+
+const POINT_BYTES: usize = 33;
+const SCALAR_BYTES: usize = 32;
+const HASH_BYTES: usize = 32;
+
+/// The cost of running a single protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseCost {
+    /// Number of communication rounds, an echo broadcast counting as three
+    pub rounds: usize,
+    /// Bytes received by the busiest participant, message headers included
+    pub bytes_per_participant: usize,
+}
+
+/// The cost of the protocols of a scheme, as returned by [`comm_cost`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommCost {
+    /// Generating the key used by the scheme
+    pub dkg: PhaseCost,
+    /// Presigning, excluding the triple generation for OT based ECDSA.
+    /// `None` for [`Scheme::Dkg`]
+    pub presign: Option<PhaseCost>,
+    /// Signing, the busiest participant being the coordinator.
+    /// `None` for [`Scheme::Dkg`]
+    pub sign: Option<PhaseCost>,
+}
+
+/// Returns the communication cost of the protocols of `scheme` run by `n` participants.
+///
+/// The threshold `t` is the one passed to the protocols of the scheme, i.e. the
+/// reconstruction lower bound for DKG and OT based ECDSA, and the maximum number
+/// of malicious parties for robust ECDSA, whose key is generated with threshold `t + 1`.
+pub fn comm_cost(scheme: Scheme, n: usize, t: usize) -> Result<CommCost, InitializationError> {
+    if n < 2 {
+        return Err(InitializationError::NotEnoughParticipants { participants: n });
+    }
+    let overflow = || {
+        InitializationError::BadParameters(
+            "communication cost must be less than usize::MAX".to_string(),
+        )
+    };
+    match scheme {
+        Scheme::Dkg => {
+            assert_threshold(n, t)?;
+            Ok(CommCost {
+                dkg: dkg_cost(n, t).ok_or_else(overflow)?,
+                presign: None,
+                sign: None,
+            })
+        }
+        Scheme::RobustEcdsa => {
+            if t == 0 {
+                return Err(InitializationError::ThresholdTooSmall {
+                    threshold: t,
+                    min: 1,
+                });
+            }
+            if t.checked_mul(2).and_then(|v| v.checked_add(1)) != Some(n) {
+                return Err(InitializationError::BadParameters(
+                    "robust ECDSA presigning requires exactly 2*max_malicious+1 participants"
+                        .to_string(),
+                ));
+            }
+            Ok(CommCost {
+                dkg: dkg_cost(n, t + 1).ok_or_else(overflow)?,
+                presign: Some(robust_ecdsa_presign_cost(n).ok_or_else(overflow)?),
+                sign: Some(sign_cost(n).ok_or_else(overflow)?),
+            })
+        }
+        Scheme::OtBasedEcdsa => {
+            assert_threshold(n, t)?;
+            Ok(CommCost {
+                dkg: dkg_cost(n, t).ok_or_else(overflow)?,
+                presign: Some(ot_based_ecdsa_presign_cost(n).ok_or_else(overflow)?),
+                sign: Some(sign_cost(n).ok_or_else(overflow)?),
+            })
+        }
+        Scheme::Frost => Err(InitializationError::BadParameters(
+            "the communication cost is only modelled for the secp256k1 schemes".to_string(),
+        )),
+    }
+}
+
+fn assert_threshold(n: usize, t: usize) -> Result<(), InitializationError> {
+    if t < 2 {
+        return Err(InitializationError::ThresholdTooSmall {
+            threshold: t,
+            min: 2,
+        });
+    }
+    if t > n {
+        return Err(InitializationError::ThresholdTooLarge {
+            threshold: t,
+            max: n,
+        });
+    }
+    Ok(())
+}
+
+/// Bytes received from `senders` participants, each sending `messages` messages of `payload` bytes
+fn received(senders: usize, messages: usize, payload: usize) -> Option<usize> {
+    senders
+        .checked_mul(messages)?
+        .checked_mul(MESSAGE_HEADER_LEN.checked_add(payload)?)
+}
+
+/// Bytes received during an echo broadcast of `payload` bytes by every participant.
+///
+/// Each participant sends its own payload, then echoes every payload once
+/// and sends two ready messages for each, the second one amplifying the first.
+fn broadcast(n: usize, payload: usize) -> Option<usize> {
+    let messages = n.checked_mul(3)?.checked_add(1)?;
+    received(n - 1, messages, payload)
+}
+
+fn total(bytes: &[Option<usize>]) -> Option<usize> {
+    bytes
+        .iter()
+        .try_fold(0usize, |acc, part| acc.checked_add((*part)?))
+}
+
+fn dkg_cost(n: usize, t: usize) -> Option<PhaseCost> {
+    // the commitment to the secret polynomial and the proof of knowledge of its constant term
+    let commitment = t
+        .checked_mul(POINT_BYTES)?
+        .checked_add(POINT_BYTES + SCALAR_BYTES)?;
+    let bytes_per_participant = total(&[
+        // session ids
+        broadcast(n, HASH_BYTES),
+        // commitment hashes
+        received(n - 1, 1, HASH_BYTES),
+        // commitments and proofs of knowledge
+        broadcast(n, commitment),
+        // secret shares
+        received(n - 1, 1, SCALAR_BYTES),
+        // success votes along with the session id
+        broadcast(n, 1 + HASH_BYTES),
+    ])?;
+    Some(PhaseCost {
+        rounds: 11,
+        bytes_per_participant,
+    })
+}
+
+fn robust_ecdsa_presign_cost(n: usize) -> Option<PhaseCost> {
+    let bytes_per_participant = total(&[
+        // shares of the five secret polynomials
+        received(n - 1, 1, 5 * SCALAR_BYTES),
+        // R and w shares
+        received(n - 1, 1, POINT_BYTES + SCALAR_BYTES),
+        // W shares
+        received(n - 1, 1, POINT_BYTES),
+    ])?;
+    Some(PhaseCost {
+        rounds: 3,
+        bytes_per_participant,
+    })
+}
+
+fn ot_based_ecdsa_presign_cost(n: usize) -> Option<PhaseCost> {
+    let bytes_per_participant = total(&[
+        // e shares
+        received(n - 1, 1, SCALAR_BYTES),
+        // alpha and beta shares
+        received(n - 1, 1, 2 * SCALAR_BYTES),
+    ])?;
+    Some(PhaseCost {
+        rounds: 2,
+        bytes_per_participant,
+    })
+}
+
+/// Every participant sends its signature share to the coordinator
+fn sign_cost(n: usize) -> Option<PhaseCost> {
+    Some(PhaseCost {
+        rounds: 1,
+        bytes_per_participant: received(n - 1, 1, SCALAR_BYTES)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{comm_cost, PhaseCost};
+    use crate::ecdsa::{
+        ot_based_ecdsa,
+        robust_ecdsa::{self, RerandomizedPresignOutput},
+        KeygenOutput, MessageHash, Polynomial, Scalar, SignatureOption,
+    };
+    use crate::errors::InitializationError;
+    use crate::participants::Participant;
+    use crate::protocol::Protocol;
+    use crate::test_utils::{
+        generate_participants, run_protocol_and_take_snapshots, GenProtocol, MockCryptoRng,
+        Simulator, TestGenerators,
+    };
+    use crate::{keygen, Scheme};
+    use frost_core::keys::SigningShare;
+    use frost_secp256k1::{Secp256K1Sha256, VerifyingKey};
+    use k256::ProjectivePoint;
+    use rand_core::{RngCore, SeedableRng};
+
+    /// Runs the protocols and returns what `busiest` received from `sender`
+    fn measure<T>(
+        protocols: GenProtocol<T>,
+        busiest: Participant,
+        sender: Participant,
+    ) -> Measured {
+        let (_, snapshot) = run_protocol_and_take_snapshots(protocols).unwrap();
+        Measured::new(&Simulator::new(busiest, snapshot).unwrap(), sender)
+    }
+
+    /// The bytes and rounds seen by the busiest participant
+    struct Measured {
+        bytes: usize,
+        rounds: usize,
+    }
+
+    impl Measured {
+        fn new(simulator: &Simulator, sender: Participant) -> Self {
+            // an echo broadcast is the only waitpoint on which a participant sends
+            // several messages, its send, echo and ready phases counting as three rounds
+            let rounds = simulator
+                .messages_per_waitpoint(sender)
+                .into_iter()
+                .map(|messages| if messages == 1 { 1 } else { 3 })
+                .sum();
+            Self {
+                bytes: simulator.get_view_size(),
+                rounds,
+            }
+        }
+
+        /// Checks the analytical figures against the measured ones.
+        ///
+        /// The measured bytes only exceed the analytical figure by the encoding overhead,
+        /// which stays under half of it: the largest one is for secp256k1 scalars,
+        /// encoded as arrays of 32 integers of which about half take two bytes.
+        fn assert_matches(&self, cost: PhaseCost) {
+            assert_eq!(cost.rounds, self.rounds);
+            assert!(
+                cost.bytes_per_participant <= self.bytes
+                    && 2 * self.bytes <= 3 * cost.bytes_per_participant,
+                "analytical {} bytes, measured {} bytes",
+                cost.bytes_per_participant,
+                self.bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_dkg_cost() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        for (n, t) in [(3, 2), (5, 3)] {
+            let participants = generate_participants(n);
+            let mut protocols: GenProtocol<KeygenOutput> = Vec::with_capacity(n);
+            for p in &participants {
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let protocol = keygen::<Secp256K1Sha256>(&participants, *p, t, rng_p).unwrap();
+                protocols.push((*p, Box::new(protocol)));
+            }
+            let cost = comm_cost(Scheme::Dkg, n, t).unwrap();
+            assert_eq!(cost.presign, None);
+            assert_eq!(cost.sign, None);
+            measure(protocols, participants[0], participants[1]).assert_matches(cost.dkg);
+        }
+    }
+
+    #[test]
+    fn test_robust_ecdsa_cost() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious = 2;
+        let participants = generate_participants(5);
+        let cost = comm_cost(Scheme::RobustEcdsa, 5, max_malicious).unwrap();
+
+        let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let public_key = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+        let keygen_outputs = participants
+            .iter()
+            .map(|p| KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                public_key: VerifyingKey::new(public_key),
+                epoch: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut protocols: GenProtocol<robust_ecdsa::PresignOutput> =
+            Vec::with_capacity(participants.len());
+        for (p, keygen_out) in participants.iter().zip(keygen_outputs) {
+            let protocol = robust_ecdsa::presign::presign(
+                &participants,
+                *p,
+                robust_ecdsa::PresignArguments {
+                    keygen_out,
+                    max_malicious: max_malicious.into(),
+                    active_security: true,
+                },
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let (presignatures, snapshot) = run_protocol_and_take_snapshots(protocols).unwrap();
+        let simulator = Simulator::new(participants[0], snapshot).unwrap();
+        Measured::new(&simulator, participants[1]).assert_matches(cost.presign.unwrap());
+
+        let coordinator = participants[0];
+        let msg_hash = Scalar::from(42u64);
+        let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+        for (p, presignature) in presignatures {
+            let protocol = robust_ecdsa::sign::sign(
+                &participants,
+                coordinator,
+                max_malicious,
+                p,
+                public_key.to_affine(),
+                RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                presignature.id,
+                0,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+            )
+            .unwrap();
+            protocols.push((
+                p,
+                Box::new(protocol) as Box<dyn Protocol<Output = SignatureOption>>,
+            ));
+        }
+        measure(protocols, coordinator, participants[1]).assert_matches(cost.sign.unwrap());
+    }
+
+    #[test]
+    fn test_ot_based_ecdsa_cost() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let (n, t) = (3, 2);
+        let generators = TestGenerators::new_contiguous_participant_ids(n, t.into());
+        let keygens = generators.make_ecdsa_keygens(&mut rng);
+        let triple0s = generators.make_triples(&mut rng);
+        let triple1s = generators.make_triples(&mut rng);
+        let cost = comm_cost(Scheme::OtBasedEcdsa, n, t).unwrap();
+
+        let participants = &generators.participants;
+        let mut protocols: GenProtocol<ot_based_ecdsa::PresignOutput> = Vec::with_capacity(n);
+        for p in participants {
+            let protocol = ot_based_ecdsa::presign::presign(
+                participants,
+                *p,
+                ot_based_ecdsa::PresignArguments {
+                    triple0: triple0s[p].clone(),
+                    triple1: triple1s[p].clone(),
+                    keygen_out: keygens[p].clone(),
+                    threshold: t.into(),
+                    record_provenance: false,
+                },
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        measure(protocols, participants[0], participants[1]).assert_matches(cost.presign.unwrap());
+    }
+
+    #[test]
+    fn test_comm_cost_rejects_bad_inputs() {
+        assert_eq!(
+            comm_cost(Scheme::Dkg, 1, 1).err().unwrap(),
+            InitializationError::NotEnoughParticipants { participants: 1 }
+        );
+        assert_eq!(
+            comm_cost(Scheme::Dkg, 4, 5).err().unwrap(),
+            InitializationError::ThresholdTooLarge {
+                threshold: 5,
+                max: 4
+            }
+        );
+        assert_eq!(
+            comm_cost(Scheme::OtBasedEcdsa, 4, 1).err().unwrap(),
+            InitializationError::ThresholdTooSmall {
+                threshold: 1,
+                min: 2
+            }
+        );
+        assert!(comm_cost(Scheme::RobustEcdsa, 4, 2).is_err());
+        assert!(comm_cost(Scheme::RobustEcdsa, 5, 2).is_ok());
+        assert!(comm_cost(Scheme::Frost, 3, 2).is_err());
+        assert!(comm_cost(Scheme::Dkg, usize::MAX, usize::MAX).is_err());
+    }
+}
@@ -1,10 +1,15 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use subtle::{Choice, ConstantTimeEq};
 
 use crate::errors::ProtocolError;
 
 /// Represents a unique identifier for an application in the confidential key derivation protocol
+///
+/// App ids are confidential: the protocol exists so that the derivation
+/// service never learns them. Compare them with [`ConstantTimeEq::ct_eq`]
+/// rather than `==` whenever one side is secret.
 #[derive(Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub struct AppId(Arc<[u8]>);
 
@@ -97,6 +102,14 @@ impl std::borrow::Borrow<[u8]> for AppId {
     }
 }
 
+/// Runs in time independent of the contents of the ids,
+/// but not of their lengths
+impl ConstantTimeEq for AppId {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+}
+
 impl std::fmt::Display for AppId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", hex::encode(self.as_bytes()))
@@ -238,6 +251,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_ct_eq() {
+        let app_id = AppId::try_new([0x01, 0x02, 0x03]).unwrap();
+        assert!(bool::from(app_id.ct_eq(&app_id.clone())));
+        assert!(!bool::from(
+            app_id.ct_eq(&AppId::try_new([0x01, 0x02, 0x04]).unwrap())
+        ));
+        assert!(!bool::from(
+            app_id.ct_eq(&AppId::try_new([0x01, 0x02]).unwrap())
+        ));
+        assert!(bool::from(
+            AppId::try_new([])
+                .unwrap()
+                .ct_eq(&AppId::try_new([]).unwrap())
+        ));
+    }
+
     #[test]
     fn test_deref_and_borrow() {
         let bytes = vec![0x01, 0x02, 0x03];
@@ -1,4 +1,6 @@
-use crate::confidential_key_derivation::{hash_app_id_with_pk, ElementG1, Signature, VerifyingKey};
+use crate::confidential_key_derivation::{
+    hash_app_id_with_pk_in_domain, ElementG1, Signature, VerifyingKey,
+};
 use crate::crypto::ciphersuite::{BytesOrder, ScalarSerializationFormat};
 use crate::crypto::constants::NEAR_CKD_DOMAIN;
 use blstrs::{G1Affine, G2Affine};
@@ -32,7 +34,23 @@ impl ScalarSerializationFormat for BLS12381SHA256 {
     }
 }
 
-impl crate::Ciphersuite for BLS12381SHA256 {}
+impl crate::Ciphersuite for BLS12381SHA256 {
+    fn scalar_from_uniform_bytes(bytes: &[u8; 64]) -> Scalar {
+        // blstrs has no wide reduction: the little endian integer is split in
+        // chunks of 248 bits, which are all smaller than the group order
+        let from_chunk = |chunk: &[u8]| {
+            let mut repr = [0u8; 32];
+            repr.iter_mut().zip(chunk).for_each(|(r, b)| *r = *b);
+            Scalar::from_bytes_le(&repr)
+                .into_option()
+                .expect("a 248 bits integer is smaller than the group order")
+        };
+        bytes
+            .chunks(31)
+            .rev()
+            .fold(Scalar::ZERO, |acc, chunk| acc.shl(248) + from_chunk(chunk))
+    }
+}
 
 const CONTEXT_STRING: &str = "NEAR-BLS12381-G2-SHA256-v1";
 
@@ -155,6 +173,8 @@ impl frost_core::Group for BLS12381G2Group {
         }
     }
 
+    /// `from_compressed` checks that the point lies in the prime order subgroup,
+    /// which is why the cofactor can be treated as one.
     fn deserialize(buf: &Self::Serialization) -> Result<Self::Element, frost_core::GroupError> {
         Self::Element::from_compressed(buf).into_option().map_or(
             Err(frost_core::GroupError::MalformedElement),
@@ -199,6 +219,8 @@ impl frost_core::Group for BLS12381G1Group {
         }
     }
 
+    /// `from_compressed` checks that the point lies in the prime order subgroup,
+    /// which is why the cofactor can be treated as one.
     fn deserialize(buf: &Self::Serialization) -> Result<Self::Element, frost_core::GroupError> {
         Self::Element::from_compressed(buf).into_option().map_or(
             Err(frost_core::GroupError::MalformedElement),
@@ -219,6 +241,17 @@ pub fn verify_signature(
     verifying_key: &VerifyingKey,
     msg: &[u8],
     signature: &Signature,
+) -> Result<(), frost_core::Error<BLS12381SHA256>> {
+    verify_signature_in_domain(verifying_key, msg, signature, NEAR_CKD_DOMAIN)
+}
+
+/// Same as [`verify_signature`] but using a custom
+/// hash-to-curve domain separation tag
+pub fn verify_signature_in_domain(
+    verifying_key: &VerifyingKey,
+    msg: &[u8],
+    signature: &Signature,
+    domain: &[u8],
 ) -> Result<(), frost_core::Error<BLS12381SHA256>> {
     let element1: G1Affine = signature.into();
     if (!element1.is_on_curve() | !element1.is_torsion_free() | element1.is_identity()).into() {
@@ -231,7 +264,7 @@ pub fn verify_signature(
 
     // Concatenate the master public key (96 bytes) in the hash computation
     // H(pk || app_id) when H is a random oracle
-    let base1 = hash_app_id_with_pk(verifying_key, msg).into();
+    let base1 = hash_app_id_with_pk_in_domain(verifying_key, msg, domain).into();
     let base2 =
         <<BLS12381SHA256 as frost_core::Ciphersuite>::Group as frost_core::Group>::generator()
             .into();
@@ -244,7 +277,12 @@ pub fn verify_signature(
 }
 
 pub fn hash_to_curve(bytes: &[u8]) -> ElementG1 {
-    G1Projective::hash_to_curve(bytes, NEAR_CKD_DOMAIN, &[])
+    hash_to_curve_in_domain(bytes, NEAR_CKD_DOMAIN)
+}
+
+/// Hashes to G1 using the given domain separation tag
+pub fn hash_to_curve_in_domain(bytes: &[u8], domain: &[u8]) -> ElementG1 {
+    G1Projective::hash_to_curve(bytes, domain, &[])
 }
 
 // From https://github.com/ZcashFoundation/frost/blob/3ffc19d8f473d5bc4e07ed41bc884bdb42d6c29f/frost-secp256k1/src/lib.rs#L161
@@ -267,6 +305,7 @@ mod tests {
     use elliptic_curve::{hash2curve::FromOkm, Field, Group};
     use rand_core::SeedableRng;
 
+    use crate::confidential_key_derivation::ciphersuite::{BLS12381G1Group, BLS12381G2Group};
     use crate::confidential_key_derivation::scalar_wrapper::ScalarWrapper;
     use crate::confidential_key_derivation::Scalar;
     use crate::test_utils::MockCryptoRng;
@@ -342,4 +381,42 @@ mod tests {
             frost_core::Error::InvalidSignature
         );
     }
+
+    /// Returns the first compressed encoding with a small x coordinate of a point
+    /// that is on the curve but outside of the prime order subgroup
+    fn small_subgroup_encoding<const N: usize>(
+        outside_subgroup: impl Fn(&[u8; N]) -> bool,
+    ) -> [u8; N] {
+        (0u8..=u8::MAX)
+            .map(|x| {
+                let mut encoded = [0u8; N];
+                // compression flag
+                encoded[0] = 0x80;
+                encoded[N - 1] = x;
+                encoded
+            })
+            .find(|encoded| outside_subgroup(encoded))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_rejects_points_outside_subgroup() {
+        let g1 = small_subgroup_encoding(|encoded| {
+            bool::from(blstrs::G1Affine::from_compressed_unchecked(encoded).is_some())
+                && bool::from(blstrs::G1Affine::from_compressed(encoded).is_none())
+        });
+        assert!(matches!(
+            <BLS12381G1Group as frost_core::Group>::deserialize(&g1),
+            Err(frost_core::GroupError::MalformedElement)
+        ));
+
+        let g2 = small_subgroup_encoding(|encoded| {
+            bool::from(blstrs::G2Affine::from_compressed_unchecked(encoded).is_some())
+                && bool::from(blstrs::G2Affine::from_compressed(encoded).is_none())
+        });
+        assert!(matches!(
+            <BLS12381G2Group as frost_core::Group>::deserialize(&g2),
+            Err(frost_core::GroupError::MalformedElement)
+        ));
+    }
 }
@@ -15,7 +15,14 @@ mod scalar_wrapper;
 #[cfg(test)]
 mod test;
 
+use elliptic_curve::Group;
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+use crate::crypto::constants::NEAR_CKD_DOMAIN;
+use crate::errors::ProtocolError;
+use crate::participants::Participant;
+use crate::protocol::RoleOutput;
 
 pub use app_id::AppId;
 
@@ -28,15 +35,25 @@ pub type KeygenOutput = crate::KeygenOutput<BLS12381SHA256>;
 pub type SigningShare = crate::SigningShare<BLS12381SHA256>;
 
 /// The output of the confidential key derivation protocol when run by the coordinator
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// Records the `app_id` and `app_pk` of the request it answers,
+/// so that it cannot be unmasked on behalf of another request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct CKDOutput {
     big_y: ElementG1,
     big_c: ElementG1,
+    app_id: AppId,
+    app_pk: PublicKey,
 }
 
 impl CKDOutput {
-    pub fn new(big_y: ElementG1, big_c: ElementG1) -> Self {
-        Self { big_y, big_c }
+    pub fn new(big_y: ElementG1, big_c: ElementG1, app_id: AppId, app_pk: PublicKey) -> Self {
+        Self {
+            big_y,
+            big_c,
+            app_id,
+            app_pk,
+        }
     }
 
     /// Outputs `big_y`
@@ -49,14 +66,109 @@ impl CKDOutput {
         self.big_c
     }
 
+    /// Outputs the `app_id` the key was derived for
+    pub fn app_id(&self) -> &AppId {
+        &self.app_id
+    }
+
+    /// Outputs the `app_pk` the key was encrypted to
+    pub fn app_pk(&self) -> PublicKey {
+        self.app_pk
+    }
+
     /// Takes a secret scalar and returns
     /// s <- C − a ⋅ Y = msk ⋅ H ( `app_id` )
-    pub fn unmask(&self, secret_scalar: Scalar) -> Signature {
-        self.big_c - self.big_y * secret_scalar
+    ///
+    /// Fails if `app_id` or the public key of `secret_scalar`
+    /// differ from the ones of the request this output answers
+    pub fn unmask(
+        &self,
+        app_id: &AppId,
+        secret_scalar: Scalar,
+    ) -> Result<Signature, ProtocolError> {
+        if !bool::from(app_id.ct_eq(&self.app_id)) {
+            return Err(ProtocolError::InvalidInput(
+                "the output was derived for a different app id".to_string(),
+            ));
+        }
+        if ElementG1::generator() * secret_scalar != self.app_pk {
+            return Err(ProtocolError::InvalidInput(
+                "the output was encrypted to a different app public key".to_string(),
+            ));
+        }
+        Ok(self.big_c - self.big_y * secret_scalar)
+    }
+
+    /// Encodes the output as the compressed `big_y`, `big_c` and `app_pk`,
+    /// of 48 bytes each, followed by the `app_id`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let app_id = self.app_id.as_bytes();
+        let mut out = Vec::with_capacity(3 * G1_COMPRESSED_LEN + app_id.len());
+        out.extend_from_slice(&self.big_y.to_compressed());
+        out.extend_from_slice(&self.big_c.to_compressed());
+        out.extend_from_slice(&self.app_pk.to_compressed());
+        out.extend_from_slice(app_id);
+        out
+    }
+
+    /// Decodes an output encoded with [`Self::to_bytes`]
+    ///
+    /// Fails on points outside of the prime order subgroup,
+    /// as blst checks the subgroup membership of compressed points
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let (big_y, rest) = decode_g1(bytes)?;
+        let (big_c, rest) = decode_g1(rest)?;
+        let (app_pk, app_id) = decode_g1(rest)?;
+        Ok(Self::new(big_y, big_c, AppId::try_new(app_id)?, app_pk))
+    }
+}
+
+/// The length of a compressed G1 element
+const G1_COMPRESSED_LEN: usize = 48;
+
+/// Decodes a compressed G1 element, checked to be in the prime order subgroup,
+/// from the start of `bytes` and returns it along with the remaining bytes
+fn decode_g1(bytes: &[u8]) -> Result<(ElementG1, &[u8]), ProtocolError> {
+    let (encoded, rest) = bytes
+        .split_first_chunk::<G1_COMPRESSED_LEN>()
+        .ok_or_else(|| ProtocolError::DeserializationError("truncated G1 element".to_string()))?;
+    let point =
+        Option::from(ElementG1::from_compressed(encoded)).ok_or(ProtocolError::MalformedElement)?;
+    Ok((point, rest))
+}
+
+/// Parameters of the confidential key derivation protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CkdParams {
+    /// The domain separation tag fed to hash-to-curve.
+    /// Deployments using distinct domains derive independent keys
+    /// for the same app id.
+    pub domain: Vec<u8>,
+}
+
+impl Default for CkdParams {
+    fn default() -> Self {
+        Self {
+            domain: NEAR_CKD_DOMAIN.to_vec(),
+        }
     }
 }
 
+/// A request of a batch, see [`protocol::ckd_many`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CkdRequest {
+    pub app_id: AppId,
+    pub app_pk: PublicKey,
+    /// The participant aggregating the contributions for this request
+    pub coordinator: Participant,
+}
+
+/// The output of [`protocol::ckd`], only the coordinator gets a [`CKDOutput`]
+pub type CKDRoleOutput = RoleOutput<CKDOutput>;
 /// None for participants and Some for coordinator
+#[deprecated(
+    note = "`ckd` now returns a `CKDRoleOutput`, use `RoleOutput::into_coordinator_output`"
+)]
 pub type CKDOutputOption = Option<CKDOutput>;
 pub type VerifyingKey = crate::VerifyingKey<BLS12381SHA256>;
 pub type PublicKey = ElementG1;
@@ -65,7 +177,37 @@ pub type Signature = ElementG1;
 /// Hashes the app id and the public key as of
 /// H(pk || `app_id`) where H is a random oracle
 pub fn hash_app_id_with_pk(pk: &VerifyingKey, app_id: &[u8]) -> ElementG1 {
+    hash_app_id_with_pk_in_domain(pk, app_id, NEAR_CKD_DOMAIN)
+}
+
+/// Same as [`hash_app_id_with_pk`] but using a custom
+/// hash-to-curve domain separation tag
+pub fn hash_app_id_with_pk_in_domain(pk: &VerifyingKey, app_id: &[u8], domain: &[u8]) -> ElementG1 {
     let compressed_pk = pk.to_element().to_compressed();
     let input = [compressed_pk.as_slice(), app_id].concat();
-    ciphersuite::hash_to_curve(&input)
+    ciphersuite::hash_to_curve_in_domain(&input, domain)
+}
+
+/// Same as [`hash_app_id_with_pk`] for several app ids at once
+pub fn hash_app_id_with_pk_many(pk: &VerifyingKey, app_ids: &[AppId]) -> Vec<ElementG1> {
+    hash_app_id_with_pk_in_domain_many(pk, app_ids, NEAR_CKD_DOMAIN)
+}
+
+/// Same as [`hash_app_id_with_pk_in_domain`] for several app ids at once.
+/// The public key is compressed once and the hash input buffer is reused across ids.
+pub fn hash_app_id_with_pk_in_domain_many(
+    pk: &VerifyingKey,
+    app_ids: &[AppId],
+    domain: &[u8],
+) -> Vec<ElementG1> {
+    let compressed_pk = pk.to_element().to_compressed();
+    let mut input = compressed_pk.to_vec();
+    app_ids
+        .iter()
+        .map(|app_id| {
+            input.truncate(compressed_pk.len());
+            input.extend_from_slice(app_id);
+            ciphersuite::hash_to_curve_in_domain(&input, domain)
+        })
+        .collect()
 }
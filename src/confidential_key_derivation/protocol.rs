@@ -1,17 +1,20 @@
 use crate::confidential_key_derivation::ciphersuite::BLS12381SHA256;
 use crate::confidential_key_derivation::{
-    hash_app_id_with_pk, AppId, CKDOutput, CKDOutputOption, ElementG1, KeygenOutput, PublicKey,
-    Scalar,
+    hash_app_id_with_pk_in_domain, AppId, CKDOutput, CKDRoleOutput, CkdParams, CkdRequest,
+    ElementG1, KeygenOutput, PublicKey, Scalar,
 };
+use crate::crypto::secret::Zeroizing;
 use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::{Participant, ParticipantList};
-use crate::protocol::helpers::recv_from_others;
+use crate::protocol::helpers::collect_from_all;
 use crate::protocol::internal::{make_protocol, Comms, SharedChannel};
+use crate::protocol::RoleOutput;
 use crate::Protocol;
 
+use blstrs::G1Affine;
 use elliptic_curve::{Field, Group};
 use rand_core::CryptoRngCore;
-use zeroize::Zeroizing;
+use std::collections::BTreeSet;
 
 #[allow(clippy::too_many_arguments)]
 fn do_ckd_participant(
@@ -22,16 +25,18 @@ fn do_ckd_participant(
     key_pair: &KeygenOutput,
     app_id: &AppId,
     app_pk: PublicKey,
+    params: &CkdParams,
     rng: &mut impl CryptoRngCore,
-) -> Result<CKDOutputOption, ProtocolError> {
+) -> Result<CKDRoleOutput, ProtocolError> {
     let (norm_big_y, norm_big_c) =
-        compute_signature_share(participants, me, key_pair, app_id, app_pk, rng)?;
+        compute_signature_share(participants, me, key_pair, app_id, app_pk, params, rng)?;
     let waitpoint = chan.next_waitpoint();
     chan.send_private(waitpoint, coordinator, &(norm_big_y, norm_big_c))?;
 
-    Ok(None)
+    Ok(RoleOutput::Participant)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn do_ckd_coordinator(
     mut chan: SharedChannel,
     participants: ParticipantList,
@@ -39,22 +44,48 @@ async fn do_ckd_coordinator(
     key_pair: &KeygenOutput,
     app_id: &AppId,
     app_pk: PublicKey,
+    params: &CkdParams,
     rng: &mut impl CryptoRngCore,
-) -> Result<CKDOutputOption, ProtocolError> {
+) -> Result<CKDRoleOutput, ProtocolError> {
     let (mut norm_big_y, mut norm_big_c) =
-        compute_signature_share(&participants, me, key_pair, app_id, app_pk, rng)?;
+        compute_signature_share(&participants, me, key_pair, app_id, app_pk, params, rng)?;
 
     // Receive everyone's inputs and add them together
     let waitpoint = chan.next_waitpoint();
 
-    for (_, participant_output) in
-        recv_from_others::<CKDOutput>(&chan, waitpoint, &participants, me).await?
-    {
-        norm_big_y += participant_output.big_y();
-        norm_big_c += participant_output.big_c();
+    let contributions = collect_from_all(
+        &chan,
+        waitpoint,
+        &participants,
+        me,
+        |from, (big_y, big_c): (ElementG1, ElementG1)| {
+            check_contribution(from, &big_y)?;
+            check_contribution(from, &big_c)?;
+            Ok((big_y, big_c))
+        },
+    )
+    .await?;
+    for (big_y, big_c) in contributions.values() {
+        norm_big_y += big_y;
+        norm_big_c += big_c;
+    }
+    let ckd_output = CKDOutput::new(norm_big_y, norm_big_c, app_id.clone(), app_pk);
+    Ok(RoleOutput::Coordinator(ckd_output))
+}
+
+/// Checks a point received from `from` before it is added to the aggregate.
+///
+/// An honest contribution is the identity with negligible probability only,
+/// and the decoding should already reject points outside of the subgroup.
+fn check_contribution(from: Participant, point: &ElementG1) -> Result<(), ProtocolError> {
+    let point = G1Affine::from(point);
+    if point.is_identity().into() {
+        return Err(ProtocolError::IdentityElement);
+    }
+    if (!point.is_on_curve() | !point.is_torsion_free()).into() {
+        return Err(ProtocolError::MaliciousParticipant(from));
     }
-    let ckd_output = CKDOutput::new(norm_big_y, norm_big_c);
-    Ok(Some(ckd_output))
+    Ok(())
 }
 
 /// Runs the confidential key derivation protocol.
@@ -63,6 +94,10 @@ async fn do_ckd_coordinator(
 ///
 /// Depending on whether the current participant is a coordinator or not,
 /// runs the signature protocol as either a participant or a coordinator.
+///
+/// The hash-to-curve domain separation tag is taken from `params`,
+/// use `CkdParams::default()` for the standard NEAR domain.
+#[allow(clippy::too_many_arguments)]
 pub fn ckd(
     participants: &[Participant],
     coordinator: Participant,
@@ -70,9 +105,166 @@ pub fn ckd(
     key_pair: KeygenOutput,
     app_id: impl Into<AppId>,
     app_pk: PublicKey,
+    params: CkdParams,
     rng: impl CryptoRngCore + Send + 'static,
-) -> Result<impl Protocol<Output = CKDOutputOption>, InitializationError> {
+) -> Result<impl Protocol<Output = CKDRoleOutput>, InitializationError> {
+    let participants = check_participants(participants, me, [coordinator])?;
+
+    let comms = Comms::new();
+    let chan = comms.shared_channel();
+
+    let fut = run_ckd_protocol(
+        chan,
+        coordinator,
+        me,
+        participants,
+        key_pair,
+        app_id.into(),
+        app_pk,
+        params,
+        rng,
+    );
+    Ok(make_protocol(comms, fut))
+}
+
+/// Runs the confidential key derivation protocol for a batch of requests.
+///
+/// Every request designates its own coordinator, which lets a long running
+/// service spread the aggregation load over the participants. Each participant
+/// sends a single message per coordinator, holding its contributions to the
+/// requests of that coordinator in batch order.
+///
+/// The output has one entry per request, which is `Some` exactly for the
+/// requests coordinated by `me`.
+pub fn ckd_many(
+    participants: &[Participant],
+    me: Participant,
+    key_pair: KeygenOutput,
+    requests: Vec<CkdRequest>,
+    params: CkdParams,
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = Vec<CKDRoleOutput>>, InitializationError> {
+    if requests.is_empty() {
+        return Err(InitializationError::BadParameters(
+            "the batch must contain at least one request".to_string(),
+        ));
+    }
+    let participants = check_participants(
+        participants,
+        me,
+        requests.iter().map(|request| request.coordinator),
+    )?;
+
+    let comms = Comms::new();
+    let chan = comms.shared_channel();
+    let fut = do_ckd_many(chan, participants, me, key_pair, requests, params, rng);
+    Ok(make_protocol(comms, fut))
+}
+
+async fn do_ckd_many(
+    mut chan: SharedChannel,
+    participants: ParticipantList,
+    me: Participant,
+    key_pair: KeygenOutput,
+    requests: Vec<CkdRequest>,
+    params: CkdParams,
+    mut rng: impl CryptoRngCore,
+) -> Result<Vec<CKDRoleOutput>, ProtocolError> {
+    let shares = requests
+        .iter()
+        .map(|request| {
+            compute_signature_share(
+                &participants,
+                me,
+                &key_pair,
+                &request.app_id,
+                request.app_pk,
+                &params,
+                &mut rng,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let shares_for = |coordinator: Participant| {
+        requests
+            .iter()
+            .zip(&shares)
+            .filter(|(request, _)| request.coordinator == coordinator)
+            .map(|(_, share)| *share)
+            .collect::<Vec<_>>()
+    };
+
+    let waitpoint = chan.next_waitpoint();
+    let coordinators = requests
+        .iter()
+        .map(|request| request.coordinator)
+        .collect::<BTreeSet<_>>();
+    for coordinator in coordinators.into_iter().filter(|c| *c != me) {
+        chan.send_private(waitpoint, coordinator, &shares_for(coordinator))?;
+    }
+
+    // the running sums of the requests we coordinate, in batch order
+    let mut sums = shares_for(me);
+    if !sums.is_empty() {
+        let expected = sums.len();
+        let contributions = collect_from_all(
+            &chan,
+            waitpoint,
+            &participants,
+            me,
+            |from, points: Vec<(ElementG1, ElementG1)>| {
+                if points.len() != expected {
+                    return Err(ProtocolError::MalformedPackage {
+                        from,
+                        expected,
+                        got: points.len(),
+                    });
+                }
+                for (big_y, big_c) in &points {
+                    check_contribution(from, big_y)?;
+                    check_contribution(from, big_c)?;
+                }
+                Ok(points)
+            },
+        )
+        .await?;
+        for points in contributions.values() {
+            for ((norm_big_y, norm_big_c), (big_y, big_c)) in sums.iter_mut().zip(points) {
+                *norm_big_y += big_y;
+                *norm_big_c += big_c;
+            }
+        }
+    }
+
+    let mut sums = sums.into_iter();
+    Ok(requests
+        .into_iter()
+        .map(|request| {
+            if request.coordinator != me {
+                return RoleOutput::Participant;
+            }
+            sums.next()
+                .map_or(RoleOutput::Participant, |(big_y, big_c)| {
+                    RoleOutput::Coordinator(CKDOutput::new(
+                        big_y,
+                        big_c,
+                        request.app_id,
+                        request.app_pk,
+                    ))
+                })
+        })
+        .collect())
+}
+
+/// Checks the participants of a CKD run and that `me` and the `coordinators` are among them.
+fn check_participants(
+    participants: &[Participant],
+    me: Participant,
+    coordinators: impl IntoIterator<Item = Participant>,
+) -> Result<ParticipantList, InitializationError> {
     // not enough participants
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -92,28 +284,17 @@ pub fn ckd(
         });
     }
 
-    // ensure the coordinator is a participant
-    if !participants.contains(coordinator) {
-        return Err(InitializationError::MissingParticipant {
-            role: "coordinator",
-            participant: coordinator,
-        });
+    // ensure the coordinators are participants
+    for coordinator in coordinators {
+        if !participants.contains(coordinator) {
+            return Err(InitializationError::MissingParticipant {
+                role: "coordinator",
+                participant: coordinator,
+            });
+        }
     }
 
-    let comms = Comms::new();
-    let chan = comms.shared_channel();
-
-    let fut = run_ckd_protocol(
-        chan,
-        coordinator,
-        me,
-        participants,
-        key_pair,
-        app_id.into(),
-        app_pk,
-        rng,
-    );
-    Ok(make_protocol(comms, fut))
+    Ok(participants)
 }
 
 /// Depending on whether the current participant is a coordinator or not,
@@ -127,10 +308,21 @@ async fn run_ckd_protocol(
     key_pair: KeygenOutput,
     app_id: AppId,
     app_pk: PublicKey,
+    params: CkdParams,
     mut rng: impl CryptoRngCore,
-) -> Result<CKDOutputOption, ProtocolError> {
+) -> Result<CKDRoleOutput, ProtocolError> {
     if me == coordinator {
-        do_ckd_coordinator(chan, participants, me, &key_pair, &app_id, app_pk, &mut rng).await
+        do_ckd_coordinator(
+            chan,
+            participants,
+            me,
+            &key_pair,
+            &app_id,
+            app_pk,
+            &params,
+            &mut rng,
+        )
+        .await
     } else {
         do_ckd_participant(
             chan,
@@ -140,6 +332,7 @@ async fn run_ckd_protocol(
             &key_pair,
             &app_id,
             app_pk,
+            &params,
             &mut rng,
         )
     }
@@ -151,6 +344,7 @@ fn compute_signature_share(
     key_pair: &KeygenOutput,
     app_id: &AppId,
     app_pk: PublicKey,
+    params: &CkdParams,
     rng: &mut impl CryptoRngCore,
 ) -> Result<(ElementG1, ElementG1), ProtocolError> {
     // Ensures the value is zeroized on drop
@@ -165,7 +359,7 @@ fn compute_signature_share(
     let big_y = ElementG1::generator() * y.0;
 
     // H(pk || app_id) when H is a random oracle
-    let hash_point = hash_app_id_with_pk(&key_pair.public_key, app_id);
+    let hash_point = hash_app_id_with_pk_in_domain(&key_pair.public_key, app_id, &params.domain);
 
     // S <- x . H(app_id)
     let big_s = hash_point * private_share.to_scalar();
@@ -185,12 +379,14 @@ fn compute_signature_share(
 mod test {
     use super::*;
     use crate::confidential_key_derivation::{
-        ciphersuite::{hash_to_curve, G2Projective},
-        hash_app_id_with_pk, SigningShare, VerifyingKey,
+        ciphersuite::{hash_to_curve, hash_to_curve_in_domain, G2Projective},
+        hash_app_id_with_pk, hash_app_id_with_pk_in_domain_many, hash_app_id_with_pk_many,
+        SigningShare, VerifyingKey,
     };
+    use crate::protocol::Action;
     use crate::test_utils::{
-        check_one_coordinator_output, generate_participants, run_protocol, GenProtocol,
-        MockCryptoRng,
+        assert_serde_roundtrip, check_one_coordinator_output, generate_participants, run_protocol,
+        GenProtocol, MockCryptoRng,
     };
     use rand::{seq::SliceRandom as _, RngCore, SeedableRng};
 
@@ -207,6 +403,41 @@ mod test {
         assert_ne!(pt1, pt2);
     }
 
+    #[test]
+    fn test_hash2curve_domains() {
+        let app_id = AppId::try_from(b"Hello Near").unwrap();
+        let default_domain = CkdParams::default().domain;
+        let pt1 = hash_to_curve_in_domain(&app_id, &default_domain);
+        assert_eq!(pt1, hash_to_curve(&app_id));
+
+        let pt2 = hash_to_curve_in_domain(&app_id, b"OTHER BLS12381G1_XMD:SHA-256_SSWU_RO_");
+        assert_ne!(pt1, pt2);
+    }
+
+    #[test]
+    fn test_hash_app_id_with_pk_many() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let pk = VerifyingKey::new(G2Projective::generator() * Scalar::random(&mut rng));
+        let app_ids = [b"Hello Near".as_slice(), b"Hello Near!", b"", b"Hello Near"]
+            .into_iter()
+            .map(|app_id| AppId::try_from(app_id).unwrap())
+            .collect::<Vec<_>>();
+
+        let points = hash_app_id_with_pk_many(&pk, &app_ids);
+        assert_eq!(points.len(), app_ids.len());
+        for (app_id, point) in app_ids.iter().zip(&points) {
+            assert_eq!(*point, hash_app_id_with_pk(&pk, app_id));
+        }
+        assert_eq!(points[0], points[3]);
+
+        let domain = b"OTHER BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let points = hash_app_id_with_pk_in_domain_many(&pk, &app_ids, domain);
+        for (app_id, point) in app_ids.iter().zip(&points) {
+            assert_eq!(*point, hash_app_id_with_pk_in_domain(&pk, app_id, domain));
+        }
+        assert!(hash_app_id_with_pk_many(&pk, &[]).is_empty());
+    }
+
     #[test]
     fn test_ckd() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -242,12 +473,13 @@ mod test {
         // Manually compute master verification
         let pk = VerifyingKey::new(G2Projective::generator() * msk);
 
-        let mut protocols: GenProtocol<CKDOutputOption> = Vec::with_capacity(participants.len());
+        let mut protocols: GenProtocol<CKDRoleOutput> = Vec::with_capacity(participants.len());
         for (i, p) in participants.iter().enumerate() {
             let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
             let key_pair = KeygenOutput {
                 public_key: pk,
                 private_share: private_shares[i],
+                epoch: 0,
             };
 
             let protocol = ckd(
@@ -257,6 +489,7 @@ mod test {
                 key_pair,
                 app_id.clone(),
                 app_pk,
+                CkdParams::default(),
                 rng_p,
             )
             .unwrap();
@@ -268,9 +501,10 @@ mod test {
 
         // test one single some for the coordinator
         let ckd_output = check_one_coordinator_output(result, coordinator).unwrap();
+        assert_serde_roundtrip(&ckd_output);
 
         // compute msk . H(pk, app_id)
-        let confidential_key = ckd_output.unmask(app_sk);
+        let confidential_key = ckd_output.unmask(&app_id, app_sk).unwrap();
 
         // H(pk || app_id) * msk
         let expected_confidential_key = hash_app_id_with_pk(&pk, &app_id) * msk;
@@ -280,5 +514,405 @@ mod test {
             "Keys should be equal"
         );
         insta::assert_json_snapshot!(ckd_output);
+
+        // the output cannot be unmasked on behalf of another request
+        let other_app_id = AppId::try_from(b"Other App").unwrap();
+        assert!(ckd_output.unmask(&other_app_id, app_sk).is_err());
+        assert!(ckd_output
+            .unmask(&app_id, Scalar::random(&mut rng))
+            .is_err());
+    }
+
+    #[test]
+    fn test_ckd_output_bytes() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let mut random_point = || ElementG1::generator() * Scalar::random(&mut rng);
+        let output = CKDOutput::new(
+            random_point(),
+            random_point(),
+            AppId::try_from(b"Near App").unwrap(),
+            random_point(),
+        );
+
+        let bytes = output.to_bytes();
+        assert_eq!(bytes.len(), 3 * 48 + b"Near App".len());
+        let decoded = CKDOutput::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.big_y(), output.big_y());
+        assert_eq!(decoded.big_c(), output.big_c());
+        assert_eq!(decoded.app_pk(), output.app_pk());
+        assert_eq!(decoded.app_id(), output.app_id());
+
+        assert!(matches!(
+            CKDOutput::from_bytes(&bytes[..3 * 48 - 1]),
+            Err(ProtocolError::DeserializationError(_))
+        ));
+
+        // find a point on the curve outside of the prime order subgroup
+        let small_subgroup_point = (0u8..=u8::MAX)
+            .map(|x| {
+                let mut encoded = [0u8; 48];
+                // compression flag
+                encoded[0] = 0x80;
+                encoded[47] = x;
+                encoded
+            })
+            .find(|encoded| {
+                bool::from(G1Affine::from_compressed_unchecked(encoded).is_some())
+                    && bool::from(G1Affine::from_compressed(encoded).is_none())
+            })
+            .unwrap();
+        let mut bytes = bytes;
+        bytes[48..96].copy_from_slice(&small_subgroup_point);
+        assert_eq!(
+            CKDOutput::from_bytes(&bytes).err(),
+            Some(ProtocolError::MalformedElement)
+        );
+    }
+
+    /// Runs ckd with fixed keys, drawing the randomness of each participant from `seed`
+    fn run_ckd_with_seed(seed: u64, app_id: &AppId, app_sk: Scalar) -> CKDOutput {
+        let mut key_rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let private_shares = participants
+            .iter()
+            .map(|_| SigningShare::new(Scalar::random(&mut key_rng)))
+            .collect::<Vec<_>>();
+        let msk = participants
+            .iter()
+            .zip(&private_shares)
+            .map(|(p, share)| {
+                participant_list.lagrange::<BLS12381SHA256>(*p).unwrap() * share.to_scalar()
+            })
+            .sum::<Scalar>();
+        let pk = VerifyingKey::new(G2Projective::generator() * msk);
+
+        let mut rng = MockCryptoRng::seed_from_u64(seed);
+        let mut protocols: GenProtocol<CKDRoleOutput> = Vec::with_capacity(participants.len());
+        for (p, private_share) in participants.iter().zip(private_shares) {
+            let key_pair = KeygenOutput {
+                public_key: pk,
+                private_share,
+                epoch: 0,
+            };
+            let protocol = ckd(
+                &participants,
+                participants[0],
+                *p,
+                key_pair,
+                app_id.clone(),
+                ElementG1::generator() * app_sk,
+                CkdParams::default(),
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        check_one_coordinator_output(run_protocol(protocols).unwrap(), participants[0]).unwrap()
+    }
+
+    #[test]
+    fn test_ckd_randomness_comes_from_rng() {
+        let app_id = AppId::try_from(b"Near App").unwrap();
+        let app_sk = Scalar::from(7u64);
+
+        // the injected rng is the only source of entropy, including for the blinding y
+        let output = run_ckd_with_seed(1, &app_id, app_sk);
+        let same_seed = run_ckd_with_seed(1, &app_id, app_sk);
+        assert_eq!(output.big_y(), same_seed.big_y());
+        assert_eq!(output.big_c(), same_seed.big_c());
+
+        // another seed blinds the key differently
+        let other_seed = run_ckd_with_seed(2, &app_id, app_sk);
+        assert_ne!(output.big_y(), other_seed.big_y());
+        assert_ne!(output.big_c(), other_seed.big_c());
+        assert_eq!(
+            output.unmask(&app_id, app_sk).unwrap(),
+            other_seed.unmask(&app_id, app_sk).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ckd_non_coordinators_return_none() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let app_id = AppId::try_from(b"Near App").unwrap();
+        let app_pk = ElementG1::generator() * Scalar::random(&mut rng);
+        let pk = VerifyingKey::new(G2Projective::generator() * Scalar::random(&mut rng));
+        let participants = generate_participants(4);
+
+        // the coordinator may be poked before or after the participants returned
+        for coordinator in [participants[0], participants[3]] {
+            let mut protocols: GenProtocol<CKDRoleOutput> = Vec::new();
+            for p in &participants {
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let key_pair = KeygenOutput {
+                    public_key: pk,
+                    private_share: SigningShare::new(Scalar::random(&mut rng)),
+                    epoch: 0,
+                };
+                let protocol = ckd(
+                    &participants,
+                    coordinator,
+                    *p,
+                    key_pair,
+                    app_id.clone(),
+                    app_pk,
+                    CkdParams::default(),
+                    rng_p,
+                )
+                .unwrap();
+                protocols.push((*p, Box::new(protocol)));
+            }
+
+            let result = run_protocol(protocols).unwrap();
+            assert_eq!(result.len(), participants.len());
+            for (p, output) in &result {
+                assert_eq!(output.is_coordinator(), *p == coordinator);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ckd_coordinator_rejects_identity_contribution() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let app_id = AppId::try_from(b"Near App").unwrap();
+        let app_pk = ElementG1::generator() * Scalar::random(&mut rng);
+        let pk = VerifyingKey::new(G2Projective::generator() * Scalar::random(&mut rng));
+        let participants = generate_participants(3);
+        let coordinator = participants[0];
+        let malicious = participants[2];
+
+        let mut protocols: GenProtocol<CKDRoleOutput> = Vec::new();
+        for p in &participants {
+            if *p == malicious {
+                // sends the identity in place of its share
+                let comms = Comms::new();
+                let mut chan = comms.shared_channel();
+                let fut = async move {
+                    let waitpoint = chan.next_waitpoint();
+                    let identity = (ElementG1::identity(), ElementG1::identity());
+                    chan.send_private(waitpoint, coordinator, &identity)?;
+                    Ok(RoleOutput::Participant)
+                };
+                protocols.push((*p, Box::new(make_protocol(comms, fut))));
+                continue;
+            }
+            let key_pair = KeygenOutput {
+                public_key: pk,
+                private_share: SigningShare::new(Scalar::random(&mut rng)),
+                epoch: 0,
+            };
+            let protocol = ckd(
+                &participants,
+                coordinator,
+                *p,
+                key_pair,
+                app_id.clone(),
+                app_pk,
+                CkdParams::default(),
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let result = run_protocol(protocols);
+        assert_eq!(result.err().unwrap(), ProtocolError::IdentityElement);
+    }
+
+    #[test]
+    fn test_check_contribution() {
+        let from = Participant::from(1u32);
+        assert!(check_contribution(from, &ElementG1::generator()).is_ok());
+        assert_eq!(
+            check_contribution(from, &ElementG1::identity()),
+            Err(ProtocolError::IdentityElement)
+        );
+    }
+
+    #[test]
+    fn test_ckd_two_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let app_id = AppId::try_from(b"Near App").unwrap();
+        let app_sk = Scalar::random(&mut rng);
+        let app_pk = ElementG1::generator() * app_sk;
+
+        let participants = generate_participants(2);
+        let (participant, coordinator) = (participants[0], participants[1]);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let private_shares = participants
+            .iter()
+            .map(|_| SigningShare::new(Scalar::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let msk = participants
+            .iter()
+            .zip(&private_shares)
+            .map(|(p, share)| {
+                participant_list.lagrange::<BLS12381SHA256>(*p).unwrap() * share.to_scalar()
+            })
+            .sum::<Scalar>();
+        let pk = VerifyingKey::new(G2Projective::generator() * msk);
+
+        let mut protocols = participants
+            .iter()
+            .zip(private_shares)
+            .map(|(p, private_share)| {
+                let key_pair = KeygenOutput {
+                    public_key: pk,
+                    private_share,
+                    epoch: 0,
+                };
+                ckd(
+                    &participants,
+                    coordinator,
+                    *p,
+                    key_pair,
+                    app_id.clone(),
+                    app_pk,
+                    CkdParams::default(),
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut coordinator_protocol = protocols.pop().unwrap();
+        let mut participant_protocol = protocols.pop().unwrap();
+
+        // the coordinator waits for the contribution of the only other participant
+        assert!(matches!(coordinator_protocol.poke().unwrap(), Action::Wait));
+        let Action::SendPrivate(to, message) = participant_protocol.poke().unwrap() else {
+            panic!("the participant should send its contribution");
+        };
+        assert_eq!(to, coordinator);
+        assert!(matches!(
+            participant_protocol.poke().unwrap(),
+            Action::Return(RoleOutput::Participant)
+        ));
+        coordinator_protocol.message(participant, message);
+        let Action::Return(RoleOutput::Coordinator(ckd_output)) =
+            coordinator_protocol.poke().unwrap()
+        else {
+            panic!("the coordinator should return once it got the contribution");
+        };
+        assert_eq!(
+            ckd_output.unmask(&app_id, app_sk).unwrap(),
+            hash_app_id_with_pk(&pk, &app_id) * msk
+        );
+
+        // the coordinator cannot be the only participant
+        let key_pair = KeygenOutput {
+            public_key: pk,
+            private_share: SigningShare::new(Scalar::random(&mut rng)),
+            epoch: 0,
+        };
+        let result = ckd(
+            &[coordinator],
+            coordinator,
+            coordinator,
+            key_pair,
+            app_id,
+            app_pk,
+            CkdParams::default(),
+            rng,
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::NotEnoughParticipants { participants: 1 }
+        );
+    }
+
+    #[test]
+    fn test_ckd_many_with_two_coordinators() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let private_shares = participants
+            .iter()
+            .map(|_| SigningShare::new(Scalar::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let msk = participants
+            .iter()
+            .zip(&private_shares)
+            .map(|(p, share)| {
+                participant_list.lagrange::<BLS12381SHA256>(*p).unwrap() * share.to_scalar()
+            })
+            .sum::<Scalar>();
+        let pk = VerifyingKey::new(G2Projective::generator() * msk);
+
+        // the first two participants coordinate every other app
+        let mut app_sks = Vec::new();
+        let mut requests = Vec::new();
+        for i in 0..4 {
+            let app_sk = Scalar::random(&mut rng);
+            requests.push(CkdRequest {
+                app_id: AppId::try_new(format!("Near App {i}")).unwrap(),
+                app_pk: ElementG1::generator() * app_sk,
+                coordinator: participants[i % 2],
+            });
+            app_sks.push(app_sk);
+        }
+
+        let mut protocols: GenProtocol<Vec<CKDRoleOutput>> = Vec::with_capacity(participants.len());
+        for (p, private_share) in participants.iter().zip(private_shares) {
+            let key_pair = KeygenOutput {
+                public_key: pk,
+                private_share,
+                epoch: 0,
+            };
+            let protocol = ckd_many(
+                &participants,
+                *p,
+                key_pair,
+                requests.clone(),
+                CkdParams::default(),
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        for (p, outputs) in run_protocol(protocols).unwrap() {
+            assert_eq!(outputs.len(), requests.len());
+            for ((request, app_sk), output) in requests.iter().zip(&app_sks).zip(outputs) {
+                if request.coordinator != p {
+                    assert_eq!(output, RoleOutput::Participant);
+                    continue;
+                }
+                let confidential_key = output
+                    .into_coordinator_output()
+                    .unwrap()
+                    .unmask(&request.app_id, *app_sk)
+                    .unwrap();
+                assert_eq!(
+                    confidential_key,
+                    hash_app_id_with_pk(&pk, &request.app_id) * msk
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ckd_empty_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let me = Participant::from(0u32);
+        let key_pair = KeygenOutput {
+            public_key: VerifyingKey::new(G2Projective::generator() * Scalar::random(&mut rng)),
+            private_share: SigningShare::new(Scalar::random(&mut rng)),
+            epoch: 0,
+        };
+        let result = ckd(
+            &[],
+            me,
+            me,
+            key_pair,
+            AppId::try_from(b"Near App").unwrap(),
+            ElementG1::generator() * Scalar::random(&mut rng),
+            CkdParams::default(),
+            rng,
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
     }
 }
@@ -2,10 +2,12 @@ use core::ptr;
 use digest::consts::U48;
 use digest::generic_array::GenericArray;
 use elliptic_curve::hash2curve::FromOkm;
-use elliptic_curve::Field;
 use std::sync::atomic;
 use zeroize::Zeroize;
 
+use super::ciphersuite::BLS12381SHA256;
+use crate::Ciphersuite;
+
 #[derive(Default, Clone, Debug)]
 pub struct ScalarWrapper(pub(crate) blstrs::Scalar);
 
@@ -24,46 +26,27 @@ impl Zeroize for ScalarWrapper {
     }
 }
 
-impl ScalarWrapper {
-    // Based on https://github.com/arkworks-rs/algebra/blob/c6f9284c17df00c50d954a5fe1c72dd4a5698103/ff/src/fields/prime.rs#L72
-    // Converts `bytes` into a `Scalar` by interpreting the input as
-    // an integer in big-endian and then converting the result to Scalar
-    // which implicitly does modular reduction
-    fn from_be_bytes_mod_order(bytes: &[u8]) -> Self {
-        let mut res = blstrs::Scalar::ZERO;
-
-        let mut count = 0;
-        let mut remainder = 0;
-        for byte in bytes {
-            remainder = (remainder << 8) + u64::from(*byte);
-            count += 1;
-            if count == 8 {
-                res = res.shl(64) + blstrs::Scalar::from(remainder);
-                remainder = 0;
-                count = 0;
-            }
-        }
-        if count > 0 {
-            res = res * res.shl(count * 8) + blstrs::Scalar::from(remainder);
-        }
-        Self(res)
-    }
-}
-
 // Follows https://github.com/zkcrypto/bls12_381/blob/6bb96951d5c2035caf4989b6e4a018435379590f/src/hash_to_curve/map_scalar.rs
 impl FromOkm for ScalarWrapper {
     // ceil(log2(p)) = 255, m = 1, k = 128.
     type Length = U48;
 
     fn from_okm(okm: &GenericArray<u8, Self::Length>) -> Self {
-        Self::from_be_bytes_mod_order(okm)
+        // the output is read as a big endian integer, unlike the scalar serialization
+        let mut bytes = [0u8; 64];
+        bytes
+            .iter_mut()
+            .zip(okm.iter().rev())
+            .for_each(|(b, o)| *b = *o);
+        Self(BLS12381SHA256::scalar_from_uniform_bytes(&bytes))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        confidential_key_derivation::scalar_wrapper::ScalarWrapper, test_utils::MockCryptoRng,
+        confidential_key_derivation::ciphersuite::BLS12381SHA256, test_utils::MockCryptoRng,
+        Ciphersuite,
     };
     use rand::Rng as _;
     use rand_core::{RngCore, SeedableRng};
@@ -71,15 +54,15 @@ mod tests {
     #[test]
     // This test only makes sense if `overflow-checks` are enabled
     // This is guaranteed by the `test_verify_overflow_failure` below
-    fn test_stress_test_scalar_from_le_bytes_mod_order() {
-        // empty case
-        ScalarWrapper::from_be_bytes_mod_order(&[]);
+    fn test_stress_test_scalar_from_uniform_bytes() {
+        // extreme cases
+        BLS12381SHA256::scalar_from_uniform_bytes(&[0; 64]);
+        BLS12381SHA256::scalar_from_uniform_bytes(&[0xff; 64]);
         let mut rng = MockCryptoRng::seed_from_u64(42);
         for _ in 0..1000 {
-            let len = rng.gen_range(1..10000);
-            let mut bytes = vec![0; len];
+            let mut bytes = [0; 64];
             rng.fill_bytes(&mut bytes);
-            ScalarWrapper::from_be_bytes_mod_order(&bytes);
+            BLS12381SHA256::scalar_from_uniform_bytes(&bytes);
         }
     }
 
@@ -1,5 +1,7 @@
 // Generic Ciphersuite Trait
 
+use crate::Scalar;
+
 pub enum BytesOrder {
     BigEndian,
     LittleEndian,
@@ -8,4 +10,153 @@ pub enum BytesOrder {
 pub trait ScalarSerializationFormat {
     fn bytes_order() -> BytesOrder;
 }
-pub trait Ciphersuite: frost_core::Ciphersuite + ScalarSerializationFormat {}
+
+/// The ciphersuites supported by the generic protocols.
+///
+/// Received points are decoded with [`Group::deserialize`], which must reject points
+/// outside of the prime order subgroup, as the protocols never clear cofactors:
+/// * secp256k1 has a cofactor of one, every point on the curve is in the group;
+/// * Ed25519 and Jubjub, of cofactor 8, are decoded by `frost-ed25519` and `reddsa`,
+///   which reject points with a torsion component;
+/// * the BLS12-381 groups are decoded by `blstrs`, which checks subgroup membership.
+pub trait Ciphersuite: frost_core::Ciphersuite + ScalarSerializationFormat {
+    /// Maps 64 uniformly random bytes to a scalar by reducing their integer value,
+    /// read in the order given by [`ScalarSerializationFormat`], modulo the group order.
+    ///
+    /// 64 bytes exceed every supported group order by more than 128 bits, so the result
+    /// is close to uniform. The bytes are usually secret: implementations must use a
+    /// constant time wide reduction.
+    fn scalar_from_uniform_bytes(bytes: &[u8; 64]) -> Scalar<Self>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BytesOrder, Ciphersuite};
+    use crate::confidential_key_derivation::ciphersuite::BLS12381SHA256;
+    use crate::test_utils::MockCryptoRng;
+    use crate::Scalar;
+
+    use frost_core::{Field, Group};
+    use frost_ed25519::Ed25519Sha512;
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::{RngCore, SeedableRng};
+    use reddsa::frost::redjubjub::JubjubBlake2b512;
+    use sha2::{Digest, Sha512};
+
+    fn serialize<C: Ciphersuite>(scalar: &Scalar<C>) -> Vec<u8> {
+        <<C::Group as Group>::Field>::little_endian_serialize(scalar)
+            .as_ref()
+            .to_vec()
+    }
+
+    /// Checks the low bits of the outputs against a uniform distribution
+    /// with a chi-squared test over 16 buckets
+    fn check_uniformity<C: Ciphersuite>() {
+        const SAMPLES: u32 = 16_000;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let mut buckets = [0u32; 16];
+        for _ in 0..SAMPLES {
+            let mut bytes = [0u8; 64];
+            rng.fill_bytes(&mut bytes);
+            let scalar = C::scalar_from_uniform_bytes(&bytes);
+            buckets[usize::from(serialize::<C>(&scalar)[0] & 0xf)] += 1;
+        }
+        let expected = f64::from(SAMPLES) / 16.0;
+        let chi_squared: f64 = buckets
+            .iter()
+            .map(|count| (f64::from(*count) - expected).powi(2) / expected)
+            .sum();
+        // critical value for 15 degrees of freedom at p = 0.001
+        assert!(chi_squared < 37.7, "chi squared too large: {chi_squared}");
+    }
+
+    /// Reduces `bytes` bit by bit with field operations, as a slow reference
+    fn reference_reduction<C: Ciphersuite>(bytes: &[u8; 64]) -> Scalar<C> {
+        let zero = <<C::Group as Group>::Field>::zero();
+        let one = <<C::Group as Group>::Field>::one();
+        let push_byte = |acc: Scalar<C>, byte: &u8| {
+            (0..8).rev().fold(acc, |acc, bit| {
+                let acc = acc + acc;
+                if (byte >> bit) & 1 == 1 {
+                    acc + one
+                } else {
+                    acc
+                }
+            })
+        };
+        match C::bytes_order() {
+            BytesOrder::BigEndian => bytes.iter().fold(zero, push_byte),
+            BytesOrder::LittleEndian => bytes.iter().rev().fold(zero, push_byte),
+        }
+    }
+
+    fn check_reference<C: Ciphersuite>() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let mut inputs = vec![[0xffu8; 64]];
+        for _ in 0..100 {
+            let mut bytes = [0u8; 64];
+            rng.fill_bytes(&mut bytes);
+            inputs.push(bytes);
+        }
+        for bytes in inputs {
+            assert_eq!(
+                C::scalar_from_uniform_bytes(&bytes),
+                reference_reduction::<C>(&bytes)
+            );
+        }
+    }
+
+    fn check_small_values<C: Ciphersuite>() {
+        let zero = <<C::Group as Group>::Field>::zero();
+        let one = <<C::Group as Group>::Field>::one();
+        assert_eq!(C::scalar_from_uniform_bytes(&[0u8; 64]), zero);
+
+        // 2^64 + 5, spanning two limbs
+        let mut bytes = [0u8; 64];
+        bytes[8] = 1;
+        bytes[0] = 5;
+        if matches!(C::bytes_order(), BytesOrder::BigEndian) {
+            bytes.reverse();
+        }
+        let two = one + one;
+        let two_pow_64 = (0..64).fold(one, |acc, _| acc * two);
+        let five = (0..5).fold(zero, |acc, _| acc + one);
+        assert_eq!(C::scalar_from_uniform_bytes(&bytes), two_pow_64 + five);
+    }
+
+    #[test]
+    fn test_scalar_from_uniform_bytes_secp256k1() {
+        check_small_values::<Secp256K1Sha256>();
+        check_uniformity::<Secp256K1Sha256>();
+        check_reference::<Secp256K1Sha256>();
+    }
+
+    #[test]
+    fn test_scalar_from_uniform_bytes_ed25519() {
+        check_small_values::<Ed25519Sha512>();
+        check_uniformity::<Ed25519Sha512>();
+        check_reference::<Ed25519Sha512>();
+
+        // H2 of RFC 9591 reduces the little endian SHA-512 digest
+        for msg in [&b""[..], &b"Hello from Near"[..], &[0xffu8; 200][..]] {
+            assert_eq!(
+                Ed25519Sha512::scalar_from_uniform_bytes(&Sha512::digest(msg).into()),
+                <Ed25519Sha512 as frost_core::Ciphersuite>::H2(msg)
+            );
+        }
+    }
+
+    #[test]
+    fn test_scalar_from_uniform_bytes_bls12381() {
+        check_small_values::<BLS12381SHA256>();
+        check_uniformity::<BLS12381SHA256>();
+        check_reference::<BLS12381SHA256>();
+    }
+
+    #[test]
+    fn test_scalar_from_uniform_bytes_jubjub() {
+        check_small_values::<JubjubBlake2b512>();
+        check_uniformity::<JubjubBlake2b512>();
+        check_reference::<JubjubBlake2b512>();
+    }
+}
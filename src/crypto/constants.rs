@@ -11,6 +11,11 @@ pub const COMMIT_LEN: usize = 32;
 /// Commitment scheme domain separator.
 pub const START_LABEL: &[u8] = b"start data";
 
+// Polynomial Constants
+/// Maximum degree accepted when deserializing a polynomial commitment.
+/// Bounds the allocation a peer can force through a single message.
+pub const MAX_COMMITMENT_DEGREE: usize = 4096;
+
 // Hashing Constants
 /// Generic hash domain separator.
 pub const NEAR_HASH_LABEL: &[u8] = b"Near threshold signature generic hash";
@@ -21,6 +26,12 @@ pub const HASH_LEN: usize = 32;
 /// Length of the randomizer for commitments.
 pub const RANDOMIZER_LEN: usize = 32;
 
+// Committee Commitment Constants
+/// Domain separator of the leaves of a committee commitment.
+pub const NEAR_COMMITTEE_LEAF_LABEL: &[u8] = b"Near committee commitment leaf";
+/// Domain separator of the inner nodes of a committee commitment.
+pub const NEAR_COMMITTEE_NODE_LABEL: &[u8] = b"Near committee commitment node";
+
 // Confidential Key Derivation Constants
 /// Confidential key derivation domain separator.
 pub const NEAR_CKD_DOMAIN: &[u8] = b"NEAR BLS12381G1_XMD:SHA-256_SSWU_RO_";
@@ -92,3 +103,8 @@ pub const NEAR_RANDOM_OT_EXTENSION_HASH_CTX: &[u8] = b"Random OT Extension Hash"
 // Channel Tags Constants
 /// Channel tags domain separator.
 pub const NEAR_CHANNEL_TAGS_DOMAIN: &[u8] = b"Near threshold signatures channel tags";
+
+// Rerandomization Constants
+/// Domain separator of the rerandomization entropy derived by signers.
+pub const NEAR_RERANDOMIZATION_ENTROPY_DOMAIN: &[u8] =
+    b"NEAR THRESHOLD SIGNATURES ECDSA RERANDOMIZATION ENTROPY";
@@ -5,3 +5,4 @@ pub mod hash;
 pub mod polynomials;
 pub mod proofs;
 pub mod random;
+pub(crate) mod secret;
@@ -4,13 +4,19 @@ use frost_core::{
 use rand_core::CryptoRngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-use super::ciphersuite::Ciphersuite;
+use super::{ciphersuite::Ciphersuite, constants::MAX_COMMITMENT_DEGREE};
 use crate::{errors::ProtocolError, participants::Participant};
 
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
 
 /// Polynomial structure of non-empty or non-zero coefficients
 /// Represents a polynomial with coefficients in the scalar field of the curve.
+#[derive(Clone)]
 pub struct Polynomial<C: Ciphersuite> {
     /// The coefficients of our polynomial,
     /// The 0 term being the constant term of the polynomial
@@ -53,6 +59,21 @@ impl<C: Ciphersuite> Polynomial<C> {
         self.coefficients.clone()
     }
 
+    /// Returns the coefficient of degree `i`, if any, without cloning the others
+    pub fn coefficient(&self, i: usize) -> Option<&Scalar<C>> {
+        self.coefficients.get(i)
+    }
+
+    /// Outputs the degree of the polynomial
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// Checks whether the polynomial is reduced to its constant term
+    pub fn is_constant(&self) -> bool {
+        self.coefficients.len() == 1
+    }
+
     /// Creates a random polynomial p of the given degree
     /// and sets p(0) = secret
     /// if the secret is not given then it is picked at random
@@ -162,6 +183,28 @@ impl<C: Ciphersuite> Polynomial<C> {
         Ok(SerializableScalar(interpolation))
     }
 
+    /// Computes polynomial interpolation in a single pass over the
+    /// `(identifier, share)` pairs using precomputed Lagrange coefficients.
+    /// Input requirements:
+    ///     * every identifier in the context appears exactly once in `shares`
+    /// Returns error if an identifier is unknown to the context, repeated or missing.
+    pub fn eval_interpolation_iter(
+        context: &LagrangeContext<C>,
+        shares: impl Iterator<Item = (Scalar<C>, SerializableScalar<C>)>,
+    ) -> Result<SerializableScalar<C>, ProtocolError> {
+        let mut interpolation = <C::Group as Group>::Field::zero();
+        let mut seen = vec![false; context.len()];
+        for (identifier, share) in shares {
+            let (index, lagrange_coefficient) = context.position_and_coefficient(&identifier)?;
+            mark_seen(&mut seen, index)?;
+            interpolation = interpolation + (lagrange_coefficient * share.0);
+        }
+        if seen.iter().any(|s| !s) {
+            return Err(ProtocolError::InvalidInterpolationArguments);
+        }
+        Ok(SerializableScalar(interpolation))
+    }
+
     /// Commits to a polynomial returning a sequence of group coefficients
     /// Creates a commitment vector of coefficients * G
     pub fn commit_polynomial(&self) -> Result<PolynomialCommitment<C>, ProtocolError> {
@@ -340,6 +383,28 @@ impl<C: Ciphersuite> PolynomialCommitment<C> {
         Ok(CoefficientCommitment::new(interpolation))
     }
 
+    /// Computes polynomial interpolation on the exponent in a single pass over the
+    /// `(identifier, share)` pairs using precomputed Lagrange coefficients.
+    /// Input requirements:
+    ///     * every identifier in the context appears exactly once in `shares`
+    /// Returns error if an identifier is unknown to the context, repeated or missing.
+    pub fn eval_exponent_interpolation_iter(
+        context: &LagrangeContext<C>,
+        shares: impl Iterator<Item = (Scalar<C>, CoefficientCommitment<C>)>,
+    ) -> Result<CoefficientCommitment<C>, ProtocolError> {
+        let mut interpolation = C::Group::identity();
+        let mut seen = vec![false; context.len()];
+        for (identifier, share) in shares {
+            let (index, lagrange_coefficient) = context.position_and_coefficient(&identifier)?;
+            mark_seen(&mut seen, index)?;
+            interpolation = interpolation + (share.value() * lagrange_coefficient);
+        }
+        if seen.iter().any(|s| !s) {
+            return Err(ProtocolError::InvalidInterpolationArguments);
+        }
+        Ok(CoefficientCommitment::new(interpolation))
+    }
+
     /// Extends the Commited Polynomial with an extra value as a constant
     /// Used usually after sending a smaller polynomial to prevent serialization from
     /// failing if the constant term is the identity
@@ -378,18 +443,71 @@ impl<C: Ciphersuite> Serialize for PolynomialCommitment<C> {
     }
 }
 
-// Deserialization enforcing non-empty vecs and non all-identity PolynomialCommitments
+/// Visitor reading at most `MAX_COMMITMENT_DEGREE + 1` coefficients
+/// so that a malicious peer cannot force arbitrarily large allocations
+struct PolynomialCommitmentVisitor<C: Ciphersuite>(PhantomData<C>);
+
+impl<'de, C: Ciphersuite> Visitor<'de> for PolynomialCommitmentVisitor<C> {
+    type Value = Vec<CoefficientCommitment<C>>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "a sequence of at most {} coefficient commitments",
+            MAX_COMMITMENT_DEGREE + 1
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let max_len = MAX_COMMITMENT_DEGREE + 1;
+        // never trust the size hint beyond the maximum allowed length
+        let capacity = seq.size_hint().unwrap_or(0).min(max_len);
+        let mut coefficients = Vec::with_capacity(capacity);
+        while let Some(coefficient) = seq.next_element::<CoefficientCommitment<C>>()? {
+            if coefficients.len() >= max_len {
+                return Err(serde::de::Error::custom(format!(
+                    "PolynomialCommitment degree exceeds the maximum allowed degree ({MAX_COMMITMENT_DEGREE})"
+                )));
+            }
+            coefficients.push(coefficient);
+        }
+        Ok(coefficients)
+    }
+}
+
+// Deserialization enforcing non-empty vecs, bounded degree
+// and non all-identity PolynomialCommitments
 impl<'de, C: Ciphersuite> Deserialize<'de> for PolynomialCommitment<C> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let coefficients = Vec::<CoefficientCommitment<C>>::deserialize(deserializer)?;
+        let coefficients =
+            deserializer.deserialize_seq(PolynomialCommitmentVisitor::<C>(PhantomData))?;
         Self::new(&coefficients)
             .map_err(|err| serde::de::Error::custom(format!("ProtocolError: {err}")))
     }
 }
 
+/// Returns [`ProtocolError::DuplicateInterpolationPoint`] with the index of the
+/// first repetition if `points_set` contains the same point twice.
+fn assert_distinct_points<C: Ciphersuite>(points_set: &[Scalar<C>]) -> Result<(), ProtocolError> {
+    let mut seen = HashSet::with_capacity(points_set.len());
+    for (index, point) in points_set.iter().enumerate() {
+        if !seen.insert(
+            <C::Group as Group>::Field::serialize(point)
+                .as_ref()
+                .to_vec(),
+        ) {
+            return Err(ProtocolError::DuplicateInterpolationPoint(index));
+        }
+    }
+    Ok(())
+}
+
 /// Computes the Lagrange coefficient (a.k.a. Lagrange basis polynomial)
 /// evaluated at point x.
 /// `lambda_i(x)` = `\prod_j` (x - `x_j`)/(`x_i` - `x_j`)  where j != i
@@ -397,8 +515,8 @@ impl<'de, C: Ciphersuite> Deserialize<'de> for PolynomialCommitment<C> {
 /// Note: if `x` is None then consider it as 0.
 /// Note: `x_j` are elements in `point_set`
 /// Note: if `x_i` is not in `point_set` then return an error
-/// Warning: For correctness, this function assumes `point_set` does not have repeated values
-/// We dont actually enforce this for performance reasons
+/// Note: if `point_set` has repeated values then return
+/// [`ProtocolError::DuplicateInterpolationPoint`] with the index of the first repetition
 pub fn compute_lagrange_coefficient<C: Ciphersuite>(
     points_set: &[Scalar<C>],
     x_i: &Scalar<C>,
@@ -412,6 +530,8 @@ pub fn compute_lagrange_coefficient<C: Ciphersuite>(
         return Err(ProtocolError::InvalidInterpolationArguments);
     }
 
+    assert_distinct_points::<C>(points_set)?;
+
     let mut contains_i = false;
     if let Some(x) = x {
         for x_j in points_set {
@@ -456,7 +576,8 @@ pub fn compute_lagrange_coefficient<C: Ciphersuite>(
 ///
 /// Requirements:
 /// - `points_set.len() > 1`.
-/// - All `x_i` are distinct.
+/// - All `x_i` are distinct, otherwise [`ProtocolError::DuplicateInterpolationPoint`]
+///   is returned with the index of the first repetition.
 ///
 /// Early exit:
 /// - If x equals some `x_k` in `points_set`, return the Kronecker delta vector:
@@ -491,6 +612,7 @@ where
     if n <= 1 {
         return Err(ProtocolError::InvalidInterpolationArguments);
     }
+    assert_distinct_points::<C>(points_set)?;
 
     // Treat None as zero
     let zero = <C::Group as Group>::Field::zero();
@@ -582,6 +704,86 @@ where
     Ok(lagrange_coeffs)
 }
 
+/// Lagrange coefficients precomputed for a fixed set of identifiers
+/// evaluated at a fixed point.
+///
+/// Allows interpolating shares as they arrive (e.g. directly from a receive loop)
+/// without first collecting them into identifier and share vectors.
+pub struct LagrangeContext<C: Ciphersuite> {
+    /// The position of every identifier, keyed by its serialization
+    positions: HashMap<Vec<u8>, usize>,
+    coefficients: Vec<SerializableScalar<C>>,
+}
+
+impl<C: Ciphersuite> LagrangeContext<C> {
+    /// Precomputes the Lagrange coefficients of `identifiers` evaluated at `point`
+    /// (0 if `point` is None) using [`batch_compute_lagrange_coefficients`].
+    /// Identifiers MUST be pairwise distinct and of length greater than 1
+    pub fn new(identifiers: &[Scalar<C>], point: Option<&Scalar<C>>) -> Result<Self, ProtocolError>
+    where
+        Scalar<C>: ConstantTimeEq,
+    {
+        let coefficients = batch_compute_lagrange_coefficients::<C>(identifiers, point)?;
+        let mut positions = HashMap::with_capacity(identifiers.len());
+        for (index, identifier) in identifiers.iter().enumerate() {
+            let key = <C::Group as Group>::Field::serialize(identifier)
+                .as_ref()
+                .to_vec();
+            if positions.insert(key, index).is_some() {
+                return Err(ProtocolError::InvalidInterpolationArguments);
+            }
+        }
+        Ok(Self {
+            positions,
+            coefficients,
+        })
+    }
+
+    /// Returns the number of identifiers in the context
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the context holds no identifier
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the precomputed Lagrange coefficient of `identifier`
+    pub fn coefficient(&self, identifier: &Scalar<C>) -> Result<Scalar<C>, ProtocolError> {
+        self.position_and_coefficient(identifier)
+            .map(|(_, coefficient)| coefficient)
+    }
+
+    fn position_and_coefficient(
+        &self,
+        identifier: &Scalar<C>,
+    ) -> Result<(usize, Scalar<C>), ProtocolError> {
+        let key = <C::Group as Group>::Field::serialize(identifier);
+        let index = *self
+            .positions
+            .get(key.as_ref())
+            .ok_or(ProtocolError::InvalidInterpolationArguments)?;
+        let coefficient = self
+            .coefficients
+            .get(index)
+            .ok_or(ProtocolError::InvalidInterpolationArguments)?;
+        Ok((index, coefficient.0))
+    }
+}
+
+/// Marks the identifier at `index` as consumed, failing on repetitions
+fn mark_seen(seen: &mut [bool], index: usize) -> Result<(), ProtocolError> {
+    let seen_i = seen
+        .get_mut(index)
+        .ok_or(ProtocolError::InvalidInterpolationArguments)?;
+    if *seen_i {
+        return Err(ProtocolError::InvalidInterpolationArguments);
+    }
+    *seen_i = true;
+    Ok(())
+}
+
 /// Batch inversion of a list of field elements.
 /// Returns a vector of inverses in the same order.
 /// Uses the standard prefix-product / suffix-product trick for O(n) inversions instead of O(n^2).
@@ -621,6 +823,30 @@ pub fn batch_invert<C: Ciphersuite>(values: &[Scalar<C>]) -> Result<Vec<Scalar<C
     Ok(inverted)
 }
 
+/// Interpolates the value at `x` of the polynomial going through `points`.
+///
+/// Each entry of `points` is a pair `(x_i, y_i)` with `y_i = f(x_i)`, so the
+/// evaluation points cannot be misaligned with their values. The `x_i` need not
+/// be participant identifiers and `x` may be any scalar, including one of the `x_i`.
+///
+/// The result is only `f(x)` if `f` has degree less than `points.len()`;
+/// otherwise it is the value at `x` of the unique such polynomial through `points`.
+///
+/// Returns [`ProtocolError::InvalidInterpolationArguments`] if there are fewer than
+/// two points, and [`ProtocolError::DuplicateInterpolationPoint`] with the index of
+/// the first repetition if two `x_i` are equal.
+pub fn interpolate_at<C: Ciphersuite>(
+    points: &[(Scalar<C>, SerializableScalar<C>)],
+    x: Scalar<C>,
+) -> Result<SerializableScalar<C>, ProtocolError>
+where
+    Scalar<C>: ConstantTimeEq,
+{
+    let (identifiers, shares): (Vec<_>, Vec<_>) = points.iter().cloned().unzip();
+    assert_distinct_points::<C>(&identifiers)?;
+    Polynomial::eval_interpolation(&identifiers, &shares, Some(&x))
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Neg;
@@ -851,6 +1077,27 @@ mod test {
         assert_eq!(coeffs[0], point);
     }
 
+    #[test]
+    fn test_degree_and_coefficient() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let degree = 10;
+        let poly = Polynomial::<C>::generate_polynomial(None, degree, &mut rng).unwrap();
+        let coeffs = poly.get_coefficients();
+        assert_eq!(poly.degree(), degree);
+        assert!(!poly.is_constant());
+        for (i, coeff) in coeffs.iter().enumerate() {
+            assert_eq!(poly.coefficient(i), Some(coeff));
+        }
+        assert_eq!(poly.coefficient(degree + 1), None);
+
+        // the null coefficients of highest degree are dropped
+        let zero = <<C as frost_core::Ciphersuite>::Group as Group>::Field::zero();
+        let constant = Polynomial::<C>::new(&[coeffs[0], zero, zero]).unwrap();
+        assert_eq!(constant.degree(), 0);
+        assert!(constant.is_constant());
+        assert_eq!(poly.commit_polynomial().unwrap().degree(), poly.degree());
+    }
+
     #[test]
     fn test_set_to_non_zero_poly() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -906,6 +1153,125 @@ mod test {
         assert!(Polynomial::eval_interpolation(&ids[..2], &shares, None).is_err());
     }
 
+    #[test]
+    fn test_interpolate_at() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let degree = 4;
+        let poly = Polynomial::<C>::generate_polynomial(None, degree, &mut rng).unwrap();
+        // arbitrary evaluation points, not derived from participants
+        let points = (0..=degree)
+            .map(|_| {
+                let x_i = Secp256K1ScalarField::random(&mut rng);
+                (x_i, poly.eval_at_point(x_i).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        let x = Secp256K1ScalarField::random(&mut rng);
+        let expected = poly.eval_at_point(x).unwrap();
+        assert_eq!(interpolate_at::<C>(&points, x).unwrap().0, expected.0);
+
+        // the order of the points does not matter
+        let mut reversed = points.clone();
+        reversed.reverse();
+        assert_eq!(interpolate_at::<C>(&reversed, x).unwrap().0, expected.0);
+
+        // interpolating at one of the points returns its value
+        let (x_0, y_0) = points[0].clone();
+        assert_eq!(interpolate_at::<C>(&points, x_0).unwrap().0, y_0.0);
+
+        // matches the slice based interpolation
+        let (ids, shares): (Vec<_>, Vec<_>) = points.iter().cloned().unzip();
+        let sliced = Polynomial::eval_interpolation(&ids, &shares, Some(&x)).unwrap();
+        assert_eq!(interpolate_at::<C>(&points, x).unwrap().0, sliced.0);
+
+        // too few points do not determine the polynomial
+        let interpolated = interpolate_at::<C>(&points[..degree], x).unwrap();
+        assert_ne!(interpolated.0, expected.0);
+
+        // not enough points
+        assert_eq!(
+            interpolate_at::<C>(&points[..1], x).unwrap_err(),
+            ProtocolError::InvalidInterpolationArguments
+        );
+        assert_eq!(
+            interpolate_at::<C>(&[], x).unwrap_err(),
+            ProtocolError::InvalidInterpolationArguments
+        );
+
+        // repeated evaluation point
+        let mut repeated = points.clone();
+        repeated.push(points[1].clone());
+        for x in [x, points[1].0] {
+            assert_eq!(
+                interpolate_at::<C>(&repeated, x).unwrap_err(),
+                ProtocolError::DuplicateInterpolationPoint(degree + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_eval_interpolation_iter() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let degree = 5;
+        let poly = Polynomial::<C>::generate_polynomial(None, degree, &mut rng).unwrap();
+        let compoly = poly.commit_polynomial().unwrap();
+        let participants = generate_participants_with_random_ids(degree + 1, &mut rng);
+        let ids = participants
+            .iter()
+            .map(Participant::scalar::<C>)
+            .collect::<Vec<_>>();
+        let shares = participants
+            .iter()
+            .map(|p| poly.eval_at_participant(*p).unwrap())
+            .collect::<Vec<_>>();
+        let com_shares = participants
+            .iter()
+            .map(|p| compoly.eval_at_participant(*p).unwrap())
+            .collect::<Vec<_>>();
+
+        let point = Secp256K1ScalarField::random(&mut rng);
+        for point in [None, Some(&point)] {
+            let context = LagrangeContext::<C>::new(&ids, point).unwrap();
+
+            // the order in which the shares arrive does not matter
+            let streamed = Polynomial::eval_interpolation_iter(
+                &context,
+                ids.iter().copied().zip(shares.iter().cloned()).rev(),
+            )
+            .unwrap();
+            let expected = Polynomial::eval_interpolation(&ids, &shares, point).unwrap();
+            assert_eq!(streamed.0, expected.0);
+
+            let streamed = PolynomialCommitment::eval_exponent_interpolation_iter(
+                &context,
+                ids.iter().copied().zip(com_shares.iter().copied()),
+            )
+            .unwrap();
+            let expected =
+                PolynomialCommitment::eval_exponent_interpolation(&ids, &com_shares, point)
+                    .unwrap();
+            assert_eq!(streamed, expected);
+        }
+
+        let context = LagrangeContext::<C>::new(&ids, None).unwrap();
+        // missing share
+        assert!(Polynomial::eval_interpolation_iter(
+            &context,
+            ids.iter().copied().zip(shares.iter().cloned()).skip(1),
+        )
+        .is_err());
+        // repeated share
+        let repeated = ids
+            .iter()
+            .copied()
+            .zip(shares.iter().cloned())
+            .chain(ids.first().copied().zip(shares.first().cloned()));
+        assert!(Polynomial::eval_interpolation_iter(&context, repeated).is_err());
+        // unknown identifier
+        let unknown = Participant::from(u32::MAX).scalar::<C>();
+        assert!(context.coefficient(&unknown).is_err());
+    }
+
     #[test]
     fn poly_eval_interpolate() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -1112,7 +1478,25 @@ mod test {
         ];
         let result =
             batch_compute_lagrange_coefficients::<C>(&points, Some(&Secp256K1ScalarField::zero()));
-        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            ProtocolError::DuplicateInterpolationPoint(1)
+        );
+
+        // the duplicate is rejected even when x hits the Kronecker early exit
+        let points = vec![
+            Participant::from(1u32).scalar::<C>(),
+            Participant::from(2u32).scalar::<C>(),
+            Participant::from(2u32).scalar::<C>(),
+        ];
+        let result = batch_compute_lagrange_coefficients::<C>(
+            &points,
+            Some(&Participant::from(1u32).scalar::<C>()),
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            ProtocolError::DuplicateInterpolationPoint(2)
+        );
 
         let points_single = vec![Participant::from(1u32).scalar::<C>()];
         let result = batch_compute_lagrange_coefficients::<C>(
@@ -1190,6 +1574,18 @@ mod test {
 
         // not enough points
         assert!(compute_lagrange_coefficient::<C>(&[one], &one, Some(&target_point)).is_err());
+
+        // repeated points, whether or not they are the point of the coefficient
+        let two = one + one;
+        assert_eq!(
+            compute_lagrange_coefficient::<C>(&[one, two, zero, two], &one, Some(&target_point))
+                .unwrap_err(),
+            ProtocolError::DuplicateInterpolationPoint(3)
+        );
+        assert_eq!(
+            compute_lagrange_coefficient::<C>(&[one, one, zero], &one, None).unwrap_err(),
+            ProtocolError::DuplicateInterpolationPoint(1)
+        );
     }
 
     #[test]
@@ -1377,4 +1773,29 @@ mod test {
         // Then
         assert_eq!(final_poly, initial_poly);
     }
+
+    #[test]
+    fn test_polynomial_commitment_deserialization_degree_limit() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_degree_poly =
+            Polynomial::<C>::generate_polynomial(None, MAX_COMMITMENT_DEGREE, &mut rng)
+                .unwrap()
+                .commit_polynomial()
+                .unwrap();
+        let poly_json = serde_json::to_string(&max_degree_poly).unwrap();
+        let final_poly: PolynomialCommitment<C> = serde_json::from_str(&poly_json).unwrap();
+        assert_eq!(final_poly, max_degree_poly);
+
+        let too_large_poly =
+            Polynomial::<C>::generate_polynomial(None, MAX_COMMITMENT_DEGREE + 1, &mut rng)
+                .unwrap()
+                .commit_polynomial()
+                .unwrap();
+        let poly_json = serde_json::to_string(&too_large_poly).unwrap();
+        let result: Result<PolynomialCommitment<C>, _> = serde_json::from_str(&poly_json);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the maximum allowed degree"));
+    }
 }
@@ -0,0 +1,64 @@
+//! Wrappers wiping secrets from memory once they are dropped.
+//!
+//! The `no-zeroize` feature turns the wiping into a no-op, so that the secrets of a
+//! failing multiparty run can still be inspected in a debugger. It is insecure and
+//! only builds with debug assertions, i.e. never in a release build.
+//!
+//! The feature only changes what dropping does: the public types implement
+//! [`zeroize::ZeroizeOnDrop`] either way, so enabling it never breaks a dependent crate.
+
+#[cfg(all(feature = "no-zeroize", not(debug_assertions)))]
+compile_error!(
+    "the `no-zeroize` feature leaves secrets in memory and cannot be used in release builds"
+);
+
+#[cfg(feature = "no-zeroize")]
+#[deprecated(note = "the `no-zeroize` feature is enabled, secrets are not wiped from memory")]
+const NO_ZEROIZE: () = ();
+
+// Warns on every build with the feature
+#[cfg(feature = "no-zeroize")]
+const _: () = NO_ZEROIZE;
+
+#[cfg(not(feature = "no-zeroize"))]
+pub use zeroize::Zeroizing;
+
+/// Stands in for [`zeroize::Zeroizing`] with the `no-zeroize` feature, leaving the value in memory.
+#[cfg(feature = "no-zeroize")]
+#[derive(Debug, Clone)]
+pub struct Zeroizing<T>(T);
+
+#[cfg(feature = "no-zeroize")]
+impl<T> Zeroizing<T> {
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "no-zeroize")]
+impl<T> std::ops::Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Implements [`zeroize::ZeroizeOnDrop`] for a type, wiping the listed secret fields
+/// when it is dropped unless the `no-zeroize` feature is enabled.
+macro_rules! zeroize_on_drop {
+    ($ty:ident $(<$param:ident: $bound:path>)?, [$($field:ident),+ $(,)?]) => {
+        impl$(<$param: $bound>)? Drop for $ty$(<$param>)? {
+            fn drop(&mut self) {
+                #[cfg(not(feature = "no-zeroize"))]
+                {
+                    use zeroize::Zeroize as _;
+                    $(self.$field.zeroize();)+
+                }
+            }
+        }
+
+        impl$(<$param: $bound>)? zeroize::ZeroizeOnDrop for $ty$(<$param>)? {}
+    };
+}
+pub(crate) use zeroize_on_drop;
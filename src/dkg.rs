@@ -1,23 +1,30 @@
 use crate::crypto::{
     ciphersuite::Ciphersuite,
+    constants::{NEAR_COMMITTEE_LEAF_LABEL, NEAR_COMMITTEE_NODE_LABEL},
     hash::{domain_separate_hash, DomainSeparator, HashOutput},
     polynomials::{Polynomial, PolynomialCommitment},
 };
 
 use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::{Participant, ParticipantList, ParticipantMap};
-use crate::protocol::{
-    echo_broadcast::do_broadcast, helpers::recv_from_others, internal::SharedChannel,
-};
+use crate::protocol::{echo_broadcast::do_broadcast, helpers::recv_from_others, Channel};
 use crate::{KeygenOutput, ReconstructionLowerBound};
 
 use frost_core::keys::{
     CoefficientCommitment, SecretShare, SigningShare, VerifiableSecretSharingCommitment,
+    VerifyingShare,
 };
 use frost_core::{
-    Challenge, Element, Error, Field, Group, Scalar, Signature, SigningKey, VerifyingKey,
+    serialization::SerializableScalar, Challenge, Element, Error, Field, Group, Scalar, Signature,
+    SigningKey, VerifyingKey,
 };
 use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// This function prevents calling keyshare function with inproper inputs
 fn assert_keyshare_inputs<C: Ciphersuite>(
@@ -226,7 +233,9 @@ fn verify_commitment_hash<C: Ciphersuite>(
     commitment: &VerifiableSecretSharingCommitment<C>,
     all_hash_commitments: &ParticipantMap<'_, HashOutput>,
 ) -> Result<(), ProtocolError> {
-    let actual_commitment_hash = all_hash_commitments.index(participant)?;
+    let actual_commitment_hash = all_hash_commitments
+        .get(participant)
+        .ok_or(ProtocolError::MissingContribution(participant))?;
     let commitment_hash =
         domain_separate_hash(domain_separator, &(&participant, &commitment, &session_id))?;
     if *actual_commitment_hash != commitment_hash {
@@ -285,6 +294,98 @@ fn validate_received_share<C: Ciphersuite>(
     Ok(())
 }
 
+/// [`Send`] when the `rayon` feature processes the shares in parallel, and
+/// implemented by every type otherwise, so that the DKG only requires sendable
+/// group elements and scalars when it needs them.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "rayon")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// [`Send`] when the `rayon` feature processes the shares in parallel, and
+/// implemented by every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "rayon"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Evaluates the secret polynomial at each of the given participants.
+/// The shares are returned in the order of `participants` so that the messages
+/// are sent in a deterministic order, even when evaluating in parallel.
+#[cfg(feature = "rayon")]
+fn evaluate_shares<C: Ciphersuite>(
+    secret_coefficients: &Polynomial<C>,
+    participants: &[Participant],
+) -> Result<Vec<SerializableScalar<C>>, ProtocolError>
+where
+    Scalar<C>: Send,
+{
+    participants
+        .par_iter()
+        .map_with(secret_coefficients.clone(), |poly, p| {
+            poly.eval_at_participant(*p)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Evaluates the secret polynomial at each of the given participants.
+#[cfg(not(feature = "rayon"))]
+fn evaluate_shares<C: Ciphersuite>(
+    secret_coefficients: &Polynomial<C>,
+    participants: &[Participant],
+) -> Result<Vec<SerializableScalar<C>>, ProtocolError> {
+    participants
+        .iter()
+        .map(|p| secret_coefficients.eval_at_participant(*p))
+        .collect()
+}
+
+/// Validates each received share against the full commitment of its sender.
+/// Reports the first culprit in the order of reception, even when validating in parallel.
+#[cfg(feature = "rayon")]
+#[allow(clippy::type_complexity)]
+fn validate_received_shares<C: Ciphersuite>(
+    me: Participant,
+    received: Vec<(
+        Participant,
+        SigningShare<C>,
+        VerifiableSecretSharingCommitment<C>,
+    )>,
+) -> Result<(), ProtocolError>
+where
+    Element<C>: Send,
+    Scalar<C>: Send,
+{
+    received
+        .into_par_iter()
+        .map(|(from, signing_share_from, commitment)| {
+            validate_received_share::<C>(me, from, &signing_share_from, &commitment)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Validates each received share against the full commitment of its sender.
+#[cfg(not(feature = "rayon"))]
+#[allow(clippy::type_complexity)]
+fn validate_received_shares<C: Ciphersuite>(
+    me: Participant,
+    received: Vec<(
+        Participant,
+        SigningShare<C>,
+        VerifiableSecretSharingCommitment<C>,
+    )>,
+) -> Result<(), ProtocolError> {
+    received
+        .into_iter()
+        .try_for_each(|(from, signing_share_from, commitment)| {
+            validate_received_share::<C>(me, from, &signing_share_from, &commitment)
+        })
+}
+
 /// generates a verification key out of a public commited polynomial
 // TODO: Fixing this one is not trivial
 #[allow(clippy::needless_pass_by_value)]
@@ -296,16 +397,35 @@ fn public_key_from_commitments<C: Ciphersuite>(
 
     let vk = VerifyingKey::from_commitment(&commitment)
         .map_err(|_| ProtocolError::ErrorExtractVerificationKey)?;
+    assert_prime_order_key(&vk)?;
     Ok(vk)
 }
 
+/// Rejects a public key with a small order component, on curves with a cofactor such as JubJub.
+///
+/// Deserialization only accepts points of the prime order subgroup, so the key is
+/// checked by roundtripping it. Clearing the cofactor instead would silently move
+/// the key away from the one the shares reconstruct.
+fn assert_prime_order_key<C: Ciphersuite>(vk: &VerifyingKey<C>) -> Result<(), ProtocolError> {
+    let element = vk.to_element();
+    if <C::Group as Group>::cofactor() == <C::Group as Group>::Field::one()
+        || element == <C::Group as Group>::identity()
+    {
+        return Ok(());
+    }
+    let bytes =
+        <C::Group as Group>::serialize(&element).map_err(|_| ProtocolError::PointSerialization)?;
+    <C::Group as Group>::deserialize(&bytes).map_err(|_| ProtocolError::MalformedElement)?;
+    Ok(())
+}
+
 /// This function takes err as input.
 /// If err is None then broadcast success
 /// otherwise, broadcast failure
 /// If during broadcast it receives an error then propagates it
 /// This function is used in the final round of DKG
 async fn broadcast_success(
-    chan: &mut SharedChannel,
+    chan: &mut impl Channel,
     participants: &ParticipantList,
     me: Participant,
     session_id: HashOutput,
@@ -338,36 +458,50 @@ async fn broadcast_success(
 }
 
 /// Performs the heart of DKG, Reshare and Refresh protocols
-#[allow(clippy::too_many_lines)]
+///
+/// When `external_session_id` is given, the first round generating
+/// the session id at random is skipped.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 async fn do_keyshare<C: Ciphersuite>(
-    mut chan: SharedChannel,
+    mut chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     threshold: ReconstructionLowerBound,
     secret: Scalar<C>,
     old_reshare_package: Option<(VerifyingKey<C>, ParticipantList)>,
+    external_session_id: Option<[u8; 32]>,
     rng: &mut impl CryptoRngCore,
-) -> Result<KeygenOutput<C>, ProtocolError> {
+) -> Result<KeygenOutput<C>, ProtocolError>
+where
+    Element<C>: MaybeSend,
+    Scalar<C>: MaybeSend,
+{
     let mut all_full_commitments = ParticipantMap::new(&participants);
     let mut domain_separator = DomainSeparator::new();
     // Make sure you do not call do_keyshare with zero as secret on an old participant
     let (old_verification_key, old_participants) =
         assert_keyshare_inputs(me, &secret, old_reshare_package)?;
 
-    // Start Round 1
-    // Step 1.2
-    let mut my_session_id = [0u8; 32]; // 256 bits
-    rng.fill_bytes(&mut my_session_id);
-    // Step 1.3 & 2.1
-    let session_ids = do_broadcast(&mut chan, &participants, me, my_session_id).await?;
+    let session_id = if let Some(session_id) = external_session_id {
+        // The session id was agreed upon out of band. A participant holding a different
+        // one fails the proof of knowledge or commitment hash checks, aborting the protocol
+        domain_separate_hash(&mut domain_separator, &session_id)?
+    } else {
+        // Start Round 1
+        // Step 1.2
+        let mut my_session_id = [0u8; 32]; // 256 bits
+        rng.fill_bytes(&mut my_session_id);
+        // Step 1.3 & 2.1
+        let session_ids = do_broadcast(&mut chan, &participants, me, my_session_id).await?;
+        // Step 2.2
+        domain_separate_hash(&mut domain_separator, &session_ids)?
+    };
 
     // Start Round 2
     // generate your secret polynomial p with the constant term set to the secret
     // and the rest of the coefficients are picked at random
     // because the library does not allow serializing the zero and identity term,
     // this function does not add the zero coefficient
-    // Step 2.2
-    let session_id = domain_separate_hash(&mut domain_separator, &session_ids)?;
     // Step 2.3
     // the degree of the polynomial is threshold - 1
     let degree = threshold
@@ -444,6 +578,7 @@ async fn do_keyshare<C: Ciphersuite>(
     let wait_round_3 = chan.next_waitpoint();
     // Step 4.2 4.3 and 4.4
     for p in participants.others(me) {
+        // the broadcast only returns once every participant contributed
         let (commitment_i, proof_i) = commitments_and_proofs_map.index(p)?;
 
         // verify the proof of knowledge
@@ -496,11 +631,11 @@ async fn do_keyshare<C: Ciphersuite>(
     }
 
     // Step 4.6
-    for p in participants.others(me) {
-        // securely send to each other participant a secret share
-        // using the evaluation secret polynomial on the identifier of the recipient
-        // should not panic as secret_coefficients are created internally
-        let signing_share_to_p = secret_coefficients.eval_at_participant(p)?;
+    // securely send to each other participant a secret share
+    // using the evaluation secret polynomial on the identifier of the recipient
+    let others = participants.others(me).collect::<Vec<_>>();
+    let signing_shares = evaluate_shares(&secret_coefficients, &others)?;
+    for (p, signing_share_to_p) in others.into_iter().zip(signing_shares) {
         // send the evaluation privately to participant p
         chan.send_private(wait_round_3, p, &signing_share_to_p)?;
     }
@@ -511,19 +646,29 @@ async fn do_keyshare<C: Ciphersuite>(
     let mut my_signing_share = secret_coefficients.eval_at_participant(me)?.0;
     // receive evaluations from all participants
     // Step 5.1
-    for (from, signing_share_from) in
-        recv_from_others(&chan, wait_round_3, &participants, me).await?
-    {
-        // Verify the share
-        // this deviates from the original FROST DKG paper
-        // however it matches the FROST implementation of ZCash
-        let full_commitment_from = all_full_commitments.index(from)?;
-        // Step 5.2
-        validate_received_share::<C>(me, from, &signing_share_from, full_commitment_from)?;
-
-        // Compute the sum of all the owned secret shares
-        // At the end of this loop, I will be owning a valid secret signing share
-        // Step 5.3
+    let received_shares: Vec<(Participant, SigningShare<C>)> =
+        recv_from_others(&chan, wait_round_3, &participants, me).await?;
+
+    // Verify the shares
+    // this deviates from the original FROST DKG paper
+    // however it matches the FROST implementation of ZCash
+    // Step 5.2
+    let to_validate = received_shares
+        .iter()
+        .map(|(from, signing_share_from)| {
+            let full_commitment_from = all_full_commitments
+                .get(*from)
+                .ok_or(ProtocolError::MissingContribution(*from))?
+                .clone();
+            Ok((*from, *signing_share_from, full_commitment_from))
+        })
+        .collect::<Result<Vec<_>, ProtocolError>>()?;
+    validate_received_shares::<C>(me, to_validate)?;
+
+    // Compute the sum of all the owned secret shares
+    // At the end of this loop, I will be owning a valid secret signing share
+    // Step 5.3
+    for (_, signing_share_from) in received_shares {
         my_signing_share = my_signing_share + signing_share_from.to_scalar();
     }
 
@@ -534,25 +679,91 @@ async fn do_keyshare<C: Ciphersuite>(
     Ok(KeygenOutput {
         private_share: SigningShare::new(my_signing_share),
         public_key: verifying_key,
+        epoch: 0,
     })
 }
 
 pub async fn do_keygen<C: Ciphersuite>(
-    chan: SharedChannel,
+    chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     threshold: impl Into<ReconstructionLowerBound>,
+    session_id: Option<[u8; 32]>,
     mut rng: impl CryptoRngCore,
-) -> Result<KeygenOutput<C>, ProtocolError> {
+) -> Result<KeygenOutput<C>, ProtocolError>
+where
+    Element<C>: MaybeSend,
+    Scalar<C>: MaybeSend,
+{
     let threshold = threshold.into();
     // pick share at random
     let secret = SigningKey::<C>::new(&mut rng).to_scalar();
     // call keyshare
-    let keygen_output =
-        do_keyshare::<C>(chan, participants, me, threshold, secret, None, &mut rng).await?;
+    let keygen_output = do_keyshare::<C>(
+        chan,
+        participants,
+        me,
+        threshold,
+        secret,
+        None,
+        session_id,
+        &mut rng,
+    )
+    .await?;
     Ok(keygen_output)
 }
 
+/// Verifies a key pair received out-of-band, e.g. restored from a backup,
+/// against the published commitment to the group polynomial.
+///
+/// This runs offline the checks of the last rounds of the DKG:
+/// the public key must be the constant term of the commitment and
+/// the private share must be its evaluation at `me`.
+pub fn verify_keygen_output<C: Ciphersuite>(
+    output: &KeygenOutput<C>,
+    me: Participant,
+    group_commitment: &VerifiableSecretSharingCommitment<C>,
+) -> Result<(), ProtocolError> {
+    let verifying_key = public_key_from_commitments(vec![group_commitment])?;
+    if verifying_key != output.public_key {
+        return Err(ProtocolError::AssertionFailed(
+            "public key does not match the group commitment".to_string(),
+        ));
+    }
+    validate_received_share(me, me, &output.private_share, group_commitment)
+}
+
+/// Adjusts an aggregated public key to a change in the set of commitments
+/// it was computed from, e.g. when a participant joins a reshare.
+///
+/// Only the constant terms of the commitments contribute to the public key,
+/// so this matches a full recomputation over the new set while only touching
+/// the commitments that changed. Commitments of new participants must be given
+/// in full, i.e. with the identity as constant term.
+pub fn update_public_key<C: Ciphersuite>(
+    prev: VerifyingKey<C>,
+    removed: &[VerifiableSecretSharingCommitment<C>],
+    added: &[VerifiableSecretSharingCommitment<C>],
+) -> Result<VerifyingKey<C>, ProtocolError> {
+    let constant_term = |commitment: &VerifiableSecretSharingCommitment<C>| {
+        commitment
+            .coefficients()
+            .first()
+            .map(CoefficientCommitment::value)
+            .ok_or(ProtocolError::IncorrectNumberOfCommitments)
+    };
+    let mut element = prev.to_element();
+    for commitment in removed {
+        element = element - constant_term(commitment)?;
+    }
+    for commitment in added {
+        element = element + constant_term(commitment)?;
+    }
+    let vk = VerifyingKey::new(element);
+    assert_prime_order_key(&vk)?;
+    Ok(vk)
+}
+
 /// This function is to be called before running DKG
 /// It ensures that the input parameters are valid
 pub fn assert_key_invariants(
@@ -560,8 +771,27 @@ pub fn assert_key_invariants(
     me: Participant,
     threshold: impl Into<ReconstructionLowerBound>,
 ) -> Result<ParticipantList, InitializationError> {
-    let threshold = usize::from(threshold.into());
+    let participants = assert_committee_invariants(participants, usize::from(threshold.into()))?;
+
+    // ensure my presence in the participant list
+    if !participants.contains(me) {
+        return Err(InitializationError::MissingParticipant {
+            role: "self",
+            participant: me,
+        });
+    }
+    Ok(participants)
+}
+
+/// Ensures that a committee can hold a key with the given threshold
+fn assert_committee_invariants(
+    participants: &[Participant],
+    threshold: usize,
+) -> Result<ParticipantList, InitializationError> {
     // need enough participants
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -582,31 +812,28 @@ pub fn assert_key_invariants(
     }
 
     // ensure uniqueness of participants in the participant list
-    let participants =
-        ParticipantList::new(participants).ok_or(InitializationError::DuplicateParticipants)?;
-
-    // ensure my presence in the participant list
-    if !participants.contains(me) {
-        return Err(InitializationError::MissingParticipant {
-            role: "self",
-            participant: me,
-        });
-    }
-    Ok(participants)
+    ParticipantList::new(participants).ok_or(InitializationError::DuplicateParticipants)
 }
 
-/// reshares the keyshares between the parties and allows changing the threshold
+/// reshares the keyshares between the parties and allows changing the threshold,
+/// outputting shares in `epoch`
 #[allow(clippy::too_many_arguments)]
 pub async fn do_reshare<C: Ciphersuite>(
-    chan: SharedChannel,
+    chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     threshold: impl Into<ReconstructionLowerBound>,
     old_signing_key: Option<SigningShare<C>>,
     old_public_key: VerifyingKey<C>,
+    epoch: u64,
     old_participants: ParticipantList,
+    session_id: Option<[u8; 32]>,
     mut rng: impl CryptoRngCore,
-) -> Result<KeygenOutput<C>, ProtocolError> {
+) -> Result<KeygenOutput<C>, ProtocolError>
+where
+    Element<C>: MaybeSend,
+    Scalar<C>: MaybeSend,
+{
     let threshold = threshold.into();
     let intersection = old_participants.intersection(&participants);
     // either extract the share and linearize it or set it to zero
@@ -627,11 +854,19 @@ pub async fn do_reshare<C: Ciphersuite>(
         threshold,
         secret,
         old_reshare_package,
+        session_id,
         &mut rng,
     )
     .await?;
 
-    Ok(keygen_output)
+    Ok(keygen_output.with_epoch(epoch))
+}
+
+/// The epoch of the shares output by a reshare of shares in `old_epoch`
+pub fn next_epoch(old_epoch: u64) -> Result<u64, InitializationError> {
+    old_epoch
+        .checked_add(1)
+        .ok_or_else(|| InitializationError::BadParameters("the key epoch overflows".to_string()))
 }
 
 // Step 1.1
@@ -648,16 +883,13 @@ pub fn assert_reshare_keys_invariants<C: Ciphersuite>(
 
     let participants = assert_key_invariants(participants, me, threshold)?;
 
-    let old_participants =
-        ParticipantList::new(old_participants).ok_or(InitializationError::DuplicateParticipants)?;
+    let old_participants = assert_old_participants_invariants(
+        old_participants,
+        old_threshold,
+        &participants,
+        threshold,
+    )?;
 
-    // Step 1.1
-    if old_participants.intersection(&participants).len() < old_threshold {
-        return Err(InitializationError::NotEnoughParticipantsForNewThreshold {
-            threshold: old_threshold,
-            participants: old_participants.intersection(&participants).len(),
-        });
-    }
     // Step 1.1
     // if me is not in the old participant set then ensure that old_signing_key is None
     if old_participants.contains(me) && old_signing_key.is_none() {
@@ -668,22 +900,392 @@ pub fn assert_reshare_keys_invariants<C: Ciphersuite>(
     Ok((participants, old_participants))
 }
 
+/// Ensures that enough holders of the old shares take part in the new committee
+fn assert_old_participants_invariants(
+    old_participants: &[Participant],
+    old_threshold: usize,
+    participants: &ParticipantList,
+    threshold: usize,
+) -> Result<ParticipantList, InitializationError> {
+    if old_participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
+    let old_participants =
+        ParticipantList::new(old_participants).ok_or(InitializationError::DuplicateParticipants)?;
+
+    // Step 1.1
+    // the old key can only be reshared if at least old_threshold holders of
+    // old shares take part, whether the threshold is raised or lowered
+    let intersection = old_participants.intersection(participants).len();
+    if intersection < old_threshold {
+        if threshold > old_threshold {
+            return Err(InitializationError::NotEnoughParticipantsForNewThreshold {
+                threshold: old_threshold,
+                participants: intersection,
+            });
+        }
+        return Err(
+            InitializationError::NotEnoughOldParticipantsForLowerThreshold {
+                threshold,
+                old_threshold,
+                participants: intersection,
+            },
+        );
+    }
+    Ok(old_participants)
+}
+
+/// How a reshare changes the committee, as returned by [`reshare_plan`].
+///
+/// Each list is in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResharePlan {
+    /// Holders of an old share that are part of the new committee
+    pub staying: Vec<Participant>,
+    /// New committee members without an old share
+    pub joining: Vec<Participant>,
+    /// Holders of an old share that are not part of the new committee
+    pub leaving: Vec<Participant>,
+    pub old_threshold: usize,
+    pub new_threshold: usize,
+}
+
+/// Classifies the members of a reshare from the `old` to the `new` committee,
+/// checking the same invariants as [`crate::reshare`] does for every participant.
+///
+/// In particular the staying members must hold at least `old_threshold` shares
+/// for the key to be reconstructed, whether the threshold is raised or lowered.
+pub fn reshare_plan(
+    old: &[Participant],
+    old_threshold: impl Into<ReconstructionLowerBound>,
+    new: &[Participant],
+    new_threshold: impl Into<ReconstructionLowerBound>,
+) -> Result<ResharePlan, InitializationError> {
+    let old_threshold = usize::from(old_threshold.into());
+    let new_threshold = usize::from(new_threshold.into());
+    let new = assert_committee_invariants(new, new_threshold)?;
+    let old = assert_old_participants_invariants(old, old_threshold, &new, new_threshold)?;
+
+    let (staying, leaving) = old
+        .participants()
+        .iter()
+        .copied()
+        .partition(|p| new.contains(*p));
+    let joining = new
+        .participants()
+        .iter()
+        .copied()
+        .filter(|p| !old.contains(*p))
+        .collect();
+    Ok(ResharePlan {
+        staying,
+        joining,
+        leaving,
+        old_threshold,
+        new_threshold,
+    })
+}
+
+/// A Merkle tree over the verifying shares of a committee,
+/// with one leaf per participant in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    participants: Vec<Participant>,
+    /// The hashes of each level, from the leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+/// A proof that a verifying share belongs to a committee commitment,
+/// as returned by [`prove_membership`].
+///
+/// It holds the siblings on the path from the leaf to the root. The last node
+/// of a level of odd length has no sibling and is moved up as is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MembershipProof {
+    index: usize,
+    siblings: Vec<Option<[u8; 32]>>,
+}
+
+fn committee_leaf<C: Ciphersuite>(
+    participant: Participant,
+    share: &VerifyingShare<C>,
+) -> Result<[u8; 32], ProtocolError> {
+    let share = share
+        .serialize()
+        .map_err(|_| ProtocolError::PointSerialization)?;
+    let mut hasher = Sha256::new();
+    hasher.update(NEAR_COMMITTEE_LEAF_LABEL);
+    hasher.update(participant.bytes());
+    hasher.update(share);
+    Ok(hasher.finalize().into())
+}
+
+fn committee_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(NEAR_COMMITTEE_NODE_LABEL);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Commits to the verifying shares of a committee with a Merkle tree,
+/// returning its 32-byte root along with the tree to prove memberships.
+///
+/// The root does not depend on the order of `shares`, as the leaves
+/// are sorted by participant. Each participant must appear once.
+pub fn committee_commitment<C: Ciphersuite>(
+    shares: &[(Participant, VerifyingShare<C>)],
+) -> Result<([u8; 32], MerkleTree), ProtocolError> {
+    let mut shares = shares.iter().collect::<Vec<_>>();
+    shares.sort_by_key(|(p, _)| *p);
+    if let Some(p) = shares.windows(2).find_map(|pair| match pair {
+        [(a, _), (b, _)] if a == b => Some(*a),
+        _ => None,
+    }) {
+        return Err(ProtocolError::InvalidInput(format!(
+            "participant {p:?} appears twice in the committee"
+        )));
+    }
+
+    let leaves = shares
+        .iter()
+        .map(|(p, share)| committee_leaf(*p, share))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut levels = vec![leaves];
+    loop {
+        let next = match levels.last() {
+            Some(level) if level.len() > 1 => level
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [left, right] => Some(committee_node(left, right)),
+                    [single] => Some(*single),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            _ => break,
+        };
+        levels.push(next);
+    }
+    let root = levels
+        .last()
+        .and_then(|level| level.first())
+        .copied()
+        .ok_or_else(|| ProtocolError::InvalidInput("the committee cannot be empty".to_string()))?;
+
+    let tree = MerkleTree {
+        participants: shares.iter().map(|(p, _)| *p).collect(),
+        levels,
+    };
+    Ok((root, tree))
+}
+
+/// Proves that the verifying share of `participant` is part of the tree,
+/// or returns `None` if the participant is not in the committee.
+pub fn prove_membership(tree: &MerkleTree, participant: Participant) -> Option<MembershipProof> {
+    let index = tree.participants.binary_search(&participant).ok()?;
+    let mut position = index;
+    let siblings = tree
+        .levels
+        .iter()
+        .take(tree.levels.len().saturating_sub(1))
+        .map(|level| {
+            let sibling = level.get(position ^ 1).copied();
+            position /= 2;
+            sibling
+        })
+        .collect();
+    Some(MembershipProof { index, siblings })
+}
+
+/// Checks that `share` is the verifying share of `participant`
+/// in the committee committed to by `root`.
+pub fn verify_membership<C: Ciphersuite>(
+    root: &[u8; 32],
+    participant: Participant,
+    share: &VerifyingShare<C>,
+    proof: &MembershipProof,
+) -> bool {
+    let Ok(mut node) = committee_leaf(participant, share) else {
+        return false;
+    };
+    let mut position = proof.index;
+    for sibling in &proof.siblings {
+        if let Some(sibling) = sibling {
+            node = if position % 2 == 0 {
+                committee_node(&node, sibling)
+            } else {
+                committee_node(sibling, &node)
+            };
+        }
+        position /= 2;
+    }
+    node == *root
+}
+
+/// The public outcome of a key generation for a whole committee: the group
+/// public key, the threshold and the verifying share of every member.
+///
+/// This is the artifact to publish after a DKG. Its serialization is prefixed
+/// by a format version, and is checked against the same invariants as [`crate::keygen`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound = "C: Ciphersuite",
+    into = "StoredCommitteeKeyPackage<C>",
+    try_from = "StoredCommitteeKeyPackage<C>"
+)]
+pub struct CommitteeKeyPackage<C: Ciphersuite> {
+    group_public_key: VerifyingKey<C>,
+    threshold: usize,
+    verifying_shares: BTreeMap<Participant, VerifyingShare<C>>,
+}
+
+impl<C: Ciphersuite> CommitteeKeyPackage<C> {
+    fn new(
+        group_public_key: VerifyingKey<C>,
+        threshold: usize,
+        verifying_shares: Vec<(Participant, VerifyingShare<C>)>,
+    ) -> Result<Self, InitializationError> {
+        let participants = verifying_shares.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        assert_committee_invariants(&participants, threshold)?;
+        Ok(Self {
+            group_public_key,
+            threshold,
+            verifying_shares: verifying_shares.into_iter().collect(),
+        })
+    }
+
+    /// Gathers the key pairs output by a key generation, or a reshare, of a committee.
+    ///
+    /// Every member must be given once and all must agree on the public key.
+    pub fn from_keygen_outputs(
+        outputs: &[(Participant, KeygenOutput<C>)],
+        threshold: impl Into<ReconstructionLowerBound>,
+    ) -> Result<Self, InitializationError> {
+        let group_public_key = outputs
+            .first()
+            .map(|(_, output)| output.public_key)
+            .ok_or(InitializationError::EmptyParticipants)?;
+        if outputs
+            .iter()
+            .any(|(_, output)| output.public_key != group_public_key)
+        {
+            return Err(InitializationError::BadParameters(
+                "the key pairs hold different public keys".to_string(),
+            ));
+        }
+        let verifying_shares = outputs
+            .iter()
+            .map(|(p, output)| (*p, VerifyingShare::from(output.private_share)))
+            .collect();
+        Self::new(
+            group_public_key,
+            usize::from(threshold.into()),
+            verifying_shares,
+        )
+    }
+
+    pub fn group_public_key(&self) -> &VerifyingKey<C> {
+        &self.group_public_key
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The members of the committee, in ascending order
+    pub fn participants(&self) -> Vec<Participant> {
+        self.verifying_shares.keys().copied().collect()
+    }
+
+    /// The verifying share of `participant`, if it is a member of the committee
+    pub fn verifying_share(&self, participant: Participant) -> Option<&VerifyingShare<C>> {
+        self.verifying_shares.get(&participant)
+    }
+}
+
+/// The serialized form of a [`CommitteeKeyPackage`], prefixed by its format version.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "C: Ciphersuite")]
+struct StoredCommitteeKeyPackage<C: Ciphersuite> {
+    version: u8,
+    group_public_key: VerifyingKey<C>,
+    threshold: usize,
+    verifying_shares: Vec<(Participant, VerifyingShare<C>)>,
+}
+
+impl<C: Ciphersuite> StoredCommitteeKeyPackage<C> {
+    const VERSION: u8 = 1;
+}
+
+impl<C: Ciphersuite> From<CommitteeKeyPackage<C>> for StoredCommitteeKeyPackage<C> {
+    fn from(package: CommitteeKeyPackage<C>) -> Self {
+        Self {
+            version: Self::VERSION,
+            group_public_key: package.group_public_key,
+            threshold: package.threshold,
+            verifying_shares: package.verifying_shares.into_iter().collect(),
+        }
+    }
+}
+
+impl<C: Ciphersuite> TryFrom<StoredCommitteeKeyPackage<C>> for CommitteeKeyPackage<C> {
+    type Error = ProtocolError;
+
+    fn try_from(stored: StoredCommitteeKeyPackage<C>) -> Result<Self, Self::Error> {
+        if stored.version != StoredCommitteeKeyPackage::<C>::VERSION {
+            return Err(ProtocolError::DeserializationError(format!(
+                "unsupported committee key package version {}",
+                stored.version
+            )));
+        }
+        Self::new(
+            stored.group_public_key,
+            stored.threshold,
+            stored.verifying_shares,
+        )
+        .map_err(|e| ProtocolError::DeserializationError(e.to_string()))
+    }
+}
+
 #[cfg(test)]
 pub mod test {
 
-    use super::domain_separate_hash;
+    use super::{
+        committee_commitment, domain_separate_hash, prove_membership, public_key_from_commitments,
+        reshare_plan, update_public_key, verify_keygen_output, verify_membership,
+        CommitteeKeyPackage,
+    };
     use crate::crypto::ciphersuite::Ciphersuite;
     use crate::crypto::hash::DomainSeparator;
-    use crate::errors::InitializationError;
+    use crate::crypto::polynomials::Polynomial;
+    use crate::errors::{InitializationError, ProtocolError};
     use crate::participants::{Participant, ParticipantList};
     use crate::test_utils::{
-        assert_public_key_invariant, generate_participants, run_keygen, run_refresh, run_reshare,
-        GenOutput,
+        assert_public_key_invariant, assert_serde_roundtrip, generate_participants, run_keygen,
+        run_protocol, run_protocol_instrumented, run_refresh, run_reshare, GenOutput, GenProtocol,
+        MockCryptoRng,
+    };
+    use crate::{
+        keygen, keygen_additive, keygen_with_session_id, reconstruct_additive_secret, refresh,
+        reshare, reshare_with_session_id,
+    };
+    #[cfg(feature = "custom-executor")]
+    use crate::{
+        keygen_with_channel,
+        test_utils::{run_futures, MemoryChannel},
     };
-    use crate::{keygen, reshare};
-    use crate::{KeygenOutput, ReconstructionLowerBound};
-    use frost_core::{Field, Group};
-    use rand_core::{CryptoRngCore, SeedableRng};
+    use crate::{AdditiveKeygenOutput, KeygenOutput, ReconstructionLowerBound, Tweak};
+    use elliptic_curve::group::GroupEncoding;
+    use frost_core::{
+        keys::{
+            CoefficientCommitment, SigningShare, VerifiableSecretSharingCommitment, VerifyingShare,
+        },
+        Element, Field, Group, VerifyingKey,
+    };
+    use frost_secp256k1::{Secp256K1Group, Secp256K1ScalarField, Secp256K1Sha256};
+    use rand_core::{CryptoRngCore, RngCore, SeedableRng};
+    use reddsa::frost::redjubjub::JubjubBlake2b512;
+    use subtle::ConstantTimeEq;
 
     #[test]
     fn test_domain_separate_hash() {
@@ -698,6 +1300,510 @@ pub mod test {
         assert!(hash_1 != hash_2);
     }
 
+    #[test]
+    fn test_keygen_output_ct_eq() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let result = run_keygen::<Secp256K1Sha256, _>(&participants, 2, &mut rng);
+        let key_pair = &result[0].1;
+        assert!(bool::from(key_pair.ct_eq(&key_pair.clone())));
+        assert!(!bool::from(key_pair.ct_eq(&result[1].1)));
+        let other_key = KeygenOutput {
+            private_share: SigningShare::new(key_pair.private_share.to_scalar()),
+            public_key: result[1].1.public_key,
+            epoch: 0,
+        };
+        assert!(!bool::from(key_pair.ct_eq(&other_key)));
+        assert!(!bool::from(
+            key_pair.ct_eq(&key_pair.clone().with_epoch(key_pair.epoch + 1))
+        ));
+    }
+
+    #[test]
+    fn test_keygen_output_serde_roundtrip() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        for (_, key_pair) in run_keygen::<Secp256K1Sha256, _>(&participants, 2, &mut rng) {
+            assert_serde_roundtrip(&key_pair);
+            assert_serde_roundtrip(&key_pair.with_epoch(7));
+        }
+        let tweak = Tweak::<Secp256K1Sha256>::new(Secp256K1ScalarField::random(&mut rng));
+        assert_serde_roundtrip(&tweak);
+    }
+
+    #[test]
+    fn test_reshare_lower_threshold_needs_old_threshold_holders() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let old_participants = generate_participants(7);
+        let result0 = run_keygen::<Secp256K1Sha256, _>(&old_participants, 5, &mut rng);
+        let pub_key = result0[0].1.public_key;
+
+        // 5-of-7 down to 3-of-4 with only four old share holders
+        let new_participants = &old_participants[..4];
+        let result = reshare::<Secp256K1Sha256>(
+            &old_participants,
+            5,
+            Some(result0[0].1.private_share),
+            pub_key,
+            0,
+            new_participants,
+            3,
+            new_participants[0],
+            MockCryptoRng::seed_from_u64(rng.next_u64()),
+        );
+        let err = result.err().unwrap();
+        assert_eq!(
+            err,
+            InitializationError::NotEnoughOldParticipantsForLowerThreshold {
+                threshold: 3,
+                old_threshold: 5,
+                participants: 4,
+            }
+        );
+        assert!(!err.to_string().contains("bigger than old threshold"));
+
+        // 5-of-7 down to 3-of-5 keeps enough old share holders
+        let new_participants = &old_participants[2..];
+        let result1 = run_reshare(
+            &old_participants,
+            &pub_key,
+            &result0,
+            5,
+            3,
+            new_participants,
+            &mut rng,
+        );
+        assert_public_key_invariant(&result1);
+        assert_eq!(result1[0].1.public_key, pub_key);
+    }
+
+    #[test]
+    fn test_empty_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let keys = run_keygen::<Secp256K1Sha256, _>(&participants, 2, &mut rng);
+        let me = participants[0];
+        let share = Some(keys[0].1.private_share);
+        let pub_key = keys[0].1.public_key;
+
+        let result = keygen::<Secp256K1Sha256>(&[], me, 2, MockCryptoRng::seed_from_u64(1));
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+
+        let result = refresh::<Secp256K1Sha256>(
+            share,
+            pub_key,
+            0,
+            &[],
+            2,
+            me,
+            MockCryptoRng::seed_from_u64(1),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+
+        // empty new participants
+        let result = reshare::<Secp256K1Sha256>(
+            &participants,
+            2,
+            share,
+            pub_key,
+            0,
+            &[],
+            2,
+            me,
+            MockCryptoRng::seed_from_u64(1),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+
+        // empty old participants
+        let result = reshare::<Secp256K1Sha256>(
+            &[],
+            2,
+            share,
+            pub_key,
+            0,
+            &participants,
+            2,
+            me,
+            MockCryptoRng::seed_from_u64(1),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
+
+    #[test]
+    fn test_refresh_epoch_overflow() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let keys = run_keygen::<Secp256K1Sha256, _>(&participants, 2, &mut rng);
+        let result = refresh::<Secp256K1Sha256>(
+            Some(keys[0].1.private_share),
+            keys[0].1.public_key,
+            u64::MAX,
+            &participants,
+            2,
+            participants[0],
+            MockCryptoRng::seed_from_u64(1),
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(_)
+        ));
+    }
+
+    #[test]
+    fn test_reshare_higher_threshold() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let old_participants = generate_participants(7);
+        let result0 = run_keygen::<Secp256K1Sha256, _>(&old_participants, 5, &mut rng);
+        let pub_key = result0[0].1.public_key;
+
+        // 5-of-7 up to 6-of-9 with two new joiners
+        let new_participants = generate_participants(9);
+        let result1 = run_reshare(
+            &old_participants,
+            &pub_key,
+            &result0,
+            5,
+            6,
+            &new_participants,
+            &mut rng,
+        );
+        assert_public_key_invariant(&result1);
+        assert_eq!(result1.len(), 9);
+        let x1 = compute_private_key(&result1);
+        assert_eq!(Secp256K1Group::generator() * x1, pub_key.to_element());
+
+        // 5-of-7 up to 6-of-9 with only four old share holders
+        let mut new_participants = old_participants[..4].to_vec();
+        new_participants.extend((7..12u32).map(Participant::from));
+        let result = reshare::<Secp256K1Sha256>(
+            &old_participants,
+            5,
+            Some(result0[0].1.private_share),
+            pub_key,
+            0,
+            &new_participants,
+            6,
+            new_participants[0],
+            MockCryptoRng::seed_from_u64(rng.next_u64()),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::NotEnoughParticipantsForNewThreshold {
+                threshold: 5,
+                participants: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_reshare_plan_matrix() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let old_participants = generate_participants(6);
+        let old_threshold = 4;
+        let keys = run_keygen::<Secp256K1Sha256, _>(&old_participants, old_threshold, &mut rng);
+        let pub_key = keys[0].1.public_key;
+        let ids = |range: std::ops::Range<u32>| range.map(Participant::from).collect::<Vec<_>>();
+
+        // (new participants, new threshold, expected (staying, joining, leaving) counts)
+        let cases = [
+            // same committee
+            (ids(0..6), 4, Ok((6, 0, 0))),
+            (ids(0..6), 5, Ok((6, 0, 0))),
+            (ids(0..6), 3, Ok((6, 0, 0))),
+            // more participants
+            (ids(0..8), 4, Ok((6, 2, 0))),
+            (ids(0..8), 6, Ok((6, 2, 0))),
+            (ids(0..8), 2, Ok((6, 2, 0))),
+            // less participants
+            (ids(0..4), 4, Ok((4, 0, 2))),
+            (ids(0..4), 3, Ok((4, 0, 2))),
+            (
+                ids(0..4),
+                5,
+                Err(InitializationError::ThresholdTooLarge {
+                    threshold: 5,
+                    max: 4,
+                }),
+            ),
+            // two members swapped
+            (ids(2..8), 4, Ok((4, 2, 2))),
+            (ids(2..8), 5, Ok((4, 2, 2))),
+            (ids(2..8), 3, Ok((4, 2, 2))),
+            // half of the committee swapped
+            (
+                ids(3..9),
+                4,
+                Err(
+                    InitializationError::NotEnoughOldParticipantsForLowerThreshold {
+                        threshold: 4,
+                        old_threshold: 4,
+                        participants: 3,
+                    },
+                ),
+            ),
+            (
+                ids(3..9),
+                5,
+                Err(InitializationError::NotEnoughParticipantsForNewThreshold {
+                    threshold: 4,
+                    participants: 3,
+                }),
+            ),
+            (
+                ids(3..9),
+                2,
+                Err(
+                    InitializationError::NotEnoughOldParticipantsForLowerThreshold {
+                        threshold: 2,
+                        old_threshold: 4,
+                        participants: 3,
+                    },
+                ),
+            ),
+            // whole committee replaced
+            (
+                ids(6..12),
+                4,
+                Err(
+                    InitializationError::NotEnoughOldParticipantsForLowerThreshold {
+                        threshold: 4,
+                        old_threshold: 4,
+                        participants: 0,
+                    },
+                ),
+            ),
+        ];
+
+        for (new_participants, new_threshold, expected) in cases {
+            let plan = reshare_plan(
+                &old_participants,
+                old_threshold,
+                &new_participants,
+                new_threshold,
+            );
+            match (&plan, expected) {
+                (Ok(plan), Ok((staying, joining, leaving))) => {
+                    assert_eq!(plan.staying.len(), staying);
+                    assert_eq!(plan.joining.len(), joining);
+                    assert_eq!(plan.leaving.len(), leaving);
+                    assert_eq!(plan.old_threshold, old_threshold);
+                    assert_eq!(plan.new_threshold, new_threshold);
+
+                    let mut old = [plan.staying.clone(), plan.leaving.clone()].concat();
+                    old.sort();
+                    assert_eq!(old, old_participants);
+                    let mut new = [plan.staying.clone(), plan.joining.clone()].concat();
+                    new.sort();
+                    assert_eq!(new, new_participants);
+                }
+                (Err(err), Err(expected)) => assert_eq!(*err, expected),
+                _ => panic!("unexpected plan {plan:?} for {new_participants:?}"),
+            }
+
+            // the plan agrees with the checks run by every participant of the reshare
+            for me in &new_participants {
+                let old_signing_key = keys
+                    .iter()
+                    .find(|(p, _)| p == me)
+                    .map(|(_, key)| key.private_share);
+                let result = reshare::<Secp256K1Sha256>(
+                    &old_participants,
+                    old_threshold,
+                    old_signing_key,
+                    pub_key,
+                    0,
+                    &new_participants,
+                    new_threshold,
+                    *me,
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                );
+                assert_eq!(result.err(), plan.clone().err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_keygen_output() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let polynomial =
+            Polynomial::<Secp256K1Sha256>::generate_polynomial(None, 2, &mut rng).unwrap();
+        let group_commitment = VerifiableSecretSharingCommitment::new(
+            polynomial.commit_polynomial().unwrap().get_coefficients(),
+        );
+        let public_key =
+            VerifyingKey::new(Secp256K1Group::generator() * polynomial.eval_at_zero().unwrap().0);
+        let outputs = participants
+            .iter()
+            .map(|p| KeygenOutput {
+                private_share: SigningShare::new(polynomial.eval_at_participant(*p).unwrap().0),
+                public_key,
+                epoch: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for (p, output) in participants.iter().zip(&outputs) {
+            assert_eq!(verify_keygen_output(output, *p, &group_commitment), Ok(()));
+        }
+
+        // the share of another participant
+        assert_eq!(
+            verify_keygen_output(&outputs[1], participants[0], &group_commitment),
+            Err(ProtocolError::InvalidSecretShare(participants[0]))
+        );
+
+        // a tampered share
+        let tampered = KeygenOutput {
+            private_share: SigningShare::new(
+                outputs[0].private_share.to_scalar() + Secp256K1ScalarField::one(),
+            ),
+            public_key,
+            epoch: 0,
+        };
+        assert_eq!(
+            verify_keygen_output(&tampered, participants[0], &group_commitment),
+            Err(ProtocolError::InvalidSecretShare(participants[0]))
+        );
+
+        // a public key that is not committed to
+        let wrong_key = KeygenOutput {
+            private_share: outputs[0].private_share,
+            public_key: VerifyingKey::new(Secp256K1Group::generator()),
+            epoch: 0,
+        };
+        assert!(matches!(
+            verify_keygen_output(&wrong_key, participants[0], &group_commitment),
+            Err(ProtocolError::AssertionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_public_key() {
+        type C = Secp256K1Sha256;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold = 2;
+        let participants = generate_participants(3);
+        let keys = run_keygen::<C, _>(&participants, threshold, &mut rng);
+        let pk = keys[0].1.public_key;
+
+        // the commitment of a reshare participant, whose constant term commits
+        // to its share weighted by its Lagrange coefficient, see do_reshare
+        let mut commit = |secret| {
+            let polynomial =
+                Polynomial::<C>::generate_polynomial(Some(secret), threshold - 1, &mut rng)
+                    .unwrap();
+            VerifiableSecretSharingCommitment::new(
+                polynomial.commit_polynomial().unwrap().get_coefficients(),
+            )
+        };
+        let old_participants = ParticipantList::new(&participants).unwrap();
+        let old_commitments = keys
+            .iter()
+            .map(|(p, key)| {
+                let lambda = old_participants.lagrange::<C>(*p).unwrap();
+                commit(lambda * key.private_share.to_scalar())
+            })
+            .collect::<Vec<_>>();
+        let prev = public_key_from_commitments(old_commitments.iter().collect()).unwrap();
+        assert_eq!(prev, pk);
+
+        // a participant joining the reshare commits to a zero constant term
+        let joining = commit(Secp256K1ScalarField::zero());
+        let updated = update_public_key(prev, &[], &[joining.clone()]).unwrap();
+        let all_commitments = old_commitments.iter().chain([&joining]).collect();
+        assert_eq!(
+            updated,
+            public_key_from_commitments(all_commitments).unwrap()
+        );
+
+        // swapping a member
+        let fresh = commit(Secp256K1ScalarField::one());
+        let swapped = update_public_key(prev, &old_commitments[..1], &[fresh.clone()]).unwrap();
+        let all_commitments = old_commitments[1..].iter().chain([&fresh]).collect();
+        assert_eq!(
+            swapped,
+            public_key_from_commitments(all_commitments).unwrap()
+        );
+
+        let mut new_participants = participants.clone();
+        new_participants.push(Participant::from(3u32));
+        let reshared = run_reshare(
+            &participants,
+            &pk,
+            &keys,
+            threshold,
+            threshold,
+            &new_participants,
+            &mut rng,
+        );
+        assert_public_key_invariant(&reshared);
+        assert_eq!(reshared[0].1.public_key, updated);
+
+        // removing and adding the same commitments is a no-op
+        assert_eq!(
+            update_public_key(prev, &old_commitments, &old_commitments).unwrap(),
+            prev
+        );
+
+        let empty = VerifiableSecretSharingCommitment::new(vec![]);
+        assert_eq!(
+            update_public_key(prev, &[], &[empty]),
+            Err(ProtocolError::IncorrectNumberOfCommitments)
+        );
+    }
+
+    #[test]
+    fn test_small_order_public_key_is_rejected() {
+        type C = JubjubBlake2b512;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let coefficients = (0..3)
+            .map(|_| {
+                let polynomial = Polynomial::<C>::generate_polynomial(None, 1, &mut rng).unwrap();
+                polynomial.commit_polynomial().unwrap().get_coefficients()
+            })
+            .collect::<Vec<_>>();
+        let honest = coefficients
+            .iter()
+            .cloned()
+            .map(VerifiableSecretSharingCommitment::new)
+            .collect::<Vec<_>>();
+        let prev = public_key_from_commitments(honest.iter().collect()).unwrap();
+
+        // the point (0, -1) of order 2, outside of the prime order subgroup
+        let bytes: [u8; 32] =
+            hex::decode("00000000fffffffffe5bfeff02a4bd5305d8a10908d83933487d9d2953a7ed73")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let small_order = Option::<Element<C>>::from(Element::<C>::from_bytes(&bytes)).unwrap();
+
+        // a participant shifting its constant term by the small order point
+        let mut tampered = coefficients[0].clone();
+        tampered[0] = CoefficientCommitment::new(tampered[0].value() + small_order);
+        let malicious = VerifiableSecretSharingCommitment::new(tampered);
+        let all_commitments = honest[1..].iter().chain([&malicious]).collect();
+        assert_eq!(
+            public_key_from_commitments(all_commitments),
+            Err(ProtocolError::MalformedElement)
+        );
+        assert_eq!(
+            update_public_key(prev, &honest[..1], &[malicious]),
+            Err(ProtocolError::MalformedElement)
+        );
+    }
+
     fn compute_private_key<C: Ciphersuite>(
         keygen_result: &GenOutput<C>,
     ) -> <<C::Group as Group>::Field as Field>::Scalar {
@@ -715,6 +1821,149 @@ pub mod test {
         x
     }
 
+    #[test]
+    fn test_keygen_additive() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let mut protocols: GenProtocol<AdditiveKeygenOutput<Secp256K1Sha256>> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let protocol = keygen_additive::<Secp256K1Sha256>(
+                &participants,
+                *p,
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let keys = run_protocol(protocols)
+            .unwrap()
+            .into_iter()
+            .map(|(_, key)| key)
+            .collect::<Vec<_>>();
+        assert!(keys.iter().all(|key| key.public_key == keys[0].public_key));
+
+        // the secret key is the plain sum of the shares
+        let x = reconstruct_additive_secret(&keys);
+        assert_eq!(
+            Secp256K1Group::generator() * x,
+            keys[0].public_key.to_element()
+        );
+
+        // a single participant cannot run it on its own
+        assert!(keygen_additive::<Secp256K1Sha256>(
+            &participants[..1],
+            participants[0],
+            MockCryptoRng::seed_from_u64(42)
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "custom-executor")]
+    #[test]
+    fn test_keygen_with_channel() {
+        let participants = generate_participants(4);
+        let threshold = 3;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        // the bare futures run over a transport and an executor foreign to the crate
+        let futures = MemoryChannel::connect(&participants)
+            .into_iter()
+            .map(|(p, chan)| {
+                let fut = keygen_with_channel::<Secp256K1Sha256>(
+                    chan,
+                    &participants,
+                    p,
+                    threshold,
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                )
+                .unwrap();
+                (p, fut)
+            })
+            .collect();
+        let keys = run_futures(futures).unwrap();
+        assert_public_key_invariant(&keys);
+
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        assert_eq!(
+            keys,
+            run_keygen::<Secp256K1Sha256, _>(&participants, threshold, &mut rng)
+        );
+    }
+
+    #[test]
+    fn test_keygen_with_session_id() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let session_id = [7u8; 32];
+        let keygen_protocols = |session_ids: &[[u8; 32]], rng: &mut MockCryptoRng| {
+            let mut protocols: GenProtocol<KeygenOutput<Secp256K1Sha256>> =
+                Vec::with_capacity(participants.len());
+            for (p, session_id) in participants.iter().zip(session_ids) {
+                let protocol = keygen_with_session_id::<Secp256K1Sha256>(
+                    &participants,
+                    *p,
+                    3,
+                    *session_id,
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                )
+                .unwrap();
+                protocols.push((*p, Box::new(protocol)));
+            }
+            protocols
+        };
+
+        let (keys, metrics) =
+            run_protocol_instrumented(keygen_protocols(&[session_id; 4], &mut rng)).unwrap();
+        assert_public_key_invariant(&keys);
+
+        // the round generating the session id at random is skipped
+        let mut protocols: GenProtocol<KeygenOutput<Secp256K1Sha256>> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let protocol = keygen::<Secp256K1Sha256>(
+                &participants,
+                *p,
+                3,
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let (_, random_metrics) = run_protocol_instrumented(protocols).unwrap();
+        assert!(metrics.send_many < random_metrics.send_many);
+
+        // the resharing can be bound to an external session id as well
+        let pub_key = keys[0].1.public_key;
+        let new_participants = &participants[1..];
+        let mut protocols: GenProtocol<KeygenOutput<Secp256K1Sha256>> =
+            Vec::with_capacity(participants.len());
+        for (p, key_pair) in keys.iter().filter(|(p, _)| new_participants.contains(p)) {
+            let protocol = reshare_with_session_id::<Secp256K1Sha256>(
+                &participants,
+                3,
+                Some(key_pair.private_share),
+                pub_key,
+                0,
+                new_participants,
+                2,
+                *p,
+                [8u8; 32],
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let reshared = run_protocol(protocols).unwrap();
+        assert_eq!(reshared.len(), new_participants.len());
+        assert_public_key_invariant(&reshared);
+        assert_eq!(reshared[0].1.public_key, pub_key);
+
+        // mismatching external session ids abort the protocol
+        let mut session_ids = [session_id; 4];
+        session_ids[3] = [9u8; 32];
+        assert!(run_protocol(keygen_protocols(&session_ids, &mut rng)).is_err());
+    }
+
     pub fn test_keygen<C: Ciphersuite, R: CryptoRngCore + SeedableRng + Send + 'static>(
         participants: &[Participant],
         threshold: impl Into<ReconstructionLowerBound> + Copy + Send + 'static,
@@ -774,6 +2023,8 @@ pub mod test {
 
         let result1 = run_refresh(participants, &result0, threshold, rng);
         assert_public_key_invariant(&result1);
+        // the refreshed shares are in the next epoch
+        assert!(result1.iter().all(|(_, key_pair)| key_pair.epoch == 1));
         let x1 = compute_private_key(&result1);
 
         assert_eq!(<C::Group as Group>::generator() * x1, pub_key0);
@@ -807,6 +2058,8 @@ pub mod test {
             rng,
         );
         assert_public_key_invariant(&result1);
+        // the reshared shares are in the next epoch, including the new participant's
+        assert!(result1.iter().all(|(_, key_pair)| key_pair.epoch == 1));
 
         let x1 = compute_private_key(&result1);
 
@@ -839,6 +2092,7 @@ pub mod test {
             threshold0,
             None,
             pub_key,
+            0,
             &participants,
             threshold1,
             participants[0],
@@ -856,4 +2110,90 @@ pub mod test {
         // These threshold parameters should work correctly
         test_reshare::<C, _>(&participants, 2, 2, rng);
     }
+
+    #[test]
+    fn test_committee_commitment() {
+        type C = Secp256K1Sha256;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        for n in 1..=7 {
+            let shares = generate_participants(n)
+                .into_iter()
+                .map(|p| {
+                    let secret = frost_core::random_nonzero::<C, _>(&mut rng);
+                    (p, VerifyingShare::<C>::from(SigningShare::new(secret)))
+                })
+                .collect::<Vec<_>>();
+            let (root, tree) = committee_commitment(&shares).unwrap();
+
+            // the root does not depend on the order of the shares
+            let mut reversed = shares.clone();
+            reversed.reverse();
+            assert_eq!(committee_commitment(&reversed).unwrap().0, root);
+
+            for (i, (p, share)) in shares.iter().enumerate() {
+                let proof = prove_membership(&tree, *p).unwrap();
+                assert!(verify_membership(&root, *p, share, &proof));
+                // another share or participant does not verify
+                let (other_p, other_share) = &shares[(i + 1) % n];
+                assert_eq!(
+                    verify_membership(&root, *p, other_share, &proof),
+                    other_p == p
+                );
+                assert_eq!(
+                    verify_membership(&root, *other_p, share, &proof),
+                    other_p == p
+                );
+            }
+            assert!(prove_membership(&tree, Participant::from(1234_u32)).is_none());
+        }
+
+        let share = VerifyingShare::<C>::from(SigningShare::new(Secp256K1ScalarField::one()));
+        let p = Participant::from(0_u32);
+        assert!(committee_commitment(&[(p, share), (p, share)]).is_err());
+        assert!(committee_commitment::<C>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_committee_key_package() {
+        type C = Secp256K1Sha256;
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let outputs = run_keygen::<C, _>(&participants, 3, &mut rng);
+        let package = CommitteeKeyPackage::from_keygen_outputs(&outputs, 3).unwrap();
+
+        assert_eq!(package.group_public_key(), &outputs[0].1.public_key);
+        assert_eq!(package.threshold(), 3);
+        assert_eq!(package.participants(), participants);
+        for (p, output) in &outputs {
+            assert_eq!(
+                package.verifying_share(*p),
+                Some(&VerifyingShare::from(output.private_share))
+            );
+        }
+        assert!(package
+            .verifying_share(Participant::from(1234_u32))
+            .is_none());
+
+        let json = serde_json::to_string(&package).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CommitteeKeyPackage<C>>(&json).unwrap(),
+            package
+        );
+        let unknown_version = json.replace("\"version\":1", "\"version\":2");
+        assert!(serde_json::from_str::<CommitteeKeyPackage<C>>(&unknown_version).is_err());
+        let bad_threshold = json.replace("\"threshold\":3", "\"threshold\":5");
+        assert!(serde_json::from_str::<CommitteeKeyPackage<C>>(&bad_threshold).is_err());
+
+        // mixing the outputs of two key generations
+        let mut mixed = outputs.clone();
+        mixed[0] = run_keygen::<C, _>(&participants, 3, &mut rng).remove(0);
+        assert!(CommitteeKeyPackage::from_keygen_outputs(&mixed, 3).is_err());
+        let mut duplicated = outputs.clone();
+        duplicated[1] = outputs[0].clone();
+        assert_eq!(
+            CommitteeKeyPackage::from_keygen_outputs(&duplicated, 3),
+            Err(InitializationError::DuplicateParticipants)
+        );
+        assert!(CommitteeKeyPackage::<C>::from_keygen_outputs(&[], 3).is_err());
+    }
 }
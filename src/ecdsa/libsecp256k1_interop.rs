@@ -0,0 +1,29 @@
+//! Cross-checks our ECDSA signatures with libsecp256k1, the verifier of Bitcoin nodes,
+//! which is stricter than ours on encodings, e.g. it rejects a high `s`.
+
+use elliptic_curve::sec1::ToEncodedPoint;
+use secp256k1::{ecdsa, Message, PublicKey, Secp256k1};
+
+use super::{AffinePoint, Scalar, Signature};
+
+/// Verifies `signature` of `msg_hash` under `public_key` with libsecp256k1.
+pub fn verify_with_libsecp256k1(
+    signature: &Signature,
+    public_key: &AffinePoint,
+    msg_hash: &Scalar,
+) -> bool {
+    let mut compact = [0u8; 64];
+    let (r, s) = compact.split_at_mut(32);
+    r.copy_from_slice(&signature.r_scalar().to_bytes());
+    s.copy_from_slice(&signature.s.to_bytes());
+    let Ok(signature) = ecdsa::Signature::from_compact(&compact) else {
+        return false;
+    };
+    let Ok(public_key) = PublicKey::from_slice(public_key.to_encoded_point(true).as_bytes()) else {
+        return false;
+    };
+    let message = Message::from_digest(msg_hash.to_bytes().into());
+    Secp256k1::verification_only()
+        .verify_ecdsa(&message, &signature, &public_key)
+        .is_ok()
+}
@@ -1,14 +1,19 @@
 //! This module serves as a wrapper for ECDSA scheme.
 
+#[cfg(all(test, feature = "libsecp256k1-interop"))]
+mod libsecp256k1_interop;
 pub mod ot_based_ecdsa;
 pub mod robust_ecdsa;
 
 use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use subtle::Choice;
 
 use elliptic_curve::{
-    bigint::U256,
-    ops::{Invert, Reduce},
-    point::AffineCoordinates,
+    bigint::{U256, U512},
+    ops::{Invert, LinearCombination, Reduce},
+    point::{AffineCoordinates, DecompressPoint},
     scalar::IsHigh,
     sec1::ToEncodedPoint,
     PrimeField,
@@ -16,10 +21,15 @@ use elliptic_curve::{
 
 use frost_secp256k1::{Field, Group, Secp256K1Group, Secp256K1ScalarField};
 use k256::{AffinePoint, ProjectivePoint};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
 
 use crate::crypto::ciphersuite::{BytesOrder, Ciphersuite, ScalarSerializationFormat};
-use crate::errors::ProtocolError;
+use crate::crypto::constants::NEAR_RERANDOMIZATION_ENTROPY_DOMAIN;
+use crate::crypto::polynomials::batch_invert;
+use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::ParticipantList;
+use crate::SignInput;
 
 pub use frost_secp256k1::Secp256K1Sha256;
 pub type KeygenOutput = crate::KeygenOutput<Secp256K1Sha256>;
@@ -36,13 +46,127 @@ impl ScalarSerializationFormat for Secp256K1Sha256 {
     }
 }
 
-impl Ciphersuite for Secp256K1Sha256 {}
+impl Ciphersuite for Secp256K1Sha256 {
+    fn scalar_from_uniform_bytes(bytes: &[u8; 64]) -> Scalar {
+        <Scalar as Reduce<U512>>::reduce_bytes(&(*bytes).into())
+    }
+}
 
 /// Get the x coordinate of a point, as a scalar
 pub(crate) fn x_coordinate(point: &AffinePoint) -> Scalar {
     <Scalar as Reduce<U256>>::reduce_bytes(&point.x())
 }
 
+/// Get the x coordinate of a point as a scalar, along with whether it was
+/// at least the group order and thus got reduced.
+///
+/// The flag is the overflow bit of a recovery id: the x coordinate of `R`
+/// cannot be recovered from `r` alone when it is set.
+pub fn x_coordinate_with_overflow(point: &AffinePoint) -> (Scalar, bool) {
+    let x = point.x();
+    let overflow = Scalar::from_repr(x).is_none().into();
+    (<Scalar as Reduce<U256>>::reduce_bytes(&x), overflow)
+}
+
+/// Rejects a presignature point whose x coordinate reduces to zero,
+/// as no valid signature can be produced from it
+pub(crate) fn assert_nonzero_x_coordinate(big_r: &AffinePoint) -> Result<(), ProtocolError> {
+    if x_coordinate(big_r).is_zero().into() {
+        return Err(ProtocolError::ZeroRCoordinate);
+    }
+    Ok(())
+}
+
+/// Returns whether the point has an even y coordinate, as required for the
+/// nonce commitment and the public key of BIP-340 (Taproot) signatures.
+pub fn has_even_y(point: &AffinePoint) -> bool {
+    !bool::from(point.y_is_odd())
+}
+
+/// Encodes a point as its 32-byte x coordinate, the x-only public key of BIP-340.
+///
+/// The parity of the y coordinate is dropped, see [`has_even_y`] to recover it.
+pub fn to_x_only(point: &AffinePoint) -> [u8; 32] {
+    point.x().into()
+}
+
+/// Decodes a BIP-340 x-only public key into the point with that x coordinate
+/// and an even y coordinate, or `None` if no such point is on the curve.
+pub fn from_x_only(bytes: &[u8; 32]) -> Option<AffinePoint> {
+    AffinePoint::decompress(&(*bytes).into(), Choice::from(0)).into()
+}
+
+/// Hashes a message into the scalar expected as `msg_hash` by the signers.
+///
+/// Computes SHA-256 over the message and reduces the digest modulo the group order,
+/// following <https://datatracker.ietf.org/doc/html/rfc9591#name-cryptographic-hash-function>
+pub fn hash_message(msg: &[u8]) -> Scalar {
+    let digest = Sha256::digest(msg);
+    <Scalar as Reduce<U256>>::reduce_bytes(&digest)
+}
+
+/// The hash of a message, as signed by the ECDSA signers.
+///
+/// It can only be obtained by hashing a message or from a digest, so that an
+/// arbitrary scalar, e.g. a private share, cannot be signed by mistake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHash(Scalar);
+
+impl MessageHash {
+    /// Hashes a message with SHA-256, see [`hash_message`].
+    pub fn from_message(msg: &[u8]) -> Self {
+        Self(hash_message(msg))
+    }
+
+    /// Wraps a digest computed by the caller, e.g. with Keccak-256,
+    /// reducing it modulo the group order.
+    pub fn from_prehashed(digest: [u8; 32]) -> Self {
+        Self(<Scalar as Reduce<U256>>::reduce_bytes(&digest.into()))
+    }
+
+    /// Returns the signed scalar, e.g. to verify a [`Signature`] over it.
+    pub const fn to_scalar(self) -> Scalar {
+        self.0
+    }
+}
+
+impl From<MessageHash> for SignInput {
+    fn from(msg_hash: MessageHash) -> Self {
+        Self::Prehashed(msg_hash.0.to_bytes().into())
+    }
+}
+
+impl TryFrom<SignInput> for MessageHash {
+    type Error = InitializationError;
+
+    /// Wraps a [`SignInput::Prehashed`] digest, rejecting [`SignInput::Raw`] messages.
+    fn try_from(input: SignInput) -> Result<Self, Self::Error> {
+        msg_hash_scalar(input).map(Self)
+    }
+}
+
+/// Maps the input of the ECDSA signers to the signed scalar,
+/// reducing the digest modulo the group order.
+pub(crate) fn msg_hash_scalar(input: SignInput) -> Result<Scalar, InitializationError> {
+    let digest = input.into_prehashed("ECDSA")?;
+    Ok(<Scalar as Reduce<U256>>::reduce_bytes(&digest.into()))
+}
+
+/// Hashes a message following the Ethereum personal-sign scheme (EIP-191, version 0x45).
+///
+/// Computes Keccak-256 over `"\x19Ethereum Signed Message:\n" || len(msg) || msg`,
+/// where the length is written in decimal, and reduces the digest modulo the group order.
+/// Signatures over the result can be recovered with Ethereum's `ecrecover`.
+pub fn hash_eip191(msg: &[u8]) -> Scalar {
+    const EIP191_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+    let digest = Keccak256::new()
+        .chain_update(EIP191_PREFIX)
+        .chain_update(msg.len().to_string().as_bytes())
+        .chain_update(msg)
+        .finalize();
+    <Scalar as Reduce<U256>>::reduce_bytes(&digest)
+}
+
 /// Represents a signature that supports different variants of ECDSA.
 ///
 /// An ECDSA signature is usually two scalars.
@@ -52,7 +176,7 @@ pub(crate) fn x_coordinate(point: &AffinePoint) -> Scalar {
 /// may thus include an extra information to recover this point.
 ///
 /// This signature supports all variants by containing `big_r` entirely
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Signature {
     /// This is the entire first point.
     pub big_r: AffinePoint,
@@ -61,6 +185,23 @@ pub struct Signature {
 }
 
 impl Signature {
+    /// Builds a signature from its parts, rejecting a zero `s` and an identity `big_r`,
+    /// as `from_scalars` does in `RustCrypto`.
+    pub fn new(big_r: AffinePoint, s: Scalar) -> Result<Self, ProtocolError> {
+        if big_r == AffinePoint::IDENTITY {
+            return Err(ProtocolError::IdentityElement);
+        }
+        if s.is_zero().into() {
+            return Err(ProtocolError::ZeroScalar);
+        }
+        Ok(Self { big_r, s })
+    }
+
+    /// Returns the first scalar `r` of the signature, i.e. the x-coordinate of `big_r`
+    pub fn r_scalar(&self) -> Scalar {
+        x_coordinate(&self.big_r)
+    }
+
     // This verification tests the signature including whether s has been normalized
     pub fn verify(&self, public_key: &AffinePoint, msg_hash: &Scalar) -> bool {
         let r: Scalar = x_coordinate(&self.big_r);
@@ -79,9 +220,102 @@ impl Signature {
     }
 }
 
+/// Verifies many signatures at once, given as `(public_key, msg_hash, signature)`,
+/// by checking a random linear combination of `s * big_r == msg_hash * G + r * X`.
+///
+/// A valid signature only fixes `big_r` up to its sign, as [`Signature::verify`] compares
+/// x-coordinates: normalizing `s` leaves `big_r` untouched, so that `s * big_r` is the
+/// negation of `msg_hash * G + r * X` whenever `s` was high. The combination cannot tell
+/// such a signature apart from an invalid one, so if it does not hold every signature is
+/// verified on its own and the index of the first invalid one is returned, and the result
+/// always matches [`Signature::verify`].
+pub fn batch_verify_ecdsa(
+    entries: &[(AffinePoint, Scalar, Signature)],
+    rng: &mut impl CryptoRngCore,
+) -> Result<(), usize> {
+    let mut generator_coefficient = Scalar::ZERO;
+    let mut combination = ProjectivePoint::IDENTITY;
+    for (i, (public_key, msg_hash, sig)) in entries.iter().enumerate() {
+        let r = sig.r_scalar();
+        if bool::from(r.is_zero() | sig.s.is_zero() | sig.s.is_high()) {
+            return Err(i);
+        }
+        let a = Secp256K1ScalarField::random(rng);
+        generator_coefficient += a * msg_hash;
+        combination += ProjectivePoint::lincomb(
+            &ProjectivePoint::from(sig.big_r),
+            &(a * sig.s),
+            &ProjectivePoint::from(*public_key),
+            &-(a * r),
+        );
+    }
+    if combination == ProjectivePoint::GENERATOR * generator_coefficient {
+        return Ok(());
+    }
+    entries
+        .iter()
+        .position(|(public_key, msg_hash, sig)| !sig.verify(public_key, msg_hash))
+        .map_or(Ok(()), Err)
+}
+
 /// None for participants and Some for coordinator
 pub type SignatureOption = Option<Signature>;
 
+/// Identifies a presignature, so that signing can reject a presignature
+/// fed to the wrong session.
+///
+/// Every participant derives the same identifier at the end of presigning,
+/// by hashing the fresh nonce commitment `big_r` with the sorted participants
+/// of the presigning session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PresignId(pub [u8; 32]);
+
+impl PresignId {
+    const DOMAIN: &'static [u8] = b"NEAR THRESHOLD SIGNATURES ECDSA PRESIGN ID";
+
+    pub fn derive(big_r: &AffinePoint, participants: &ParticipantList) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(Self::DOMAIN);
+        hasher.update(big_r.to_encoded_point(true).as_bytes());
+        for participant in participants.participants() {
+            hasher.update(participant.bytes());
+        }
+        Self(hasher.finalize().into())
+    }
+}
+
+// Encoded in hex for human readable formats, as the points and scalars of the presignature
+impl Serialize for PresignId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode_upper(self.0))
+        } else {
+            serde_bytes::Serialize::serialize(&self.0[..], serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PresignId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes = if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            hex::decode(encoded).map_err(serde::de::Error::custom)?
+        } else {
+            serde_bytes::Deserialize::deserialize(deserializer)?
+        };
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("a presign id is 32 bytes long"))?;
+        Ok(Self(bytes))
+    }
+}
+
 /// The arguments used to derive randomness used for presignature rerandomization.
 /// Presignature rerandomization has been thoroughly described in
 /// \[GS21\] <https://eprint.iacr.org/2021/1330.pdf>
@@ -132,6 +366,24 @@ impl RerandomizationArguments {
         }
     }
 
+    /// Derives the `entropy` of [`Self::new`] from a session identifier, the message hash
+    /// and the presignature R.
+    ///
+    /// Every signer computes the same entropy on its own, which removes the need for an
+    /// extra round to agree on it. The entropy is only as unpredictable as `session_id`,
+    /// which must be public, fresh, and unknown when the presignature was generated,
+    /// e.g. the hash of a block produced after presigning.
+    pub fn derive_entropy(session_id: &[u8], msg_hash: &[u8; 32], big_r: &AffinePoint) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(NEAR_RERANDOMIZATION_ENTROPY_DOMAIN);
+        // the session id has a variable length, hence the prefix
+        hasher.update((session_id.len() as u64).to_be_bytes());
+        hasher.update(session_id);
+        hasher.update(msg_hash);
+        hasher.update(big_r.to_encoded_point(true).as_bytes());
+        hasher.finalize().into()
+    }
+
     /// Derives a random string from the public key, tweak, message hash, presignature R,
     /// set of participants and the entropy.
     ///
@@ -186,24 +438,71 @@ impl RerandomizationArguments {
         }
         Ok(delta)
     }
+
+    /// Checks that the arguments belong to the presignature committing to `big_r`
+    /// and derives the nonzero rerandomization scalar `delta`.
+    pub(crate) fn derive_delta(&self, big_r: &AffinePoint) -> Result<Scalar, ProtocolError> {
+        if *big_r != self.big_r {
+            return Err(ProtocolError::IncompatibleRerandomizationInputs);
+        }
+        let delta = self.derive_randomness()?;
+        if delta.is_zero().into() {
+            return Err(ProtocolError::ZeroScalar);
+        }
+        Ok(delta)
+    }
+
+    /// Derives the rerandomization scalars of many presignatures, given their
+    /// commitments `big_rs`, along with their inverses computed in a single batch.
+    pub(crate) fn derive_deltas_and_inverses(
+        big_rs: &[AffinePoint],
+        args: &[Self],
+    ) -> Result<(Vec<Scalar>, Vec<Scalar>), ProtocolError> {
+        if big_rs.len() != args.len() {
+            return Err(ProtocolError::InvalidInput(
+                "expected one set of rerandomization arguments per presignature".to_string(),
+            ));
+        }
+        if big_rs.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let deltas = big_rs
+            .iter()
+            .zip(args)
+            .map(|(big_r, args)| args.derive_delta(big_r))
+            .collect::<Result<Vec<_>, _>>()?;
+        let inv_deltas = batch_invert::<Secp256K1Sha256>(&deltas)?;
+        Ok((deltas, inv_deltas))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
+        crypto::hash::test::scalar_hash_secp256k1,
         ecdsa::{
-            KeygenOutput, RerandomizationArguments, Scalar, Secp256K1Sha256, Signature, Tweak,
+            assert_nonzero_x_coordinate, batch_verify_ecdsa, from_x_only, has_even_y, hash_eip191,
+            hash_message, msg_hash_scalar, to_x_only, x_coordinate, x_coordinate_with_overflow,
+            AffinePoint, KeygenOutput, MessageHash, RerandomizationArguments, Scalar,
+            Secp256K1ScalarField, Secp256K1Sha256, Signature, Tweak,
         },
+        errors::{InitializationError, ProtocolError},
         participants::ParticipantList,
         test_utils::{
             ecdsa_generate_rerandpresig_args, generate_participants,
             generate_participants_with_random_ids, MockCryptoRng,
         },
+        SignInput,
     };
 
-    use elliptic_curve::ops::{Invert, LinearCombination, Reduce};
+    use elliptic_curve::{
+        bigint::{Encoding, U256},
+        ops::{Invert, LinearCombination, Reduce},
+        scalar::IsHigh,
+        sec1::ToEncodedPoint,
+    };
 
-    use frost_core::{keys::SigningShare, Ciphersuite, SigningKey as FrostSigningKey};
+    use frost_core::{keys::SigningShare, Ciphersuite, Field, SigningKey as FrostSigningKey};
 
     use k256::{
         ecdsa::{signature::Verifier, SigningKey},
@@ -212,6 +511,7 @@ mod test {
     use rand::SeedableRng;
     use rand_core::{CryptoRngCore, RngCore};
     use sha2::{digest::FixedOutput, Digest, Sha256};
+    use subtle::ConditionallySelectable;
     type C = Secp256K1Sha256;
 
     #[test]
@@ -247,6 +547,186 @@ mod test {
         assert!(is_verified);
     }
 
+    #[test]
+    fn test_signature_new() {
+        let big_r = (ProjectivePoint::GENERATOR * Scalar::from(42u64)).to_affine();
+        let sig = Signature::new(big_r, Scalar::ONE).unwrap();
+        assert_eq!(sig.r_scalar(), x_coordinate(&big_r));
+        assert_eq!(sig.s, Scalar::ONE);
+
+        assert_eq!(
+            Signature::new(big_r, Scalar::ZERO).err(),
+            Some(ProtocolError::ZeroScalar)
+        );
+        assert_eq!(
+            Signature::new(AffinePoint::IDENTITY, Scalar::ONE).err(),
+            Some(ProtocolError::IdentityElement)
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_ecdsa() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let mut entries = (0..8u64)
+            .map(|i| {
+                let x = Secp256K1ScalarField::random(&mut rng);
+                let k = Secp256K1ScalarField::random(&mut rng);
+                let public_key = (ProjectivePoint::GENERATOR * x).to_affine();
+                let big_r = (ProjectivePoint::GENERATOR * k).to_affine();
+                let msg_hash = Scalar::from(i + 1);
+                let mut s = k.invert().unwrap() * (msg_hash + x_coordinate(&big_r) * x);
+                s.conditional_assign(&(-s), s.is_high());
+                let sig = Signature::new(big_r, s).unwrap();
+                assert!(sig.verify(&public_key, &msg_hash));
+                (public_key, msg_hash, sig)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(batch_verify_ecdsa(&entries, &mut rng), Ok(()));
+        assert_eq!(batch_verify_ecdsa(&[], &mut rng), Ok(()));
+
+        // either sign of big_r is accepted
+        let flipped = entries
+            .iter()
+            .map(|(pk, h, sig)| (*pk, *h, Signature::new(-sig.big_r, sig.s).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(batch_verify_ecdsa(&flipped, &mut rng), Ok(()));
+
+        entries[5].1 += Scalar::ONE;
+        assert_eq!(batch_verify_ecdsa(&entries, &mut rng), Err(5));
+        entries[2].2.s = -entries[2].2.s;
+        assert_eq!(batch_verify_ecdsa(&entries, &mut rng), Err(2));
+    }
+
+    #[test]
+    fn test_has_even_y() {
+        let generator = ProjectivePoint::GENERATOR;
+        assert!(has_even_y(&generator.to_affine()));
+        assert!(!has_even_y(&(-generator).to_affine()));
+    }
+
+    #[test]
+    fn test_x_only_round_trip() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        for _ in 0..10 {
+            let point = (ProjectivePoint::GENERATOR * frost_core::random_nonzero::<C, _>(&mut rng))
+                .to_affine();
+            let x_only = to_x_only(&point);
+            // the x-only key is the compressed encoding without its prefix byte
+            assert_eq!(&x_only[..], &point.to_encoded_point(true).as_bytes()[1..]);
+
+            let lifted = from_x_only(&x_only).unwrap();
+            assert!(has_even_y(&lifted));
+            assert_eq!(to_x_only(&lifted), x_only);
+            if has_even_y(&point) {
+                assert_eq!(lifted, point);
+            } else {
+                assert_eq!(lifted, -point);
+            }
+        }
+        // not a field element, as it exceeds the field modulus
+        assert!(from_x_only(&[0xFF; 32]).is_none());
+    }
+
+    #[test]
+    fn test_assert_nonzero_x_coordinate() {
+        // the point whose x coordinate equals the group order reduces to r = 0
+        let order = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141";
+        let encoded = hex::decode(format!("02{order}")).unwrap();
+        let big_r = *k256::PublicKey::from_sec1_bytes(&encoded)
+            .unwrap()
+            .as_affine();
+        assert!(matches!(
+            assert_nonzero_x_coordinate(&big_r),
+            Err(ProtocolError::ZeroRCoordinate)
+        ));
+        assert!(assert_nonzero_x_coordinate(&ProjectivePoint::GENERATOR.to_affine()).is_ok());
+    }
+
+    #[test]
+    fn test_x_coordinate_with_overflow() {
+        let generator = ProjectivePoint::GENERATOR.to_affine();
+        assert_eq!(
+            x_coordinate_with_overflow(&generator),
+            (x_coordinate(&generator), false)
+        );
+
+        // x coordinates just above the group order, when they are on the curve
+        let order =
+            U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        let mut found = 0;
+        for excess in 0u64..64 {
+            let x = order.wrapping_add(&U256::from_u64(excess));
+            let mut encoded = vec![0x02];
+            encoded.extend_from_slice(&x.to_be_bytes());
+            let Ok(point) = k256::PublicKey::from_sec1_bytes(&encoded) else {
+                continue;
+            };
+            let (r, overflow) = x_coordinate_with_overflow(point.as_affine());
+            assert!(overflow);
+            assert_eq!(r, Scalar::from(excess));
+            assert_eq!(r, x_coordinate(point.as_affine()));
+            found += 1;
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_hash_message() {
+        let msg = b"Hello from Near";
+        assert_eq!(hash_message(msg), scalar_hash_secp256k1(msg));
+        assert_ne!(hash_message(msg), hash_eip191(msg));
+    }
+
+    #[test]
+    fn test_msg_hash_scalar() {
+        let msg_hash = hash_message(b"Hello from Near");
+        let input = MessageHash::from_message(b"Hello from Near").into();
+        assert_eq!(msg_hash_scalar(input), Ok(msg_hash));
+        let digest: [u8; 32] = Sha256::digest(b"Hello from Near").into();
+        assert_eq!(msg_hash_scalar(digest.into()), Ok(msg_hash));
+        // digests larger than the group order are reduced
+        assert_eq!(
+            msg_hash_scalar(SignInput::Prehashed([0xff; 32])),
+            Ok(<Scalar as Reduce<U256>>::reduce_bytes(&[0xffu8; 32].into()))
+        );
+        assert!(matches!(
+            msg_hash_scalar(SignInput::Raw(b"Hello from Near".to_vec())),
+            Err(InitializationError::BadParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_hash() {
+        let msg = b"Hello from Near";
+        let msg_hash = MessageHash::from_message(msg);
+        assert_eq!(msg_hash.to_scalar(), hash_message(msg));
+        let digest: [u8; 32] = Sha256::digest(msg).into();
+        assert_eq!(MessageHash::from_prehashed(digest), msg_hash);
+        assert_eq!(msg_hash_scalar(msg_hash.into()), Ok(msg_hash.to_scalar()));
+        assert_eq!(
+            MessageHash::try_from(SignInput::from(msg_hash)),
+            Ok(msg_hash)
+        );
+        assert!(MessageHash::try_from(SignInput::Raw(msg.to_vec())).is_err());
+        // digests larger than the group order are reduced
+        assert_eq!(
+            MessageHash::from_prehashed([0xff; 32]).to_scalar(),
+            <Scalar as Reduce<U256>>::reduce_bytes(&[0xffu8; 32].into())
+        );
+    }
+
+    #[test]
+    fn test_hash_eip191() {
+        // keccak256("\x19Ethereum Signed Message:\n11hello world")
+        let expected =
+            hex::decode("d9eba16ed0ecae432b71fe008c98cc872bb4cc214d3220a36f365326cf807d68")
+                .unwrap();
+        let expected = <Scalar as Reduce<<Secp256k1 as elliptic_curve::Curve>::Uint>>::reduce_bytes(
+            expected.as_slice().into(),
+        );
+        assert_eq!(hash_eip191(b"hello world"), expected);
+    }
+
     #[test]
     fn keygen_output_should_be_serializable() {
         // Given
@@ -256,6 +736,7 @@ mod test {
         let keygen_output = KeygenOutput {
             private_share: SigningShare::<C>::new(Scalar::ONE),
             public_key: frost_core::VerifyingKey::<C>::from(signing_key),
+            epoch: 0,
         };
 
         // When
@@ -372,6 +853,36 @@ mod test {
         assert_eq!(delta, delta_prime);
     }
 
+    #[test]
+    fn test_derive_entropy() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let (args, _) = compute_random_outputs(&mut rng, 10);
+        let entropy =
+            RerandomizationArguments::derive_entropy(b"session", &args.msg_hash, &args.big_r);
+        // every signer derives the same entropy
+        assert_eq!(
+            entropy,
+            RerandomizationArguments::derive_entropy(b"session", &args.msg_hash, &args.big_r)
+        );
+        assert_ne!(
+            entropy,
+            RerandomizationArguments::derive_entropy(b"other session", &args.msg_hash, &args.big_r)
+        );
+        assert_ne!(
+            entropy,
+            RerandomizationArguments::derive_entropy(b"session", &[0; 32], &args.big_r)
+        );
+        let (_, big_r) = <C>::generate_nonce(&mut rng);
+        assert_ne!(
+            entropy,
+            RerandomizationArguments::derive_entropy(
+                b"session",
+                &args.msg_hash,
+                &big_r.to_affine()
+            )
+        );
+    }
+
     #[test]
     fn test_keygen() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
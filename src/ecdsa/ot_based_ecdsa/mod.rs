@@ -6,18 +6,22 @@ pub mod sign;
 pub mod triples;
 
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
 
-use crate::errors::ProtocolError;
+use crate::errors::{InitializationError, ProtocolError};
 use crate::{
+    crypto::secret::zeroize_on_drop,
     ecdsa::{
+        has_even_y,
         ot_based_ecdsa::triples::{TriplePub, TripleShare},
-        AffinePoint, KeygenOutput, RerandomizationArguments, Scalar,
+        AffinePoint, KeygenOutput, PresignId, ProjectivePoint, RerandomizationArguments, Scalar,
+        Secp256K1Sha256,
     },
+    participants::{Participant, ParticipantList},
     ReconstructionLowerBound,
 };
 use serde::{Deserialize, Serialize};
-use zeroize::ZeroizeOnDrop;
+use std::collections::BTreeMap;
 
 /// The arguments needed to create a presignature.
 #[derive(Debug, Clone)]
@@ -31,53 +35,208 @@ pub struct PresignArguments {
     pub keygen_out: KeygenOutput,
     /// The desired threshold for the presignature, which must match the original threshold
     pub threshold: ReconstructionLowerBound,
+    /// Whether to record the [`PresignProvenance`] in the output, for auditing.
+    pub record_provenance: bool,
+}
+
+impl PresignArguments {
+    /// Records the [`PresignProvenance`] in the output of presigning.
+    pub fn with_provenance(self) -> Self {
+        Self {
+            record_provenance: true,
+            ..self
+        }
+    }
+
+    /// Checks that the public parts of both triples are well formed,
+    /// before spending a presigning round on them.
+    pub fn validate(&self) -> Result<(), InitializationError> {
+        for (name, (_, triple_pub)) in [("first", &self.triple0), ("second", &self.triple1)] {
+            triple_pub.verify().map_err(|err| {
+                InitializationError::BadParameters(format!("{name} triple is malformed: {err}"))
+            })?;
+        }
+        Ok(())
+    }
 }
 
 /// The output of the presigning protocol.
 ///
 /// This output is basically all the parts of the signature that we can perform
 /// without knowing the message.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, ZeroizeOnDrop)]
+///
+/// Every field is always serialized, as the MessagePack encoding is positional.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct PresignOutput {
     /// The public nonce commitment.
-    #[zeroize[skip]]
     pub big_r: AffinePoint,
     /// Our share of the nonce value.
     pub k: Scalar,
     /// Our share of the sigma value.
     pub sigma: Scalar,
+    /// The identifier of the presignature, the same for every participant.
+    pub id: PresignId,
+    /// What produced the presignature, only recorded if requested by
+    /// [`PresignArguments::record_provenance`].
+    #[serde(default)]
+    pub provenance: Option<PresignProvenance>,
+    /// The epoch of the key shares the presignature was made with.
+    #[serde(default)]
+    pub epoch: u64,
+}
+
+zeroize_on_drop!(PresignOutput, [k, sigma]);
+
+/// Records which triples and participants produced a presignature, for auditing.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PresignProvenance {
+    /// The identifiers of the two triples consumed, see [`TriplePub::id`].
+    pub triple_ids: [[u8; 32]; 2],
+    /// The participants of the presigning.
+    pub participants: Vec<Participant>,
+    /// The participants which generated each of the two triples.
+    pub bt_participants: [Vec<Participant>; 2],
+}
+
+/// The public messages of a presigning run, checked offline by
+/// [`presign::verify_presign_transcript`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresignTranscript {
+    /// The public parts of the two consumed triples.
+    pub triple0: TriplePub,
+    pub triple1: TriplePub,
+    /// The share of `kd` sent by each participant in the first round.
+    pub kd_shares: BTreeMap<Participant, Scalar>,
+    /// The shares of `ka` and `xb` sent by each participant in the second round.
+    pub ka_xb_shares: BTreeMap<Participant, (Scalar, Scalar)>,
+}
+
+impl PresignOutput {
+    /// Negates the nonce shares if needed so that `big_r` has an even y coordinate.
+    ///
+    /// As every participant holds the same `big_r`, applying this locally on each
+    /// share keeps the presignature consistent: `-R` is the commitment to `-k`.
+    pub fn force_even_y(&mut self) {
+        if has_even_y(&self.big_r) {
+            return;
+        }
+        self.big_r = -self.big_r;
+        self.k = -self.k;
+        self.sigma = -self.sigma;
+    }
+
+    /// Checks that all `outputs` agree on `big_r`, and that it is `k^{-1} * G` for the nonce
+    /// `k` interpolated from their shares.
+    ///
+    /// This needs the secret `k` shares of at least `threshold` participants of the same
+    /// presigning, hence is meant for audits and tests rather than for a single signer.
+    pub fn verify_big_r(
+        outputs: &[(Participant, Self)],
+        threshold: impl Into<ReconstructionLowerBound>,
+    ) -> Result<(), ProtocolError> {
+        let needed = threshold.into().value();
+        if outputs.len() < needed {
+            return Err(ProtocolError::InsufficientSigners {
+                got: outputs.len(),
+                needed,
+            });
+        }
+        let Some((_, first)) = outputs.first() else {
+            return Err(ProtocolError::InvalidInput(
+                "expected at least one presignature".to_string(),
+            ));
+        };
+        if outputs
+            .iter()
+            .any(|(_, output)| output.big_r != first.big_r)
+        {
+            return Err(ProtocolError::AssertionFailed(
+                "the presignatures disagree on big_r".to_string(),
+            ));
+        }
+
+        let participants = outputs.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        let participants = ParticipantList::new(&participants).ok_or_else(|| {
+            ProtocolError::InvalidInput("a participant appears twice".to_string())
+        })?;
+        let mut k = Scalar::ZERO;
+        for (p, output) in outputs {
+            k += participants.lagrange::<Secp256K1Sha256>(*p)? * output.k;
+        }
+        let k_inv: Option<Scalar> = k.invert().into();
+        let k_inv = k_inv.ok_or(ProtocolError::ZeroScalar)?;
+        if (ProjectivePoint::GENERATOR * k_inv).to_affine() != first.big_r {
+            return Err(ProtocolError::AssertionFailed(
+                "big_r does not match the nonce shares".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// The output of the presigning protocol.
 /// Contains the signature precomputed elements
 /// independently of the message
-#[derive(Debug, Clone, Serialize, Deserialize, ZeroizeOnDrop)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RerandomizedPresignOutput {
     /// The rerandomized public nonce commitment.
-    #[zeroize[skip]]
     pub big_r: AffinePoint,
     /// Our rerandomized share of the nonce value.
     pub k: Scalar,
     /// Our rerandomized share of the sigma value.
     pub sigma: Scalar,
+    /// The identifier of the original presignature.
+    pub id: PresignId,
+    /// The epoch of the key shares the original presignature was made with.
+    #[serde(default, skip_serializing_if = "crate::is_initial_epoch")]
+    pub epoch: u64,
 }
 
+zeroize_on_drop!(RerandomizedPresignOutput, [k, sigma]);
+
 impl RerandomizedPresignOutput {
     pub fn rerandomize_presign(
         presignature: &PresignOutput,
         args: &RerandomizationArguments,
     ) -> Result<Self, ProtocolError> {
-        if presignature.big_r != args.big_r {
-            return Err(ProtocolError::IncompatibleRerandomizationInputs);
-        }
-        let delta = args.derive_randomness()?;
-        if delta.is_zero().into() {
-            return Err(ProtocolError::ZeroScalar);
-        }
+        let delta = args.derive_delta(&presignature.big_r)?;
 
         // cannot be zero due to the previous check
         let inv_delta = delta.invert().unwrap();
 
+        Ok(Self::rerandomize_with(presignature, args, delta, inv_delta))
+    }
+
+    /// Rerandomizes a pool of presignatures, `presignatures[i]` with `args[i]`.
+    ///
+    /// The outputs match [`Self::rerandomize_presign`] item by item, but all the
+    /// `delta^{-1}` are computed with a single field inversion.
+    pub fn rerandomize_presign_many(
+        presignatures: &[PresignOutput],
+        args: &[RerandomizationArguments],
+    ) -> Result<Vec<Self>, ProtocolError> {
+        let big_rs = presignatures
+            .iter()
+            .map(|presignature| presignature.big_r)
+            .collect::<Vec<_>>();
+        let (deltas, inv_deltas) =
+            RerandomizationArguments::derive_deltas_and_inverses(&big_rs, args)?;
+        Ok(presignatures
+            .iter()
+            .zip(args)
+            .zip(deltas.into_iter().zip(inv_deltas))
+            .map(|((presignature, args), (delta, inv_delta))| {
+                Self::rerandomize_with(presignature, args, delta, inv_delta)
+            })
+            .collect())
+    }
+
+    fn rerandomize_with(
+        presignature: &PresignOutput,
+        args: &RerandomizationArguments,
+        delta: Scalar,
+        inv_delta: Scalar,
+    ) -> Self {
         // delta . R
         let rerandomized_big_r = presignature.big_r * delta;
 
@@ -88,11 +247,13 @@ impl RerandomizedPresignOutput {
         // k * delta^{-1}
         let rerandomized_k = presignature.k * inv_delta;
 
-        Ok(Self {
+        Self {
             big_r: rerandomized_big_r.into(),
             k: rerandomized_k,
             sigma: rerandomized_sigma,
-        })
+            id: presignature.id,
+            epoch: presignature.epoch,
+        }
     }
 
     #[cfg(test)]
@@ -103,6 +264,8 @@ impl RerandomizedPresignOutput {
             big_r: presignature.big_r,
             k: presignature.k,
             sigma: presignature.sigma,
+            id: presignature.id,
+            epoch: presignature.epoch,
         }
     }
 }
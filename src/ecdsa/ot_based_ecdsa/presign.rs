@@ -1,12 +1,21 @@
-use super::{PresignArguments, PresignOutput};
-use crate::ecdsa::{ProjectivePoint, Scalar, Secp256K1Sha256};
+use super::{
+    triples::TriplePub, PresignArguments, PresignOutput, PresignProvenance, PresignTranscript,
+};
+use crate::ecdsa::{
+    assert_nonzero_x_coordinate, AffinePoint, Field, PresignId, ProjectivePoint, Scalar,
+    Secp256K1ScalarField, Secp256K1Sha256,
+};
 use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::{Participant, ParticipantList};
 use crate::protocol::helpers::recv_from_others;
 use crate::protocol::{
-    internal::{make_protocol, Comms, SharedChannel},
-    Protocol,
+    internal::{make_protocol, Comms},
+    Channel, Protocol,
 };
+use crate::thresholds::{validate_threshold, Scheme};
+use frost_secp256k1::VerifyingKey;
+use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
 
 type Secp256 = Secp256K1Sha256;
 
@@ -22,11 +31,59 @@ pub fn presign(
     me: Participant,
     args: PresignArguments,
 ) -> Result<impl Protocol<Output = PresignOutput>, InitializationError> {
+    let participants = assert_presign_inputs(participants, me, &args)?;
+
+    let ctx = Comms::new();
+    let fut = do_presign(ctx.shared_channel(), participants, me, args);
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Like [`presign`], but returns the protocol core as a future exchanging its
+/// messages over `chan`, to be awaited on the caller's own executor.
+#[cfg(feature = "custom-executor")]
+pub fn presign_with_channel(
+    chan: impl Channel,
+    participants: &[Participant],
+    me: Participant,
+    args: PresignArguments,
+) -> Result<
+    impl std::future::Future<Output = Result<PresignOutput, ProtocolError>>,
+    InitializationError,
+> {
+    let participants = assert_presign_inputs(participants, me, &args)?;
+    Ok(do_presign(chan, participants, me, args))
+}
+
+/// Like [`presign`], but also outputs the public messages of the run, to be
+/// checked later with [`verify_presign_transcript`].
+pub fn presign_with_transcript(
+    participants: &[Participant],
+    me: Participant,
+    args: PresignArguments,
+) -> Result<impl Protocol<Output = (PresignOutput, PresignTranscript)>, InitializationError> {
+    let participants = assert_presign_inputs(participants, me, &args)?;
+
+    let ctx = Comms::new();
+    let fut = do_presign_with_transcript(ctx.shared_channel(), participants, me, args);
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Checks the participants of a presigning session against the triples it consumes
+fn assert_presign_inputs(
+    participants: &[Participant],
+    me: Participant,
+    args: &PresignArguments,
+) -> Result<ParticipantList, InitializationError> {
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
         });
     }
+    args.validate()?;
+
     // Spec 1.1
     if args.threshold.value() > participants.len() {
         return Err(InitializationError::ThresholdTooLarge {
@@ -34,6 +91,11 @@ pub fn presign(
             max: participants.len(),
         });
     }
+    validate_threshold(
+        Scheme::OtBasedEcdsa,
+        participants.len(),
+        args.threshold.value(),
+    )?;
 
     // NOTE: We omit the check that the new participant set was present for
     // the triple generation, because presumably they need to have been present
@@ -55,39 +117,46 @@ pub fn presign(
             participant: me,
         });
     }
-
-    let ctx = Comms::new();
-    let fut = do_presign(ctx.shared_channel(), participants, me, args);
-    Ok(make_protocol(ctx, fut))
+    Ok(participants)
 }
 
 async fn do_presign(
-    mut chan: SharedChannel,
+    chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     args: PresignArguments,
 ) -> Result<PresignOutput, ProtocolError> {
+    let (output, _) = do_presign_with_transcript(chan, participants, me, args).await?;
+    Ok(output)
+}
+
+/// Runs the presigning protocol, recording the public messages of the run.
+async fn do_presign_with_transcript(
+    mut chan: impl Channel,
+    participants: ParticipantList,
+    me: Participant,
+    args: PresignArguments,
+) -> Result<(PresignOutput, PresignTranscript), ProtocolError> {
+    let provenance = args.record_provenance.then(|| PresignProvenance {
+        triple_ids: [args.triple0.1.id(), args.triple1.1.id()],
+        participants: participants.participants().to_vec(),
+        bt_participants: [
+            args.triple0.1.participants.clone(),
+            args.triple1.1.participants.clone(),
+        ],
+    });
+
     // Round 1
     // Extracting triples private variables (ai, bi, ci)
     let a_i = args.triple1.0.a;
     let b_i = args.triple1.0.b;
     let c_i = args.triple1.0.c;
 
-    // Extracting triples public variables (A, B, _)
-    // notice C is not used
-    let big_a: ProjectivePoint = args.triple1.1.big_a.into();
-    let big_b: ProjectivePoint = args.triple1.1.big_b.into();
-
     // Extracting triples private variables (ki, _, ei)
     // notice di is not used
     let k_i = args.triple0.0.a;
     let e_i = args.triple0.0.c;
 
-    // Extracting triples public variables (K, D, E)
-    let big_k: ProjectivePoint = args.triple0.1.big_a.into();
-    let big_d = args.triple0.1.big_b;
-    let big_e = args.triple0.1.big_c;
-
     // linearize ki ei ai bi ci xi
     // Spec 1.1
     let lambda_me = participants.lagrange::<Secp256>(me)?;
@@ -98,7 +167,6 @@ async fn do_presign(
     let a_prime_i = lambda_me * a_i;
     let b_prime_i = lambda_me * b_i;
 
-    let big_x: ProjectivePoint = args.keygen_out.public_key.to_element();
     let private_share = args.keygen_out.private_share.to_scalar();
     let x_prime_i = lambda_me * private_share;
 
@@ -107,28 +175,15 @@ async fn do_presign(
     let wait0 = chan.next_waitpoint();
     chan.send_many(wait0, &e_i)?;
 
-    // Receive ej and compute e = SUM_j ej
+    // Receive ej
     // Spec 1.3
-    let mut e = e_i;
-
-    for (_, e_j) in recv_from_others::<Scalar>(&chan, wait0, &participants, me).await? {
-        if e_j.is_zero().into() {
-            return Err(ProtocolError::AssertionFailed(
-                "Received zero share of kd, indicating a triple wasn't available.".to_string(),
-            ));
-        }
-
-        // Spec 1.4
-        e += e_j;
+    let mut kd_shares = BTreeMap::from([(me, e_i)]);
+    for (from, e_j) in recv_from_others::<ByteBuf>(&chan, wait0, &participants, me).await? {
+        kd_shares.insert(from, deserialize_canonical_scalar(from, &e_j)?);
     }
 
-    // E =?= e*G
-    // Spec 1.5
-    if big_e != (ProjectivePoint::GENERATOR * e).to_affine() {
-        return Err(ProtocolError::AssertionFailed(
-            "received incorrect shares of kd".to_string(),
-        ));
-    }
+    // Spec 1.4 and 1.5
+    let e = sum_kd_shares(&args.triple0.1, &kd_shares)?;
 
     // Round 2
     // alphai = ki' + ai'
@@ -142,55 +197,180 @@ async fn do_presign(
     let wait1 = chan.next_waitpoint();
     chan.send_many(wait1, &(alpha_i, beta_i))?;
 
-    // Receive and compute alpha = SUM_j alphaj
-    // Receive and compute beta = SUM_j betaj
+    // Receive alphaj and betaj
     // Spec 2.3
-    let mut alpha = alpha_i;
-    let mut beta = beta_i;
+    let mut ka_xb_shares = BTreeMap::from([(me, (alpha_i, beta_i))]);
+    for (from, (alpha_j, beta_j)) in
+        recv_from_others::<(ByteBuf, ByteBuf)>(&chan, wait1, &participants, me).await?
+    {
+        ka_xb_shares.insert(
+            from,
+            (
+                deserialize_canonical_scalar(from, &alpha_j)?,
+                deserialize_canonical_scalar(from, &beta_j)?,
+            ),
+        );
+    }
 
-    for (_, (alpha_j, beta_j)) in
-        recv_from_others::<(Scalar, Scalar)>(&chan, wait1, &participants, me).await?
+    // Spec 2.4 and 2.5
+    let (alpha, beta) = sum_ka_xb_shares(
+        &args.triple0.1,
+        &args.triple1.1,
+        &args.keygen_out.public_key,
+        &ka_xb_shares,
+    )?;
+
+    // Spec 2.6
+    let big_r = compute_big_r(&args.triple0.1, e)?;
+
+    // sigmai = alpha*xi - beta*ai + ci
+    // Spec 2.7
+    let sigma_i = alpha * private_share - (beta * a_i - c_i);
+
+    let output = PresignOutput {
+        big_r,
+        k: k_i,
+        sigma: sigma_i,
+        id: PresignId::derive(&big_r, &participants),
+        provenance,
+        epoch: args.keygen_out.epoch,
+    };
+    let transcript = PresignTranscript {
+        triple0: args.triple0.1.clone(),
+        triple1: args.triple1.1.clone(),
+        kd_shares,
+        ka_xb_shares,
+    };
+    Ok((output, transcript))
+}
+
+/// Checks offline that the messages of a presigning run satisfy the
+/// equations checked live by [`presign`], without any network.
+///
+/// This lets auditors confirm that a successful run was consistent with
+/// the consumed triples and the public key. Transcripts are recorded
+/// with [`presign_with_transcript`].
+pub fn verify_presign_transcript(
+    transcript: &PresignTranscript,
+    participants: &[Participant],
+    public_key: &VerifyingKey,
+) -> Result<(), ProtocolError> {
+    let participants = ParticipantList::new(participants)
+        .ok_or_else(|| ProtocolError::InvalidInput("duplicate participants".to_string()))?;
+    if !participants
+        .participants()
+        .iter()
+        .eq(transcript.kd_shares.keys())
+        || !participants
+            .participants()
+            .iter()
+            .eq(transcript.ka_xb_shares.keys())
     {
-        // Spec 2.4
-        alpha += alpha_j;
-        beta += beta_j;
+        return Err(ProtocolError::InvalidInput(
+            "the transcript does not hold one message per participant".to_string(),
+        ));
     }
+    transcript.triple0.verify()?;
+    transcript.triple1.verify()?;
+
+    let e = sum_kd_shares(&transcript.triple0, &transcript.kd_shares)?;
+    sum_ka_xb_shares(
+        &transcript.triple0,
+        &transcript.triple1,
+        public_key,
+        &transcript.ka_xb_shares,
+    )?;
+    compute_big_r(&transcript.triple0, e)?;
+    Ok(())
+}
+
+/// Sums the shares of `e = kd`, checking that none is zero and that `E = e*G`.
+fn sum_kd_shares(
+    triple0: &TriplePub,
+    kd_shares: &BTreeMap<Participant, Scalar>,
+) -> Result<Scalar, ProtocolError> {
+    if kd_shares.values().any(|e_j| e_j.is_zero().into()) {
+        return Err(ProtocolError::AssertionFailed(
+            "Received zero share of kd, indicating a triple wasn't available.".to_string(),
+        ));
+    }
+    // e = SUM_j ej
+    let e: Scalar = kd_shares.values().sum();
+
+    // E =?= e*G
+    if triple0.big_c != (ProjectivePoint::GENERATOR * e).to_affine() {
+        return Err(ProtocolError::AssertionFailed(
+            "received incorrect shares of kd".to_string(),
+        ));
+    }
+    Ok(e)
+}
+
+/// Sums the shares of `alpha = ka` and `beta = xb`, checking that
+/// `alpha*G = K + A` and `beta*G = X + B`.
+fn sum_ka_xb_shares(
+    triple0: &TriplePub,
+    triple1: &TriplePub,
+    public_key: &VerifyingKey,
+    ka_xb_shares: &BTreeMap<Participant, (Scalar, Scalar)>,
+) -> Result<(Scalar, Scalar), ProtocolError> {
+    // alpha = SUM_j alphaj
+    // beta = SUM_j betaj
+    let alpha: Scalar = ka_xb_shares.values().map(|(alpha_j, _)| alpha_j).sum();
+    let beta: Scalar = ka_xb_shares.values().map(|(_, beta_j)| beta_j).sum();
 
     // alpha*G =?= K + A
     // beta*G =?= X + B
-    // Spec 2.5
+    let big_k: ProjectivePoint = triple0.big_a.into();
+    let big_a: ProjectivePoint = triple1.big_a.into();
+    let big_b: ProjectivePoint = triple1.big_b.into();
     if (ProjectivePoint::GENERATOR * alpha != big_k + big_a)
-        || (ProjectivePoint::GENERATOR * beta != big_x + big_b)
+        || (ProjectivePoint::GENERATOR * beta != public_key.to_element() + big_b)
     {
         return Err(ProtocolError::AssertionFailed(
             "received incorrect shares of additive triple phase.".to_string(),
         ));
     }
+    Ok((alpha, beta))
+}
 
-    // Compute R = 1/e * D
-    // Spec 2.6
+/// Computes `R = 1/e * D`, rejecting it if it yields a zero signature part `r`.
+fn compute_big_r(triple0: &TriplePub, e: Scalar) -> Result<AffinePoint, ProtocolError> {
     let e_inv: Option<Scalar> = e.invert().into();
     let e_inv =
         e_inv.ok_or_else(|| ProtocolError::AssertionFailed("failed to invert kd".to_string()))?;
-    let big_r = (big_d * e_inv).into();
-
-    // sigmai = alpha*xi - beta*ai + ci
-    // Spec 2.7
-    let sigma_i = alpha * private_share - (beta * a_i - c_i);
+    let big_r = (triple0.big_b * e_inv).into();
+    assert_nonzero_x_coordinate(&big_r)?;
+    Ok(big_r)
+}
 
-    Ok(PresignOutput {
-        big_r,
-        k: k_i,
-        sigma: sigma_i,
-    })
+/// Deserializes a scalar received from a participant using the ciphersuite's
+/// deserialization, rejecting any encoding that is not the canonical
+/// (fully reduced) representation of a scalar.
+///
+/// The bytes are received raw, which is wire compatible with
+/// sending a `Scalar`, so that the sender can be identified on failure.
+fn deserialize_canonical_scalar(from: Participant, bytes: &[u8]) -> Result<Scalar, ProtocolError> {
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| ProtocolError::ErrorReducingBytesToScalar(from))?;
+    <Secp256K1ScalarField as Field>::deserialize(&bytes)
+        .map_err(|_| ProtocolError::ErrorReducingBytesToScalar(from))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    #[cfg(feature = "custom-executor")]
+    use crate::test_utils::{run_futures, MemoryChannel};
     use crate::{
-        ecdsa::{ot_based_ecdsa::triples::test::deal, KeygenOutput, Polynomial, ProjectivePoint},
-        test_utils::{generate_participants, run_protocol, GenProtocol, MockCryptoRng},
+        ecdsa::{
+            ot_based_ecdsa::triples::{test::deal, TriplePub},
+            AffinePoint, KeygenOutput, Polynomial, ProjectivePoint,
+        },
+        test_utils::{
+            assert_serde_roundtrip, generate_participants, run_protocol, GenProtocol, MockCryptoRng,
+        },
     };
     use frost_secp256k1::{
         keys::{PublicKeyPackage, SigningShare},
@@ -230,6 +410,7 @@ mod test {
             let keygen_out = KeygenOutput {
                 private_share: SigningShare::new(private_share),
                 public_key: *public_key_package.verifying_key(),
+                epoch: 0,
             };
 
             let protocol = presign(
@@ -240,6 +421,7 @@ mod test {
                     triple1: (triple1, triple1_pub.clone()),
                     keygen_out,
                     threshold: threshold.into(),
+                    record_provenance: false,
                 },
             )
             .unwrap();
@@ -249,22 +431,383 @@ mod test {
         let result = run_protocol(protocols).unwrap();
 
         assert!(result.len() == 3);
-        assert_eq!(result[0].1.big_r, result[1].1.big_r);
-        assert_eq!(result[1].1.big_r, result[2].1.big_r);
-
-        let big_k = result[2].1.big_r;
+        for (_, output) in &result {
+            assert_serde_roundtrip(output);
+        }
+        PresignOutput::verify_big_r(&result, threshold).unwrap();
+        PresignOutput::verify_big_r(&result[..2], threshold).unwrap();
+        assert_eq!(
+            PresignOutput::verify_big_r(&result[..1], threshold),
+            Err(ProtocolError::InsufficientSigners { got: 1, needed: 2 })
+        );
+        // a tampered nonce share
+        let mut tampered = result.clone();
+        tampered[0].1.k += Scalar::ONE;
+        assert!(matches!(
+            PresignOutput::verify_big_r(&tampered, threshold),
+            Err(ProtocolError::AssertionFailed(_))
+        ));
 
+        // sigma = k * x, hence sigma * R = x * G as R = k^{-1} * G
         let participants = vec![result[0].0, result[1].0];
-        let k_shares = [result[0].1.k, result[1].1.k];
-        let sigma_shares = [result[0].1.sigma, result[1].1.sigma];
         let p_list = ParticipantList::new(&participants).unwrap();
-        let k = p_list.lagrange::<Secp256>(participants[0]).unwrap() * k_shares[0]
-            + p_list.lagrange::<Secp256>(participants[1]).unwrap() * k_shares[1];
-        assert_eq!(ProjectivePoint::GENERATOR * k.invert().unwrap(), big_k);
-        let sigma = p_list.lagrange::<Secp256>(participants[0]).unwrap() * sigma_shares[0]
-            + p_list.lagrange::<Secp256>(participants[1]).unwrap() * sigma_shares[1];
-        assert_eq!(sigma, k * f.eval_at_zero().unwrap().0);
+        let sigma = p_list.lagrange::<Secp256>(participants[0]).unwrap() * result[0].1.sigma
+            + p_list.lagrange::<Secp256>(participants[1]).unwrap() * result[1].1.sigma;
+        assert_eq!(result[0].1.big_r * sigma, big_x);
 
         insta::assert_json_snapshot!(result);
     }
+
+    #[test]
+    fn test_presign_provenance() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold: usize = 2;
+        let f = Polynomial::generate_polynomial(None, threshold.checked_sub(1).unwrap(), &mut rng)
+            .unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+
+        let run = |record_provenance: bool| {
+            let mut protocols: GenProtocol<PresignOutput> = Vec::with_capacity(participants.len());
+            for ((p, triple0), triple1) in participants
+                .iter()
+                .zip(triple0_shares.iter())
+                .zip(triple1_shares.iter())
+            {
+                let args = PresignArguments {
+                    triple0: (triple0.clone(), triple0_pub.clone()),
+                    triple1: (triple1.clone(), triple1_pub.clone()),
+                    keygen_out: KeygenOutput {
+                        private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                        public_key: VerifyingKey::new(big_x),
+                        epoch: 0,
+                    },
+                    threshold: threshold.into(),
+                    record_provenance: false,
+                };
+                let args = if record_provenance {
+                    args.with_provenance()
+                } else {
+                    args
+                };
+                let protocol = presign(&participants, *p, args).unwrap();
+                protocols.push((*p, Box::new(protocol)));
+            }
+            run_protocol(protocols).unwrap()
+        };
+
+        // not recorded by default
+        for (_, presignature) in run(false) {
+            assert_eq!(presignature.provenance, None);
+            assert_serde_roundtrip(&presignature);
+        }
+
+        let expected = PresignProvenance {
+            triple_ids: [triple0_pub.id(), triple1_pub.id()],
+            participants: participants.clone(),
+            bt_participants: [
+                triple0_pub.participants.clone(),
+                triple1_pub.participants.clone(),
+            ],
+        };
+        assert_ne!(expected.triple_ids[0], expected.triple_ids[1]);
+        for (_, presignature) in run(true) {
+            assert_eq!(presignature.provenance.as_ref(), Some(&expected));
+
+            let json = serde_json::to_string(&presignature).unwrap();
+            let decoded: PresignOutput = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, presignature);
+
+            let bytes = rmp_serde::encode::to_vec(&presignature).unwrap();
+            let decoded: PresignOutput = rmp_serde::decode::from_slice(&bytes).unwrap();
+            assert_eq!(decoded, presignature);
+        }
+    }
+
+    #[cfg(feature = "custom-executor")]
+    #[test]
+    fn test_presign_with_channel() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold: usize = 2;
+        let f = Polynomial::generate_polynomial(None, threshold.checked_sub(1).unwrap(), &mut rng)
+            .unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let args = |i: usize, p: Participant| PresignArguments {
+            triple0: (triple0_shares[i].clone(), triple0_pub.clone()),
+            triple1: (triple1_shares[i].clone(), triple1_pub.clone()),
+            keygen_out: KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(p).unwrap().0),
+                public_key: VerifyingKey::new(big_x),
+                epoch: 0,
+            },
+            threshold: threshold.into(),
+            record_provenance: false,
+        };
+
+        let mut protocols: GenProtocol<PresignOutput> = Vec::with_capacity(participants.len());
+        for (i, p) in participants.iter().enumerate() {
+            let protocol = presign(&participants, *p, args(i, *p)).unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let expected = run_protocol(protocols).unwrap();
+
+        // the bare futures run over a transport and an executor foreign to the crate
+        let futures = MemoryChannel::connect(&participants)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (p, chan))| {
+                let fut = presign_with_channel(chan, &participants, p, args(i, p)).unwrap();
+                (p, fut)
+            })
+            .collect();
+        let result = run_futures(futures).unwrap();
+        assert_eq!(result, expected);
+        PresignOutput::verify_big_r(&result, threshold).unwrap();
+    }
+
+    #[test]
+    fn test_presign_rejects_malformed_triples() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold = 2;
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        assert!(triple0_pub.verify().is_ok());
+        assert!(triple1_pub.verify().is_ok());
+
+        let keygen_out = KeygenOutput {
+            private_share: SigningShare::new(Scalar::ONE),
+            public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+            epoch: 0,
+        };
+        let args = |triple0_pub: TriplePub| PresignArguments {
+            triple0: (triple0_shares[0].clone(), triple0_pub),
+            triple1: (triple1_shares[0].clone(), triple1_pub.clone()),
+            keygen_out: keygen_out.clone(),
+            threshold: threshold.into(),
+            record_provenance: false,
+        };
+
+        let mut identity_c = triple0_pub.clone();
+        identity_c.big_c = AffinePoint::IDENTITY;
+        let mut duplicates = triple0_pub.clone();
+        duplicates.participants.push(participants[0]);
+        let mut large_threshold = triple0_pub;
+        large_threshold.threshold = 4.into();
+
+        for malformed in [identity_c, duplicates, large_threshold] {
+            assert!(malformed.verify().is_err());
+            let result = presign(&participants, participants[0], args(malformed));
+            assert!(matches!(
+                result.err().unwrap(),
+                InitializationError::BadParameters(msg) if msg.starts_with("first triple is malformed")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_presign_empty_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold = 2;
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let args = PresignArguments {
+            triple0: (triple0_shares[0].clone(), triple0_pub),
+            triple1: (triple1_shares[0].clone(), triple1_pub),
+            keygen_out: KeygenOutput {
+                private_share: SigningShare::new(Scalar::ONE),
+                public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+                epoch: 0,
+            },
+            threshold: threshold.into(),
+            record_provenance: false,
+        };
+        let result = presign(&[], participants[0], args);
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
+
+    #[test]
+    fn test_presign_zero_threshold() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let (triple0_pub, triple0_shares) = deal(&mut rng, &participants, 2.into()).unwrap();
+        let (triple1_pub, triple1_shares) = deal(&mut rng, &participants, 2.into()).unwrap();
+        let args = PresignArguments {
+            triple0: (triple0_shares[0].clone(), triple0_pub),
+            triple1: (triple1_shares[0].clone(), triple1_pub),
+            keygen_out: KeygenOutput {
+                private_share: SigningShare::new(Scalar::ONE),
+                public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+                epoch: 0,
+            },
+            threshold: 0.into(),
+            record_provenance: false,
+        };
+        let result = presign(&participants, participants[0], args);
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(
+                "OT based ECDSA expects a reconstruction lower bound between 2 and the 3 participants, got 0"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_presign_transcript() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold: usize = 2;
+        let f = Polynomial::generate_polynomial(None, threshold.checked_sub(1).unwrap(), &mut rng)
+            .unwrap();
+        let public_key =
+            VerifyingKey::new(ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0);
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+
+        // the messages sent by honest participants
+        let p_list = ParticipantList::new(&participants).unwrap();
+        let mut kd_shares = BTreeMap::new();
+        let mut ka_xb_shares = BTreeMap::new();
+        for ((p, triple0), triple1) in participants
+            .iter()
+            .zip(triple0_shares.iter())
+            .zip(triple1_shares.iter())
+        {
+            let lambda = p_list.lagrange::<Secp256>(*p).unwrap();
+            let x_i = f.eval_at_participant(*p).unwrap().0;
+            kd_shares.insert(*p, lambda * triple0.c);
+            ka_xb_shares.insert(
+                *p,
+                (lambda * (triple0.a + triple1.a), lambda * (x_i + triple1.b)),
+            );
+        }
+        let transcript = PresignTranscript {
+            triple0: triple0_pub,
+            triple1: triple1_pub,
+            kd_shares,
+            ka_xb_shares,
+        };
+        verify_presign_transcript(&transcript, &participants, &public_key).unwrap();
+
+        // a different public key breaks the xb equation
+        let other_key = VerifyingKey::new(ProjectivePoint::GENERATOR);
+        assert!(matches!(
+            verify_presign_transcript(&transcript, &participants, &other_key),
+            Err(ProtocolError::AssertionFailed(_))
+        ));
+
+        // so does any tampered share
+        let mut tampered = transcript.clone();
+        *tampered.kd_shares.get_mut(&participants[1]).unwrap() += Scalar::ONE;
+        assert!(matches!(
+            verify_presign_transcript(&tampered, &participants, &public_key),
+            Err(ProtocolError::AssertionFailed(_))
+        ));
+        let mut tampered = transcript.clone();
+        tampered.ka_xb_shares.get_mut(&participants[2]).unwrap().0 += Scalar::ONE;
+        assert!(matches!(
+            verify_presign_transcript(&tampered, &participants, &public_key),
+            Err(ProtocolError::AssertionFailed(_))
+        ));
+
+        // every participant must have sent its messages
+        let mut missing = transcript;
+        missing.kd_shares.remove(&participants[0]);
+        assert!(matches!(
+            verify_presign_transcript(&missing, &participants, &public_key),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_presign_with_transcript() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold: usize = 2;
+        let f = Polynomial::generate_polynomial(None, threshold.checked_sub(1).unwrap(), &mut rng)
+            .unwrap();
+        let public_key =
+            VerifyingKey::new(ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0);
+        let (triple0_pub, triple0_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (triple1_pub, triple1_shares) =
+            deal(&mut rng, &participants, threshold.into()).unwrap();
+
+        let mut protocols: GenProtocol<(PresignOutput, PresignTranscript)> =
+            Vec::with_capacity(participants.len());
+        for ((p, triple0), triple1) in participants
+            .iter()
+            .zip(triple0_shares.into_iter())
+            .zip(triple1_shares.into_iter())
+        {
+            let keygen_out = KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                public_key,
+                epoch: 0,
+            };
+            let protocol = presign_with_transcript(
+                &participants,
+                *p,
+                PresignArguments {
+                    triple0: (triple0, triple0_pub.clone()),
+                    triple1: (triple1, triple1_pub.clone()),
+                    keygen_out,
+                    threshold: threshold.into(),
+                    record_provenance: false,
+                },
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let result = run_protocol(protocols).unwrap();
+
+        // every participant records the same public messages
+        let transcript = &result[0].1 .1;
+        assert!(result.iter().all(|(_, (_, t))| t == transcript));
+        assert_eq!(transcript.triple0, triple0_pub);
+        assert_eq!(transcript.triple1, triple1_pub);
+        verify_presign_transcript(transcript, &participants, &public_key).unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_canonical_scalar() {
+        let from = Participant::from(1u32);
+        let scalar = Scalar::from(42u64);
+        let bytes = <Secp256K1ScalarField as Field>::serialize(&scalar);
+        assert_eq!(deserialize_canonical_scalar(from, &bytes).unwrap(), scalar);
+
+        // the group order itself is a non reduced form of zero
+        let order = hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")
+            .unwrap();
+        assert_eq!(
+            deserialize_canonical_scalar(from, &order).unwrap_err(),
+            ProtocolError::ErrorReducingBytesToScalar(from)
+        );
+
+        // wrong length
+        assert_eq!(
+            deserialize_canonical_scalar(from, &[0u8; 31]).unwrap_err(),
+            ProtocolError::ErrorReducingBytesToScalar(from)
+        );
+    }
 }
@@ -6,19 +6,33 @@ use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::{Participant, ParticipantList};
 use crate::ReconstructionLowerBound;
 use crate::{
-    ecdsa::{x_coordinate, AffinePoint, Scalar, Secp256K1Sha256, Signature, SignatureOption},
+    ecdsa::{
+        x_coordinate, AffinePoint, MessageHash, PresignId, Scalar, Secp256K1Sha256, Signature,
+        SignatureOption,
+    },
     protocol::{
         helpers::recv_from_others,
-        internal::{make_protocol, Comms, SharedChannel},
-        Protocol,
+        internal::{make_protocol, Comms},
+        Channel, Protocol,
     },
 };
+use std::future::Future;
 
 /// The signature protocol, allowing us to use a presignature to sign a message.
 ///
 /// **WARNING** You must absolutely hash an actual message before passing it to
 /// this function. Allowing the signing of arbitrary scalars *is* a security risk,
 /// and this function only tolerates this risk to allow for genericity.
+///
+/// The message hash is a [`MessageHash`], which can only be obtained by
+/// hashing a message or from a digest.
+///
+/// `presign_id` is the id of the presignature expected by the caller, the protocol
+/// fails with [`ProtocolError::PresignMismatch`] if the presignature has another one.
+/// Likewise, `key_epoch` is the current [`KeygenOutput::epoch`](crate::KeygenOutput::epoch),
+/// a presignature made with the shares of an older epoch fails with
+/// [`ProtocolError::StalePresignature`].
+#[allow(clippy::too_many_arguments)]
 pub fn sign(
     participants: &[Participant],
     coordinator: Participant,
@@ -26,9 +40,75 @@ pub fn sign(
     me: Participant,
     public_key: AffinePoint,
     presignature: RerandomizedPresignOutput,
-    msg_hash: Scalar,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
 ) -> Result<impl Protocol<Output = SignatureOption>, InitializationError> {
-    let threshold = usize::from(threshold.into());
+    let ctx = Comms::new();
+    let fut = build_sign(
+        ctx.shared_channel(),
+        participants,
+        coordinator,
+        threshold.into(),
+        me,
+        public_key,
+        presignature,
+        presign_id,
+        key_epoch,
+        msg_hash,
+    )?;
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Like [`sign`], but returns the protocol core as a future exchanging its
+/// messages over `chan`, to be awaited on the caller's own executor.
+#[cfg(feature = "custom-executor")]
+#[allow(clippy::too_many_arguments)]
+pub fn sign_with_channel(
+    chan: impl Channel,
+    participants: &[Participant],
+    coordinator: Participant,
+    threshold: impl Into<ReconstructionLowerBound>,
+    me: Participant,
+    public_key: AffinePoint,
+    presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
+) -> Result<impl Future<Output = Result<SignatureOption, ProtocolError>>, InitializationError> {
+    build_sign(
+        chan,
+        participants,
+        coordinator,
+        threshold.into(),
+        me,
+        public_key,
+        presignature,
+        presign_id,
+        key_epoch,
+        msg_hash,
+    )
+}
+
+/// Checks the inputs of [`sign`] and builds its future over `chan`
+#[allow(clippy::too_many_arguments)]
+fn build_sign(
+    chan: impl Channel,
+    participants: &[Participant],
+    coordinator: Participant,
+    threshold: ReconstructionLowerBound,
+    me: Participant,
+    public_key: AffinePoint,
+    presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
+) -> Result<impl Future<Output = Result<SignatureOption, ProtocolError>>, InitializationError> {
+    let threshold = usize::from(threshold);
+    let msg_hash = msg_hash.to_scalar();
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -62,22 +142,22 @@ pub fn sign(
         });
     }
 
-    let ctx = Comms::new();
-    let fut = fut_wrapper(
-        ctx.shared_channel(),
+    Ok(fut_wrapper(
+        chan,
         participants,
         coordinator,
         me,
         public_key,
         presignature,
+        presign_id,
+        key_epoch,
         msg_hash,
-    );
-    Ok(make_protocol(ctx, fut))
+    ))
 }
 
 /// Performs signing from any participant's perspective (except the coordinator)
 fn do_sign_participant(
-    mut chan: SharedChannel,
+    mut chan: impl Channel,
     participants: &ParticipantList,
     coordinator: Participant,
     me: Participant,
@@ -96,7 +176,7 @@ fn do_sign_participant(
 
 /// Performs signing from only the coordinator's perspective
 async fn do_sign_coordinator(
-    mut chan: SharedChannel,
+    mut chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     public_key: AffinePoint,
@@ -116,6 +196,12 @@ async fn do_sign_coordinator(
         s += s_j;
     }
 
+    // raise error if s is zero, as the robust signer does
+    if s.is_zero().into() {
+        return Err(ProtocolError::AssertionFailed(
+            "signature part s cannot be zero".to_string(),
+        ));
+    }
     // Normalize s
     // Spec 1.7
     s.conditional_assign(&(-s), s.is_high());
@@ -127,9 +213,7 @@ async fn do_sign_coordinator(
 
     // Spec 1.8
     if !sig.verify(&public_key, &msg_hash) {
-        return Err(ProtocolError::AssertionFailed(
-            "signature failed to verify".to_string(),
-        ));
+        return Err(ProtocolError::SignatureVerificationFailed);
     }
 
     Ok(Some(sig))
@@ -159,15 +243,27 @@ fn compute_signature_share(
 }
 
 /// Wraps the coordinator and the participant into a single functions to be called
+#[allow(clippy::too_many_arguments)]
 async fn fut_wrapper(
-    chan: SharedChannel,
+    chan: impl Channel,
     participants: ParticipantList,
     coordinator: Participant,
     me: Participant,
     public_key: AffinePoint,
     presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
     msg_hash: Scalar,
 ) -> Result<SignatureOption, ProtocolError> {
+    if presignature.id != presign_id {
+        return Err(ProtocolError::PresignMismatch);
+    }
+    if presignature.epoch != key_epoch {
+        return Err(ProtocolError::StalePresignature {
+            presignature: presignature.epoch,
+            key: key_epoch,
+        });
+    }
     if me == coordinator {
         do_sign_coordinator(chan, participants, me, public_key, presignature, msg_hash).await
     } else {
@@ -184,16 +280,25 @@ async fn fut_wrapper(
 
 #[cfg(test)]
 mod test {
-    use super::x_coordinate;
+    #[cfg(feature = "custom-executor")]
+    use super::sign_with_channel;
+    use super::{sign, x_coordinate, RerandomizedPresignOutput};
+    #[cfg(feature = "custom-executor")]
+    use crate::test_utils::{check_one_coordinator_output, run_futures, MemoryChannel};
     use crate::{
+        crypto::hash::test::scalar_hash_secp256k1,
         ecdsa::{
             ot_based_ecdsa::{
                 test::{run_sign_with_rerandomization, run_sign_without_rerandomization},
                 PresignOutput,
             },
-            Polynomial,
+            AffinePoint, MessageHash, Polynomial, PresignId, Scalar, Secp256K1Sha256,
+            SignatureOption,
         },
-        test_utils::{generate_participants, MockCryptoRng},
+        errors::{InitializationError, ProtocolError},
+        participants::{Participant, ParticipantList},
+        protocol::Protocol,
+        test_utils::{generate_participants, run_sign, MockCryptoRng},
     };
     use k256::{ecdsa::signature::Verifier, ecdsa::VerifyingKey, ProjectivePoint, PublicKey};
     use rand::SeedableRng;
@@ -226,6 +331,9 @@ mod test {
                 big_r,
                 k: g.eval_at_participant(*p).unwrap().0,
                 sigma: h.eval_at_participant(*p).unwrap().0,
+                id: PresignId([0; 32]),
+                provenance: None,
+                epoch: 0,
             };
             participants_presign.push((*p, presignature));
         }
@@ -243,6 +351,62 @@ mod test {
             .unwrap();
     }
 
+    #[cfg(feature = "custom-executor")]
+    #[test]
+    fn test_sign_with_channel() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold: usize = 2;
+        let msg = b"Hello? Is it me you're looking for?";
+        let msg_hash = scalar_hash_secp256k1(msg);
+
+        let degree = threshold.checked_sub(1).unwrap();
+        let f = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let x = f.eval_at_zero().unwrap().0;
+        let public_key = ProjectivePoint::GENERATOR * x;
+        let g = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let k = g.eval_at_zero().unwrap().0;
+        let big_r = (ProjectivePoint::GENERATOR * k.invert().unwrap()).to_affine();
+        let h = Polynomial::generate_polynomial(Some(k * x), degree, &mut rng).unwrap();
+
+        let participants = generate_participants(3);
+        let coordinator = participants[1];
+        // the bare futures run over a transport and an executor foreign to the crate
+        let futures = MemoryChannel::connect(&participants)
+            .into_iter()
+            .map(|(p, chan)| {
+                let presignature = PresignOutput {
+                    big_r,
+                    k: g.eval_at_participant(p).unwrap().0,
+                    sigma: h.eval_at_participant(p).unwrap().0,
+                    id: PresignId([0; 32]),
+                    provenance: None,
+                    epoch: 0,
+                };
+                let fut = sign_with_channel(
+                    chan,
+                    &participants,
+                    coordinator,
+                    threshold,
+                    p,
+                    public_key.to_affine(),
+                    RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                    PresignId([0; 32]),
+                    0,
+                    MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+                )
+                .unwrap();
+                (p, fut)
+            })
+            .collect();
+        let result = run_futures(futures).unwrap();
+
+        let sig = check_one_coordinator_output(result, coordinator).unwrap();
+        let sig = ecdsa::Signature::from_scalars(x_coordinate(&sig.big_r), sig.s).unwrap();
+        VerifyingKey::from(&PublicKey::from_affine(public_key.to_affine()).unwrap())
+            .verify(msg, &sig)
+            .unwrap();
+    }
+
     #[test]
     fn test_sign_with_rerandomization() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -271,6 +435,9 @@ mod test {
                 big_r,
                 k: g.eval_at_participant(*p).unwrap().0,
                 sigma: h.eval_at_participant(*p).unwrap().0,
+                id: PresignId([0; 32]),
+                provenance: None,
+                epoch: 0,
             };
             participants_presign.push((*p, presignature));
         }
@@ -292,4 +459,224 @@ mod test {
 
         insta::assert_json_snapshot!(signature);
     }
+
+    #[test]
+    fn test_sign_fails_verification_with_wrong_public_key() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold: usize = 2;
+        let msg_hash = scalar_hash_secp256k1(b"Hello? Is it me you're looking for?");
+
+        let degree = threshold.checked_sub(1).unwrap();
+        let f = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let x = f.eval_at_zero().unwrap().0;
+        // a public key that does not match the shared secret key
+        let wrong_public_key = ProjectivePoint::GENERATOR * (x + Scalar::ONE);
+
+        let g = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let k = g.eval_at_zero().unwrap().0;
+        let big_r = (ProjectivePoint::GENERATOR * k.invert().unwrap()).to_affine();
+        let h = Polynomial::generate_polynomial(Some(k * x), degree, &mut rng).unwrap();
+
+        let participants = generate_participants(2);
+        let participants_presign = participants
+            .iter()
+            .map(|p| {
+                let presignature = RerandomizedPresignOutput {
+                    big_r,
+                    k: g.eval_at_participant(*p).unwrap().0,
+                    sigma: h.eval_at_participant(*p).unwrap().0,
+                    id: PresignId([0; 32]),
+                    epoch: 0,
+                };
+                (*p, presignature)
+            })
+            .collect::<Vec<_>>();
+
+        let err = run_sign::<Secp256K1Sha256, _, _, _>(
+            participants_presign,
+            participants[0],
+            wrong_public_key,
+            msg_hash,
+            |participants, coordinator, me, pk, presignature, msg_hash| {
+                let presign_id = presignature.id;
+                sign(
+                    participants,
+                    coordinator,
+                    threshold,
+                    me,
+                    pk.to_affine(),
+                    presignature,
+                    presign_id,
+                    0,
+                    MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+                )
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ProtocolError>(),
+            Some(&ProtocolError::SignatureVerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_mismatching_presign_id() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold: usize = 2;
+        let msg_hash = scalar_hash_secp256k1(b"Hello? Is it me you're looking for?");
+
+        let degree = threshold.checked_sub(1).unwrap();
+        let f = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let x = f.eval_at_zero().unwrap().0;
+        let public_key = ProjectivePoint::GENERATOR * x;
+
+        let g = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let k = g.eval_at_zero().unwrap().0;
+        let big_r = (ProjectivePoint::GENERATOR * k.invert().unwrap()).to_affine();
+        let h = Polynomial::generate_polynomial(Some(k * x), degree, &mut rng).unwrap();
+
+        let participants = generate_participants(2);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let participants_presign = participants
+            .iter()
+            .map(|p| {
+                let presignature = RerandomizedPresignOutput {
+                    big_r,
+                    k: g.eval_at_participant(*p).unwrap().0,
+                    sigma: h.eval_at_participant(*p).unwrap().0,
+                    id: PresignId::derive(&big_r, &participant_list),
+                    epoch: 0,
+                };
+                (*p, presignature)
+            })
+            .collect::<Vec<_>>();
+        // the caller expects the presignature of another session
+        let other_id = PresignId::derive(&AffinePoint::GENERATOR, &participant_list);
+
+        let err = run_sign::<Secp256K1Sha256, _, _, _>(
+            participants_presign,
+            participants[0],
+            public_key,
+            msg_hash,
+            |participants, coordinator, me, pk, presignature, msg_hash| {
+                sign(
+                    participants,
+                    coordinator,
+                    threshold,
+                    me,
+                    pk.to_affine(),
+                    presignature,
+                    other_id,
+                    0,
+                    MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+                )
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ProtocolError>(),
+            Some(&ProtocolError::PresignMismatch)
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_stored_stale_presignature() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold: usize = 2;
+        let msg_hash = scalar_hash_secp256k1(b"Hello? Is it me you're looking for?");
+
+        let degree = threshold.checked_sub(1).unwrap();
+        let f = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let x = f.eval_at_zero().unwrap().0;
+        let public_key = ProjectivePoint::GENERATOR * x;
+
+        let g = Polynomial::generate_polynomial(None, degree, &mut rng).unwrap();
+        let k = g.eval_at_zero().unwrap().0;
+        let big_r = (ProjectivePoint::GENERATOR * k.invert().unwrap()).to_affine();
+        let h = Polynomial::generate_polynomial(Some(k * x), degree, &mut rng).unwrap();
+
+        let participants = generate_participants(2);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let participants_presign = participants
+            .iter()
+            .map(|p| {
+                // made before the shares were reshared into epoch 2, without provenance
+                let presignature = PresignOutput {
+                    big_r,
+                    k: g.eval_at_participant(*p).unwrap().0,
+                    sigma: h.eval_at_participant(*p).unwrap().0,
+                    id: PresignId::derive(&big_r, &participant_list),
+                    provenance: None,
+                    epoch: 1,
+                };
+                // stored and loaded back
+                let bytes = rmp_serde::encode::to_vec(&presignature).unwrap();
+                let stored: PresignOutput = rmp_serde::decode::from_slice(&bytes).unwrap();
+                assert_eq!(stored, presignature);
+                (
+                    *p,
+                    RerandomizedPresignOutput::new_without_rerandomization(&stored),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let err = run_sign::<Secp256K1Sha256, _, _, _>(
+            participants_presign,
+            participants[0],
+            public_key,
+            msg_hash,
+            |participants, coordinator, me, pk, presignature, msg_hash| {
+                let presign_id = presignature.id;
+                sign(
+                    participants,
+                    coordinator,
+                    threshold,
+                    me,
+                    pk.to_affine(),
+                    presignature,
+                    presign_id,
+                    2,
+                    MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+                )
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ProtocolError>(),
+            Some(&ProtocolError::StalePresignature {
+                presignature: 1,
+                key: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_sign_empty_participants() {
+        let me = Participant::from(0u32);
+        let presignature = RerandomizedPresignOutput {
+            big_r: AffinePoint::GENERATOR,
+            k: Scalar::ONE,
+            sigma: Scalar::ONE,
+            id: PresignId([0; 32]),
+            epoch: 0,
+        };
+        let result = sign(
+            &[],
+            me,
+            2,
+            me,
+            AffinePoint::GENERATOR,
+            presignature,
+            PresignId([0; 32]),
+            0,
+            MessageHash::from_prehashed(Scalar::ONE.to_bytes().into()),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
 }
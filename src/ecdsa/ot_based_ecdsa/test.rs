@@ -5,16 +5,18 @@ use super::{
     PresignArguments, PresignOutput, RerandomizedPresignOutput,
 };
 use crate::test_utils::{
-    assert_public_key_invariant, check_one_coordinator_output, generate_participants,
-    generate_participants_with_random_ids, run_keygen, run_protocol, run_refresh, run_reshare,
-    run_sign, GenOutput, GenProtocol, MockCryptoRng,
+    assert_public_key_invariant, check_one_coordinator_output, check_rerandomize_presign_many,
+    generate_participants, generate_participants_with_random_ids, run_keygen, run_protocol,
+    run_refresh, run_reshare, run_sign, GenOutput, GenProtocol, MockCryptoRng,
 };
 use crate::{protocol::Protocol, Participant, ReconstructionLowerBound};
 
 use crate::crypto::hash::test::scalar_hash_secp256k1;
+#[cfg(feature = "libsecp256k1-interop")]
+use crate::ecdsa::libsecp256k1_interop::verify_with_libsecp256k1;
 use crate::ecdsa::{
-    Element, ParticipantList, RerandomizationArguments, Secp256K1Sha256, Signature,
-    SignatureOption, Tweak,
+    batch_verify_ecdsa, has_even_y, Element, MessageHash, ParticipantList, PresignId,
+    RerandomizationArguments, Secp256K1Sha256, Signature, SignatureOption, Tweak,
 };
 
 use rand::seq::SliceRandom as _;
@@ -55,6 +57,9 @@ pub fn run_sign_without_rerandomization(
         msg_hash,
         |participants, coordinator, me, pk, presignature, msg_hash| {
             let pk = pk.to_affine();
+            let presign_id = presignature.id;
+            // the presignatures are made with the current shares
+            let epoch = presignature.epoch;
             sign(
                 participants,
                 coordinator,
@@ -62,7 +67,9 @@ pub fn run_sign_without_rerandomization(
                 me,
                 pk,
                 presignature,
-                msg_hash,
+                presign_id,
+                epoch,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
             )
             .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
         },
@@ -70,6 +77,12 @@ pub fn run_sign_without_rerandomization(
     .unwrap();
     // test one single some for the coordinator
     let signature = check_one_coordinator_output(result, coordinator).unwrap();
+    #[cfg(feature = "libsecp256k1-interop")]
+    assert!(verify_with_libsecp256k1(
+        &signature,
+        &public_key.to_affine(),
+        &msg_hash
+    ));
     (coordinator, signature)
 }
 
@@ -129,6 +142,9 @@ pub fn run_sign_with_rerandomization(
         msg_hash,
         |participants, coordinator, me, pk, presignature, msg_hash| {
             let pk = pk.to_affine();
+            let presign_id = presignature.id;
+            // the presignatures are made with the current shares
+            let epoch = presignature.epoch;
             sign(
                 participants,
                 coordinator,
@@ -136,7 +152,9 @@ pub fn run_sign_with_rerandomization(
                 me,
                 pk,
                 presignature,
-                msg_hash,
+                presign_id,
+                epoch,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
             )
             .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
         },
@@ -144,6 +162,10 @@ pub fn run_sign_with_rerandomization(
 
     // test one single some for the coordinator
     let signature = check_one_coordinator_output(result, coordinator)?;
+    #[cfg(feature = "libsecp256k1-interop")]
+    if !verify_with_libsecp256k1(&signature, &derived_pk.to_affine(), &msg_hash) {
+        return Err("libsecp256k1 rejects the signature".into());
+    }
     Ok((tweak, coordinator, signature))
 }
 
@@ -175,6 +197,7 @@ pub fn run_presign(
                 triple1: (share1, pub1.clone()),
                 keygen_out,
                 threshold,
+                record_provenance: false,
             },
         )
         .unwrap();
@@ -360,6 +383,101 @@ fn test_e2e() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// The signers normalize `s` without negating `big_r`, so their signatures
+/// may hold either sign of `big_r`
+#[test]
+fn test_batch_verify_signatures() -> Result<(), Box<dyn Error>> {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(3);
+    let threshold = 2;
+
+    let key_packages = run_keygen(&participants, threshold, &mut rng);
+    let public_key = key_packages[0].1.public_key.to_element();
+
+    let mut entries = Vec::new();
+    for i in 0..8u8 {
+        let (pub0, shares0) = deal(&mut rng, &participants, threshold.into())?;
+        let (pub1, shares1) = deal(&mut rng, &participants, threshold.into())?;
+        let presign_result = run_presign(
+            key_packages.clone(),
+            shares0,
+            shares1,
+            &pub0,
+            &pub1,
+            threshold.into(),
+        );
+        let msg = [i; 32];
+        let (_, sig) = run_sign_without_rerandomization(
+            &presign_result,
+            threshold.into(),
+            public_key,
+            &msg,
+            &mut rng,
+        );
+        entries.push((public_key.to_affine(), scalar_hash_secp256k1(&msg), sig));
+    }
+    assert_eq!(batch_verify_ecdsa(&entries, &mut rng), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn test_e2e_even_y() -> Result<(), Box<dyn Error>> {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(3);
+    let threshold = 3;
+
+    let key_packages = run_keygen(&participants, threshold, &mut rng);
+    let public_key = key_packages[0].1.public_key;
+
+    // presign until both parities of R were seen
+    let mut seen = [false; 2];
+    while !(seen[0] && seen[1]) {
+        let (pub0, shares0) = deal(&mut rng, &participants, threshold.into())?;
+        let (pub1, shares1) = deal(&mut rng, &participants, threshold.into())?;
+        let mut presign_result = run_presign(
+            key_packages.clone(),
+            shares0,
+            shares1,
+            &pub0,
+            &pub1,
+            threshold.into(),
+        );
+        seen[usize::from(has_even_y(&presign_result[0].1.big_r))] = true;
+        for (_, presignature) in &mut presign_result {
+            presignature.force_even_y();
+            assert!(has_even_y(&presignature.big_r));
+        }
+
+        let msg = b"hello world";
+        // internally verifies the signature's validity
+        run_sign_without_rerandomization(
+            &presign_result,
+            threshold.into(),
+            public_key.to_element(),
+            msg,
+            &mut rng,
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rerandomize_presign_many() {
+    check_rerandomize_presign_many(
+        |big_r, participants, rng| PresignOutput {
+            big_r,
+            k: frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut *rng),
+            sigma: frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut *rng),
+            id: PresignId::derive(&big_r, participants),
+            provenance: None,
+            epoch: 0,
+        },
+        RerandomizedPresignOutput::rerandomize_presign,
+        RerandomizedPresignOutput::rerandomize_presign_many,
+        |rerandomized| (rerandomized.big_r, vec![rerandomized.k, rerandomized.sigma]),
+    );
+}
+
 #[test]
 fn test_e2e_random_identifiers() -> Result<(), Box<dyn Error>> {
     let mut rng = MockCryptoRng::seed_from_u64(42);
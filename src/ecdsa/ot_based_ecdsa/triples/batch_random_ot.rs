@@ -373,14 +373,15 @@ mod test {
     use rand::SeedableRng;
 
     use super::*;
-    use crate::ecdsa::ot_based_ecdsa::triples::test::run_batch_random_ot;
-    use crate::participants::Participant;
-    use crate::protocol::internal::{make_protocol, Comms};
-    use crate::test_utils::{run_two_party_protocol, MockCryptoRng};
+    use crate::ecdsa::ot_based_ecdsa::triples::test::{
+        run_batch_random_ot, run_batch_random_ot_many,
+    };
+    use crate::test_utils::MockCryptoRng;
 
     #[test]
     fn test_batch_random_ot() {
-        let ((k0, k1), (delta, k_delta)) = run_batch_random_ot().unwrap();
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let ((k0, k1), (delta, k_delta)) = run_batch_random_ot(&mut rng).unwrap();
 
         // Check that we've gotten the right rows of the two matrices.
         for (((row0, row1), delta_i), row_delta) in k0
@@ -397,36 +398,6 @@ mod test {
         }
     }
 
-    /// Run the batch random OT many protocol between two parties.
-    fn run_batch_random_ot_many<const N: usize, R: CryptoRngCore + SeedableRng + Send + 'static>(
-        rng: &mut R,
-    ) -> Result<
-        (
-            Vec<BatchRandomOTOutputSender>,
-            Vec<BatchRandomOTOutputReceiver>,
-        ),
-        ProtocolError,
-    > {
-        let s = Participant::from(0u32);
-        let r = Participant::from(1u32);
-        let comms_s = Comms::new();
-        let comms_r = Comms::new();
-        let rng_1 = R::seed_from_u64(rng.next_u64());
-        let rng_2 = R::seed_from_u64(rng.next_u64());
-        run_two_party_protocol(
-            s,
-            r,
-            &mut make_protocol(
-                comms_s.clone(),
-                batch_random_ot_sender_many::<N>(comms_s.private_channel(s, r), rng_1),
-            ),
-            &mut make_protocol(
-                comms_r.clone(),
-                batch_random_ot_receiver_many::<N>(comms_r.private_channel(r, s), rng_2),
-            ),
-        )
-    }
-
     #[test]
     fn test_batch_random_ot_many() {
         const N: usize = 10;
@@ -450,4 +421,17 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_batch_random_ot_is_reproducible() {
+        let run = |seed| run_batch_random_ot(&mut MockCryptoRng::seed_from_u64(seed)).unwrap();
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+
+        let run_many = |seed| {
+            run_batch_random_ot_many::<2, _>(&mut MockCryptoRng::seed_from_u64(seed)).unwrap()
+        };
+        assert_eq!(run_many(42), run_many(42));
+        assert_ne!(run_many(42), run_many(43));
+    }
 }
@@ -106,7 +106,7 @@ mod test {
     #[test]
     fn test_correlated_ot() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
-        let ((k0, k1), (delta, k)) = run_batch_random_ot().unwrap();
+        let ((k0, k1), (delta, k)) = run_batch_random_ot(&mut rng).unwrap();
         let batch_size = 256;
         let x = BitMatrix::random(&mut rng, batch_size);
         let (q, t) = run_correlated_ot(
@@ -351,7 +351,8 @@ async fn do_generation_many<const N: usize>(
                 }
 
                 if !all_commitments
-                    .index(from)?
+                    .get(from)
+                    .ok_or(ProtocolError::MissingContribution(from))?
                     .check(
                         &(&their_big_e, &their_big_f, &their_big_l),
                         their_randomizer,
@@ -475,7 +476,10 @@ async fn do_generation_many<const N: usize>(
                 let their_phi_proof = &their_phi_proofs[i];
 
                 let statement = dlogeq::Statement::<C> {
-                    public0: &big_e_j_zero.index(from)?.value(),
+                    public0: &big_e_j_zero
+                        .get(from)
+                        .ok_or(ProtocolError::MissingContribution(from))?
+                        .value(),
                     generator1: &big_f.eval_at_zero()?.value(),
                     public1: &big_c_j,
                 };
@@ -684,6 +688,9 @@ fn validate_triple_inputs(
 ) -> Result<(ParticipantList, ReconstructionLowerBound), InitializationError> {
     let threshold = threshold.into();
     let threshold_value = threshold.value();
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -745,6 +752,7 @@ mod test {
 
     use crate::{
         ecdsa::{ot_based_ecdsa::triples::generate_triple, ProjectivePoint},
+        errors::InitializationError,
         participants::{Participant, ParticipantList},
         protocol::Protocol,
         test_utils::{generate_participants, run_protocol, MockCryptoRng},
@@ -859,4 +867,19 @@ mod test {
 
         insta::assert_json_snapshot!(result);
     }
+
+    #[test]
+    fn test_triple_generation_empty_participants() {
+        let me = Participant::from(0u32);
+        let result = generate_triple(&[], me, 2, MockCryptoRng::seed_from_u64(42));
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+        let result = generate_triple_many::<2>(&[], me, 2, MockCryptoRng::seed_from_u64(42));
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
 }
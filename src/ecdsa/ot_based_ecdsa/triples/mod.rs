@@ -35,16 +35,20 @@ mod multiplication;
 mod random_ot_extension;
 
 pub use generation::{generate_triple, generate_triple_many, TripleGenerationOutput};
+pub use multiplication::{pairing_roles, PairingRole};
 
 #[cfg(test)]
 pub(crate) mod test;
 
+use elliptic_curve::sec1::ToEncodedPoint;
 use serde::{Deserialize, Serialize};
-use zeroize::ZeroizeOnDrop;
+use sha2::{Digest, Sha256};
 
 use crate::{
+    crypto::secret::zeroize_on_drop,
     ecdsa::{AffinePoint, Scalar},
-    participants::Participant,
+    errors::ProtocolError,
+    participants::{Participant, ParticipantList},
     ReconstructionLowerBound,
 };
 
@@ -64,14 +68,57 @@ pub struct TriplePub {
     pub threshold: ReconstructionLowerBound,
 }
 
+impl TriplePub {
+    const ID_DOMAIN: &'static [u8] = b"NEAR THRESHOLD SIGNATURES ECDSA TRIPLE ID";
+
+    /// Identifies the triple by a hash of its commitments, which are unique per triple.
+    pub fn id(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(Self::ID_DOMAIN);
+        for point in [self.big_a, self.big_b, self.big_c] {
+            hasher.update(point.to_encoded_point(true).as_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Checks that the public part of the triple is well formed.
+    ///
+    /// Without a pairing, `C = a * B` cannot be checked from the commitments alone,
+    /// so this rejects identity commitments and inconsistent metadata only.
+    /// A `C` not matching the shares is still caught while presigning.
+    pub fn verify(&self) -> Result<(), ProtocolError> {
+        if [self.big_a, self.big_b, self.big_c]
+            .iter()
+            .any(|point| *point == AffinePoint::IDENTITY)
+        {
+            return Err(ProtocolError::IdentityElement);
+        }
+        if ParticipantList::new(&self.participants).is_none() {
+            return Err(ProtocolError::AssertionFailed(
+                "triple participant list contains duplicates".to_string(),
+            ));
+        }
+        let threshold = self.threshold.value();
+        if threshold == 0 || threshold > self.participants.len() {
+            return Err(ProtocolError::AssertionFailed(format!(
+                "triple threshold {threshold} is invalid for {} participants",
+                self.participants.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Represents a share of a triple.
 ///
 /// This consists of shares of each individual part.
 ///
 /// i.e. we have a share of a, b, and c such that a * b = c.
-#[derive(Clone, Debug, Serialize, Deserialize, ZeroizeOnDrop)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TripleShare {
     pub a: Scalar,
     pub b: Scalar,
     pub c: Scalar,
 }
+
+zeroize_on_drop!(TripleShare, [a, b, c]);
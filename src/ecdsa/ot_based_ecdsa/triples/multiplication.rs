@@ -30,6 +30,7 @@ use super::{
     },
 };
 use std::collections::VecDeque;
+use std::ops::Range;
 
 #[derive(derive_more::Constructor)]
 struct MultiplicationSenderRandomPackage {
@@ -150,25 +151,21 @@ async fn multiplication_receiver(
     Ok(gamma0? + gamma1?)
 }
 
-pub(super) async fn multiplication_many<const N: usize>(
-    comms: Comms,
-    sid: Vec<HashOutput>,
-    participants: ParticipantList,
+/// Runs the pairwise multiplications for the indices in `range`,
+/// returning my shares of the products in the same order.
+#[allow(clippy::too_many_arguments)]
+async fn multiplication_range(
+    comms: &Comms,
+    sid_arc: &Arc<Vec<HashOutput>>,
+    participants: &ParticipantList,
     me: Participant,
-    av_iv: Vec<Scalar>,
-    bv_iv: Vec<Scalar>,
-    mut rng: impl CryptoRngCore,
+    av_iv_arc: &Arc<Vec<Scalar>>,
+    bv_iv_arc: &Arc<Vec<Scalar>>,
+    range: Range<usize>,
+    rng: &mut impl CryptoRngCore,
 ) -> Result<Vec<Scalar>, ProtocolError> {
-    if N == 0 {
-        return Err(ProtocolError::AssertionFailed(
-            "N must be greater than 0".to_string(),
-        ));
-    }
-    let sid_arc = Arc::new(sid);
-    let av_iv_arc = Arc::new(av_iv);
-    let bv_iv_arc = Arc::new(bv_iv);
-    let mut tasks = Vec::with_capacity(participants.len() - 1);
-    for i in 0..N {
+    let mut tasks = Vec::with_capacity(range.len() * (participants.len() - 1));
+    for i in range.clone() {
         let order_key_me = hash(&(i, me))?;
         for p in participants.others(me) {
             let sid_arc = sid_arc.clone();
@@ -177,13 +174,9 @@ pub(super) async fn multiplication_many<const N: usize>(
             let chan = comms.private_channel(me, p).child(i as u64);
             let order_key_other = hash(&(i, p))?;
             let fut: Pin<Box<dyn Future<Output = _> + Send>> = {
-                // Use a deterministic but random comparison function to decide who
-                // is the sender and who is the receiver. This allows the batched
-                // multiplication operation to put even networking load between the
-                // participants.
-                if order_key_other.as_ref() < order_key_me.as_ref() {
+                if pairing_role(&order_key_me, &order_key_other) == PairingRole::Sender {
                     let precomputed_sender_package =
-                        MultiplicationSenderRandomPackage::generate_random_package(&mut rng);
+                        MultiplicationSenderRandomPackage::generate_random_package(rng);
                     Box::pin(async move {
                         #[allow(clippy::large_futures)]
                         multiplication_sender(
@@ -197,7 +190,7 @@ pub(super) async fn multiplication_many<const N: usize>(
                     })
                 } else {
                     let precomputed_receiver_package =
-                        MultiplicationReceiverRandomPackage::generate_random_package(&mut rng);
+                        MultiplicationReceiverRandomPackage::generate_random_package(rng);
                     Box::pin(async move {
                         multiplication_receiver(
                             chan,
@@ -214,7 +207,7 @@ pub(super) async fn multiplication_many<const N: usize>(
         }
     }
     let mut outs = vec![];
-    for i in 0..N {
+    for i in range {
         let av_i = &av_iv_arc.as_slice()[i];
         let bv_i = &bv_iv_arc.as_slice()[i];
         let out = *av_i * *bv_i;
@@ -226,7 +219,7 @@ pub(super) async fn multiplication_many<const N: usize>(
         .into_iter()
         .collect::<VecDeque<_>>();
 
-    for oi in outs.iter_mut().take(N) {
+    for oi in &mut outs {
         for _ in participants.others(me) {
             if let Some(result) = results.pop_front() {
                 *oi += result;
@@ -241,6 +234,121 @@ pub(super) async fn multiplication_many<const N: usize>(
     Ok(outs)
 }
 
+pub(super) async fn multiplication_many<const N: usize>(
+    comms: Comms,
+    sid: Vec<HashOutput>,
+    participants: ParticipantList,
+    me: Participant,
+    av_iv: Vec<Scalar>,
+    bv_iv: Vec<Scalar>,
+    rng: impl CryptoRngCore,
+) -> Result<Vec<Scalar>, ProtocolError> {
+    multiplication_chunked::<N>(N, comms, sid, participants, me, av_iv, bv_iv, rng).await
+}
+
+/// The part a participant plays in a multiplication with another participant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairingRole {
+    /// Runs `multiplication_sender`, receiving the base OTs and sending the MTA messages.
+    Sender,
+    /// Runs `multiplication_receiver`, the counterpart of the sender.
+    Receiver,
+}
+
+impl PairingRole {
+    /// The role of the other participant of the pair.
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Sender => Self::Receiver,
+            Self::Receiver => Self::Sender,
+        }
+    }
+}
+
+/// Use a deterministic but random comparison function to decide who
+/// is the sender and who is the receiver. This allows the batched
+/// multiplication operation to put even networking load between the
+/// participants.
+fn pairing_role(order_key_me: &HashOutput, order_key_other: &HashOutput) -> PairingRole {
+    if order_key_other.as_ref() < order_key_me.as_ref() {
+        PairingRole::Sender
+    } else {
+        PairingRole::Receiver
+    }
+}
+
+/// Returns the roles `me` takes with every other participant in each of
+/// `n` batched multiplications, as run by the triple generation.
+///
+/// Entry `i` lists the pairs of the `i`-th multiplication in participant order.
+pub fn pairing_roles(
+    participants: &ParticipantList,
+    me: Participant,
+    n: usize,
+) -> Result<Vec<Vec<(Participant, PairingRole)>>, ProtocolError> {
+    (0..n)
+        .map(|i| {
+            let order_key_me = hash(&(i, me))?;
+            participants
+                .others(me)
+                .map(|p| Ok((p, pairing_role(&order_key_me, &hash(&(i, p))?))))
+                .collect::<Result<Vec<_>, ProtocolError>>()
+        })
+        .collect()
+}
+
+/// Same as `multiplication_many`, but only runs `chunk` multiplications at a time.
+///
+/// This bounds the number of OT extensions in flight, and thus the memory used,
+/// at the cost of more round trips. The output is identical to `multiplication_many`.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn multiplication_chunked<const N: usize>(
+    chunk: usize,
+    comms: Comms,
+    sid: Vec<HashOutput>,
+    participants: ParticipantList,
+    me: Participant,
+    av_iv: Vec<Scalar>,
+    bv_iv: Vec<Scalar>,
+    mut rng: impl CryptoRngCore,
+) -> Result<Vec<Scalar>, ProtocolError> {
+    if N == 0 {
+        return Err(ProtocolError::AssertionFailed(
+            "N must be greater than 0".to_string(),
+        ));
+    }
+    if chunk == 0 {
+        return Err(ProtocolError::AssertionFailed(
+            "chunk must be greater than 0".to_string(),
+        ));
+    }
+    let sid_arc = Arc::new(sid);
+    let av_iv_arc = Arc::new(av_iv);
+    let bv_iv_arc = Arc::new(bv_iv);
+
+    let mut outs = Vec::with_capacity(N);
+    let mut start = 0;
+    while start < N {
+        let end = start.saturating_add(chunk).min(N);
+        let chunk_outs = multiplication_range(
+            &comms,
+            &sid_arc,
+            &participants,
+            me,
+            &av_iv_arc,
+            &bv_iv_arc,
+            start..end,
+            &mut rng,
+        )
+        .await?;
+        outs.extend(chunk_outs);
+        start = end;
+    }
+
+    Ok(outs)
+}
+
 #[cfg(test)]
 mod test {
     use k256::Scalar;
@@ -248,9 +356,14 @@ mod test {
 
     use crate::{
         crypto::hash::hash,
-        ecdsa::ot_based_ecdsa::triples::multiplication::multiplication_many,
+        ecdsa::ot_based_ecdsa::triples::multiplication::{
+            multiplication_chunked, multiplication_many, pairing_roles,
+        },
         participants::ParticipantList,
-        protocol::internal::{make_protocol, Comms},
+        protocol::{
+            internal::{make_protocol, Comms},
+            Protocol,
+        },
         test_utils::{generate_participants, run_protocol, GenProtocol, MockCryptoRng},
     };
 
@@ -330,4 +443,105 @@ mod test {
             assert_eq!(a_v[i] * b_v[i], c_v[i]);
         }
     }
+
+    #[test]
+    fn test_pairing_roles_are_consistent() {
+        const N: usize = 8;
+        let participants = ParticipantList::new(&generate_participants(5)).unwrap();
+        let roles = participants
+            .participants()
+            .iter()
+            .map(|p| (*p, pairing_roles(&participants, *p, N).unwrap()))
+            .collect::<Vec<_>>();
+
+        for (me, my_roles) in &roles {
+            assert_eq!(my_roles.len(), N);
+            for (i, pairs) in my_roles.iter().enumerate() {
+                assert_eq!(pairs.len(), participants.len() - 1);
+                for (p, role) in pairs {
+                    // the other endpoint plays the opposite role with me
+                    let (_, their_roles) = roles.iter().find(|(q, _)| q == p).unwrap();
+                    assert!(their_roles[i].contains(&(*me, role.opposite())));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiplication_chunked() {
+        const N: usize = 5;
+        let participants = generate_participants(3);
+        let sids = (0..N)
+            .map(|i| hash(&format!("sid{i}")))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        // runs the multiplications with the same inputs and randomness
+        let run = |chunk: Option<usize>| {
+            let mut rng = MockCryptoRng::seed_from_u64(42);
+            let mut products = vec![(Scalar::ZERO, Scalar::ZERO); N];
+            let mut protocols: GenProtocol<Vec<Scalar>> = Vec::with_capacity(participants.len());
+            for p in &participants {
+                let a_iv = (0..N)
+                    .map(|_| Scalar::generate_biased(&mut rng))
+                    .collect::<Vec<_>>();
+                let b_iv = (0..N)
+                    .map(|_| Scalar::generate_biased(&mut rng))
+                    .collect::<Vec<_>>();
+                for ((a, b), (a_i, b_i)) in products.iter_mut().zip(a_iv.iter().zip(&b_iv)) {
+                    *a += a_i;
+                    *b += b_i;
+                }
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let ctx = Comms::new();
+                let participant_list = ParticipantList::new(&participants).unwrap();
+                let prot: Box<dyn Protocol<Output = Vec<Scalar>>> = match chunk {
+                    Some(chunk) => Box::new(make_protocol(
+                        ctx.clone(),
+                        multiplication_chunked::<N>(
+                            chunk,
+                            ctx,
+                            sids.clone(),
+                            participant_list,
+                            *p,
+                            a_iv,
+                            b_iv,
+                            rng_p,
+                        ),
+                    )),
+                    None => Box::new(make_protocol(
+                        ctx.clone(),
+                        multiplication_many::<N>(
+                            ctx,
+                            sids.clone(),
+                            participant_list,
+                            *p,
+                            a_iv,
+                            b_iv,
+                            rng_p,
+                        ),
+                    )),
+                };
+                protocols.push((*p, prot));
+            }
+            let result = run_protocol(protocols).unwrap();
+            (products, result)
+        };
+
+        let (products, expected) = run(None);
+        for chunk in [1, 2, N, N + 1] {
+            let (_, result) = run(Some(chunk));
+            assert_eq!(result, expected);
+
+            let c_v = result.iter().fold(vec![Scalar::ZERO; N], |acc, (_, c_iv)| {
+                acc.iter()
+                    .zip(c_iv.iter())
+                    .map(|(acc_i, c_i)| acc_i + c_i)
+                    .collect()
+            });
+            for ((a, b), c) in products.iter().zip(c_v) {
+                assert_eq!(*a * b, c);
+            }
+        }
+    }
 }
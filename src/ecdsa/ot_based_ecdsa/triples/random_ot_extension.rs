@@ -275,7 +275,7 @@ mod test {
     #[test]
     fn test_random_ot() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
-        let ((k0, k1), (delta, k)) = run_batch_random_ot().unwrap();
+        let ((k0, k1), (delta, k)) = run_batch_random_ot(&mut rng).unwrap();
         let batch_size = 16;
         let (sender_out, receiver_out) = run_random_ot(
             (delta, k),
@@ -8,7 +8,6 @@ use super::{
 
 use crate::{
     ecdsa::{Field, Polynomial, ProjectivePoint, Secp256K1ScalarField},
-    test_utils::MockCryptoRng,
     ReconstructionLowerBound,
 };
 
@@ -59,9 +58,11 @@ pub fn deal(
 }
 
 /// Run the batch random OT protocol between two parties.
+///
+/// All the randomness is drawn from `rng`, so a seeded rng gives a reproducible run.
 pub fn run_batch_random_ot(
+    rng: &mut impl CryptoRngCore,
 ) -> Result<(BatchRandomOTOutputSender, BatchRandomOTOutputReceiver), ProtocolError> {
-    let mut rng = MockCryptoRng::seed_from_u64(42);
     let s = Participant::from(0u32);
     let r = Participant::from(1u32);
     let comms_s = Comms::new();
@@ -71,12 +72,11 @@ pub fn run_batch_random_ot(
         s,
         r,
         &mut make_protocol(comms_s.clone(), {
-            let y = super::batch_random_ot::batch_random_ot_sender_helper(&mut rng);
+            let y = super::batch_random_ot::batch_random_ot_sender_helper(&mut *rng);
             super::batch_random_ot::batch_random_ot_sender(comms_s.private_channel(s, r), y)
         }),
         &mut make_protocol(comms_r.clone(), {
-            let (delta, x) =
-                super::batch_random_ot::batch_random_ot_receiver_random_helper(&mut rng);
+            let (delta, x) = super::batch_random_ot::batch_random_ot_receiver_random_helper(rng);
             super::batch_random_ot::batch_random_ot_receiver(
                 comms_r.private_channel(r, s),
                 delta,
@@ -85,3 +85,41 @@ pub fn run_batch_random_ot(
         }),
     )
 }
+
+/// Run the batch random OT many protocol between two parties.
+///
+/// Both parties are seeded from `rng`, so a seeded rng gives a reproducible run.
+pub fn run_batch_random_ot_many<const N: usize, R: CryptoRngCore + SeedableRng + Send + 'static>(
+    rng: &mut R,
+) -> Result<
+    (
+        Vec<BatchRandomOTOutputSender>,
+        Vec<BatchRandomOTOutputReceiver>,
+    ),
+    ProtocolError,
+> {
+    let s = Participant::from(0u32);
+    let r = Participant::from(1u32);
+    let comms_s = Comms::new();
+    let comms_r = Comms::new();
+    let rng_1 = R::seed_from_u64(rng.next_u64());
+    let rng_2 = R::seed_from_u64(rng.next_u64());
+    run_two_party_protocol(
+        s,
+        r,
+        &mut make_protocol(
+            comms_s.clone(),
+            super::batch_random_ot::batch_random_ot_sender_many::<N>(
+                comms_s.private_channel(s, r),
+                rng_1,
+            ),
+        ),
+        &mut make_protocol(
+            comms_r.clone(),
+            super::batch_random_ot::batch_random_ot_receiver_many::<N>(
+                comms_r.private_channel(r, s),
+                rng_2,
+            ),
+        ),
+    )
+}
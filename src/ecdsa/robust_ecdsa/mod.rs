@@ -1,15 +1,20 @@
 pub mod presign;
 pub mod sign;
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
 
 use crate::{
-    ecdsa::{AffinePoint, KeygenOutput, RerandomizationArguments, Scalar},
+    crypto::secret::zeroize_on_drop,
+    ecdsa::{
+        assert_nonzero_x_coordinate, has_even_y, AffinePoint, CoefficientCommitment, KeygenOutput,
+        PresignId, RerandomizationArguments, Scalar,
+    },
     errors::ProtocolError,
+    participants::Participant,
     MaxMalicious,
 };
 use serde::{Deserialize, Serialize};
-use zeroize::ZeroizeOnDrop;
+use std::collections::BTreeMap;
 
 /// The necessary inputs for the creation of a presignature.
 pub struct PresignArguments {
@@ -18,15 +23,41 @@ pub struct PresignArguments {
     pub keygen_out: KeygenOutput,
     /// The desired threshold for the presignature, which must match the original threshold
     pub max_malicious: MaxMalicious,
+    /// Whether to check the `R`, `w` and `W` shares of the other participants by
+    /// interpolation, detecting malicious behaviour at the cost of extra computation.
+    /// The messages are the same either way, so participants may pick different values.
+    pub active_security: bool,
+}
+
+/// The public messages of a presigning run, checked offline by
+/// [`presign::verify_presign_transcript`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresignTranscript {
+    /// The max number of malicious parties the run was set up with.
+    pub max_malicious: MaxMalicious,
+    /// The shares of `R` and `w` sent by each participant in the second round.
+    pub big_r_w_shares: BTreeMap<Participant, (CoefficientCommitment, Scalar)>,
+    /// The share of `W` sent by each participant in the third round.
+    pub big_w_shares: BTreeMap<Participant, CoefficientCommitment>,
 }
 
 /// The output of the presigning protocol.
 /// Contains the signature precomputed elements
 /// independently of the message
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ZeroizeOnDrop)]
+///
+/// **Warning:** a serialized presignature holds our secret nonce shares in the clear,
+/// and leaking it together with a signature made from it reveals the signing key share.
+/// Serialization is thus only available behind the `store-presignatures` feature, and
+/// must only be written to encrypted storage. The encoding starts with a version, and
+/// an unknown version fails to deserialize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "store-presignatures",
+    derive(Serialize, Deserialize),
+    serde(into = "StoredPresignOutput", try_from = "StoredPresignOutput")
+)]
 pub struct PresignOutput {
     /// The public nonce commitment.
-    #[zeroize(skip)]
     pub big_r: AffinePoint,
 
     /// Our secret shares of the nonces.
@@ -34,39 +65,168 @@ pub struct PresignOutput {
     pub e: Scalar,
     pub alpha: Scalar,
     pub beta: Scalar,
+
+    /// The identifier of the presignature, the same for every participant.
+    pub id: PresignId,
+
+    /// The epoch of the key shares the presignature was made with.
+    pub epoch: u64,
+}
+
+zeroize_on_drop!(PresignOutput, [c, e, alpha, beta]);
+
+impl PresignOutput {
+    /// Negates the nonce shares if needed so that `big_r` has an even y coordinate.
+    ///
+    /// As every participant holds the same `big_r`, applying this locally on each
+    /// share keeps the presignature consistent: `-R` is the commitment to `-k`.
+    /// The shares of `e` mask a sharing of zero and are left untouched.
+    pub fn force_even_y(&mut self) {
+        if has_even_y(&self.big_r) {
+            return;
+        }
+        self.big_r = -self.big_r;
+        self.c = -self.c;
+        self.alpha = -self.alpha;
+        self.beta = -self.beta;
+    }
+}
+
+/// The serialized form of a [`PresignOutput`], prefixed by its format version.
+#[cfg(feature = "store-presignatures")]
+#[derive(Serialize, Deserialize)]
+struct StoredPresignOutput {
+    version: u8,
+    big_r: AffinePoint,
+    c: Scalar,
+    e: Scalar,
+    alpha: Scalar,
+    beta: Scalar,
+    id: PresignId,
+    #[serde(default, skip_serializing_if = "crate::is_initial_epoch")]
+    epoch: u64,
+}
+
+#[cfg(feature = "store-presignatures")]
+zeroize_on_drop!(StoredPresignOutput, [c, e, alpha, beta]);
+
+#[cfg(feature = "store-presignatures")]
+impl StoredPresignOutput {
+    const VERSION: u8 = 1;
+}
+
+#[cfg(feature = "store-presignatures")]
+impl From<PresignOutput> for StoredPresignOutput {
+    fn from(presignature: PresignOutput) -> Self {
+        Self {
+            version: Self::VERSION,
+            big_r: presignature.big_r,
+            c: presignature.c,
+            e: presignature.e,
+            alpha: presignature.alpha,
+            beta: presignature.beta,
+            id: presignature.id,
+            epoch: presignature.epoch,
+        }
+    }
+}
+
+#[cfg(feature = "store-presignatures")]
+impl TryFrom<StoredPresignOutput> for PresignOutput {
+    type Error = ProtocolError;
+
+    fn try_from(stored: StoredPresignOutput) -> Result<Self, Self::Error> {
+        if stored.version != StoredPresignOutput::VERSION {
+            return Err(ProtocolError::DeserializationError(format!(
+                "unsupported presignature version {}",
+                stored.version
+            )));
+        }
+        Ok(Self {
+            big_r: stored.big_r,
+            c: stored.c,
+            e: stored.e,
+            alpha: stored.alpha,
+            beta: stored.beta,
+            id: stored.id,
+            epoch: stored.epoch,
+        })
+    }
 }
 
 /// The output of the presigning protocol.
 /// Contains the signature precomputed elements
 /// independently of the message
-#[derive(Debug, Clone, Serialize, Deserialize, ZeroizeOnDrop)]
+///
+/// Like [`PresignOutput`], it holds secret nonce shares, so serialization is only
+/// available behind the `store-presignatures` feature.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "store-presignatures", derive(Serialize, Deserialize))]
 pub struct RerandomizedPresignOutput {
     /// The rerandomized public nonce commitment.
-    #[zeroize(skip)]
     big_r: AffinePoint,
 
     /// Our rerandomized secret shares of the nonces.
     e: Scalar,
     alpha: Scalar,
     beta: Scalar,
+
+    /// The identifier of the original presignature.
+    id: PresignId,
+
+    /// The epoch of the key shares the original presignature was made with.
+    #[cfg_attr(
+        feature = "store-presignatures",
+        serde(default, skip_serializing_if = "crate::is_initial_epoch")
+    )]
+    epoch: u64,
 }
 
+zeroize_on_drop!(RerandomizedPresignOutput, [e, alpha, beta]);
+
 impl RerandomizedPresignOutput {
     pub fn rerandomize_presign(
         presignature: &PresignOutput,
         args: &RerandomizationArguments,
     ) -> Result<Self, ProtocolError> {
-        if presignature.big_r != args.big_r {
-            return Err(ProtocolError::IncompatibleRerandomizationInputs);
-        }
-        let delta = args.derive_randomness()?;
-        if delta.is_zero().into() {
-            return Err(ProtocolError::ZeroScalar);
-        }
+        let delta = args.derive_delta(&presignature.big_r)?;
 
         // cannot be zero due to the previous check
         let inv_delta = delta.invert().unwrap();
 
+        Ok(Self::rerandomize_with(presignature, args, delta, inv_delta))
+    }
+
+    /// Rerandomizes a pool of presignatures, `presignatures[i]` with `args[i]`.
+    ///
+    /// The outputs match [`Self::rerandomize_presign`] item by item, but all the
+    /// `delta^{-1}` are computed with a single field inversion.
+    pub fn rerandomize_presign_many(
+        presignatures: &[PresignOutput],
+        args: &[RerandomizationArguments],
+    ) -> Result<Vec<Self>, ProtocolError> {
+        let big_rs = presignatures
+            .iter()
+            .map(|presignature| presignature.big_r)
+            .collect::<Vec<_>>();
+        let (deltas, inv_deltas) =
+            RerandomizationArguments::derive_deltas_and_inverses(&big_rs, args)?;
+        Ok(presignatures
+            .iter()
+            .zip(args)
+            .zip(deltas.into_iter().zip(inv_deltas))
+            .map(|((presignature, args), (delta, inv_delta))| {
+                Self::rerandomize_with(presignature, args, delta, inv_delta)
+            })
+            .collect())
+    }
+
+    fn rerandomize_with(
+        presignature: &PresignOutput,
+        args: &RerandomizationArguments,
+        delta: Scalar,
+        inv_delta: Scalar,
+    ) -> Self {
         // delta * R
         let rerandomized_big_r = presignature.big_r * delta;
 
@@ -77,12 +237,39 @@ impl RerandomizedPresignOutput {
         let rerandomized_beta =
             (presignature.beta + presignature.c * args.tweak.value()) * inv_delta;
 
-        Ok(Self {
+        Self {
             big_r: rerandomized_big_r.into(),
             alpha: rerandomized_alpha,
             beta: rerandomized_beta,
             e: presignature.e,
-        })
+            id: presignature.id,
+            epoch: presignature.epoch,
+        }
+    }
+
+    /// Checks locally, before running [`sign::sign`], that the public inputs of signing
+    /// `msg_hash` under `public_key` with this presignature are valid: neither the key
+    /// nor the nonce commitment is the identity, the nonce commitment has a nonzero x
+    /// coordinate and the message hash is nonzero, as [`sign::sign`] requires.
+    ///
+    /// The secret nonce shares can only be checked against the ones of the other
+    /// participants, so a successful check does not guarantee the signing round succeeds.
+    pub fn check_usable(
+        &self,
+        public_key: &AffinePoint,
+        msg_hash: &Scalar,
+    ) -> Result<(), ProtocolError> {
+        if *public_key == AffinePoint::IDENTITY || self.big_r == AffinePoint::IDENTITY {
+            return Err(ProtocolError::IdentityElement);
+        }
+        assert_nonzero_x_coordinate(&self.big_r)?;
+        sign::assert_nonzero_msg_hash(msg_hash)
+            .map_err(|err| ProtocolError::InvalidInput(err.to_string()))
+    }
+
+    /// The identifier of the original presignature.
+    pub const fn id(&self) -> PresignId {
+        self.id
     }
 
     #[cfg(test)]
@@ -94,6 +281,8 @@ impl RerandomizedPresignOutput {
             alpha: presignature.alpha,
             beta: presignature.beta,
             e: presignature.e,
+            id: presignature.id,
+            epoch: presignature.epoch,
         }
     }
 }
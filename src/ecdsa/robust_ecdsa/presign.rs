@@ -1,17 +1,18 @@
-use super::{PresignArguments, PresignOutput};
+use super::{PresignArguments, PresignOutput, PresignTranscript};
 use crate::participants::{Participant, ParticipantList, ParticipantMap};
 use crate::{
     ecdsa::{
-        CoefficientCommitment, Field, Polynomial, PolynomialCommitment, Scalar,
-        Secp256K1ScalarField, Secp256K1Sha256,
+        assert_nonzero_x_coordinate, CoefficientCommitment, Field, Polynomial,
+        PolynomialCommitment, PresignId, Scalar, Secp256K1ScalarField, Secp256K1Sha256,
     },
     errors::{InitializationError, ProtocolError},
     protocol::{
-        helpers::recv_from_others,
-        internal::{make_protocol, Comms, SharedChannel},
-        Protocol,
+        helpers::collect_from_all,
+        internal::{make_protocol, Comms},
+        Channel, Protocol,
     },
-    SigningShare,
+    thresholds::{validate_threshold, Scheme},
+    MaxMalicious, SigningShare,
 };
 use frost_core::serialization::SerializableScalar;
 use frost_secp256k1::{Group, Secp256K1Group};
@@ -27,12 +28,63 @@ type C = Secp256K1Sha256;
 ///
 /// This work does depend on the private key though, and it's crucial
 /// that a presignature is never reused.
+///
+/// The interpolation checks on `R`, `w` and `W` only run when
+/// [`PresignArguments::active_security`] is set.
 pub fn presign(
     participants: &[Participant],
     me: Participant,
     args: PresignArguments,
     rng: impl CryptoRngCore + Send + 'static,
 ) -> Result<impl Protocol<Output = PresignOutput>, InitializationError> {
+    let participants = assert_presign_inputs(participants, me, args.max_malicious)?;
+
+    let ctx = Comms::new();
+    let fut = do_presign(ctx.shared_channel(), participants, me, args, rng);
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Like [`presign`], but returns the protocol core as a future exchanging its
+/// messages over `chan`, to be awaited on the caller's own executor.
+#[cfg(feature = "custom-executor")]
+pub fn presign_with_channel(
+    chan: impl Channel,
+    participants: &[Participant],
+    me: Participant,
+    args: PresignArguments,
+    rng: impl CryptoRngCore,
+) -> Result<
+    impl std::future::Future<Output = Result<PresignOutput, ProtocolError>>,
+    InitializationError,
+> {
+    let participants = assert_presign_inputs(participants, me, args.max_malicious)?;
+    Ok(do_presign(chan, participants, me, args, rng))
+}
+
+/// Like [`presign`], but also outputs the public messages of the run, to be
+/// checked later with [`verify_presign_transcript`].
+pub fn presign_with_transcript(
+    participants: &[Participant],
+    me: Participant,
+    args: PresignArguments,
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = (PresignOutput, PresignTranscript)>, InitializationError> {
+    let participants = assert_presign_inputs(participants, me, args.max_malicious)?;
+
+    let ctx = Comms::new();
+    let fut = do_presign_with_transcript(ctx.shared_channel(), participants, me, args, rng);
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Checks the participants of a presigning session with at most `max_malicious` faulty parties
+pub(crate) fn assert_presign_inputs(
+    participants: &[Participant],
+    me: Participant,
+    max_malicious: MaxMalicious,
+) -> Result<ParticipantList, InitializationError> {
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -49,56 +101,60 @@ pub fn presign(
         });
     }
 
-    if args.max_malicious.value() > participants.len() {
-        return Err(InitializationError::BadParameters(
-            "max_malicious must be less than or equals to participant count".to_string(),
-        ));
-    }
-
-    let robust_ecdsa_threshold = args
-        .max_malicious
-        .value()
-        .checked_mul(2)
-        .and_then(|v| v.checked_add(1))
-        .ok_or_else(|| {
-            InitializationError::BadParameters(
-                "2*max_malicious+1 must be less than usize::MAX".to_string(),
-            )
-        })?;
-    if robust_ecdsa_threshold > participants.len() {
-        return Err(InitializationError::BadParameters(
-            "2*max_malicious+1 must be less than or equals to participant count".to_string(),
-        ));
+    let max = participants.len().saturating_sub(1) / 2;
+    if max_malicious.value() > max {
+        return Err(InitializationError::MaxMaliciousTooLarge {
+            max_malicious: max_malicious.value(),
+            participants: participants.len(),
+            max,
+        });
     }
-
     // To prevent split-view attacks documented in docs/ecdsa/robust_ecdsa/signing.md
-    if participants.len() != robust_ecdsa_threshold {
-        return Err(InitializationError::BadParameters(
-            "the number of participants during presigning must be exactly 2*max_malicious+1 to avoid split view attacks".to_string(),
-        ));
-    }
-
-    let ctx = Comms::new();
-    let fut = do_presign(ctx.shared_channel(), participants, me, args, rng);
-    Ok(make_protocol(ctx, fut))
+    validate_threshold(
+        Scheme::RobustEcdsa,
+        participants.len(),
+        max_malicious.value(),
+    )?;
+    Ok(participants)
 }
 
 /// /!\ Warning: the threshold in this scheme is the exactly the
 ///              same as the max number of malicious parties.
+pub(crate) async fn do_presign(
+    chan: impl Channel,
+    participants: ParticipantList,
+    me: Participant,
+    args: PresignArguments,
+    rng: impl CryptoRngCore,
+) -> Result<PresignOutput, ProtocolError> {
+    let (output, _) = do_presign_with_transcript(chan, participants, me, args, rng).await?;
+    Ok(output)
+}
+
+/// Runs the presigning protocol, recording the public messages of the run.
 #[allow(clippy::too_many_lines)]
-async fn do_presign(
-    mut chan: SharedChannel,
+async fn do_presign_with_transcript(
+    mut chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     args: PresignArguments,
     mut rng: impl CryptoRngCore,
-) -> Result<PresignOutput, ProtocolError> {
+) -> Result<(PresignOutput, PresignTranscript), ProtocolError> {
     let rng = &mut rng;
     let threshold = args.max_malicious.value();
     // Round 1
     let degree = threshold
         .checked_mul(2)
         .ok_or(ProtocolError::IntegerOverflow)?;
+    // the interpolations below need 2t+1 shares, which `assert_presign_inputs`
+    // guarantees for the public entry points, but do not rely on every caller
+    let needed = degree + 1;
+    if participants.len() < needed {
+        return Err(ProtocolError::AssertionFailed(format!(
+            "robust presigning with max_malicious {threshold} needs at least {needed} participants, got {}",
+            participants.len()
+        )));
+    }
     let polynomials = [
         // Step 1.1
         // degree t random secret shares where t is the max number of malicious parties
@@ -132,10 +188,12 @@ async fn do_presign(
     // Round 2
     // Step 2.1
     // Receive evaluations from all participants
-    for (_, package) in recv_from_others(&chan, wait_round_1, &participants, me).await? {
-        // Step 2.2
-        // calculate the respective sum of the different shares received from each participant
-        shares.add_shares(&package);
+    let packages =
+        collect_from_all(&chan, wait_round_1, &participants, me, Shares::from_package).await?;
+    // Step 2.2
+    // calculate the respective sum of the different shares received from each participant
+    for package in packages.values() {
+        shares.add_shares(package);
     }
 
     // Step 2.3
@@ -174,52 +232,159 @@ async fn do_presign(
         .map(Participant::scalar::<C>)
         .collect();
 
-    let signingshares = signingshares_map
+    let w_shares = signingshares_map
+        .into_vec_or_none()
+        .ok_or(ProtocolError::InvalidInterpolationArguments)?;
+    let big_r_shares = verifyingshares_map
         .into_vec_or_none()
         .ok_or(ProtocolError::InvalidInterpolationArguments)?;
 
-    // exponent interpolation of big R
-    let verifying_shares = verifyingshares_map
+    // Steps 3.2 to 3.6
+    let (big_r, w) = interpolate_big_r_w(
+        &identifiers,
+        &big_r_shares,
+        &w_shares,
+        threshold,
+        args.active_security,
+    )?;
+
+    // Step 3.7
+    // Compute W_me = R^{a_me}
+    // W is only used by the active security checks, but it is exchanged in
+    // both modes so that the messages do not depend on the option
+
+    let big_w_me = CoefficientCommitment::new(big_r.value() * shares.a());
+    // Step 3.8
+    // Send W_me
+    let wait_round_3 = chan.next_waitpoint();
+    chan.send_many(wait_round_3, &big_w_me)?;
+
+    // Step 3.9
+    // Receive W_i
+    let mut wshares_map = collect_from_all(
+        &chan,
+        wait_round_3,
+        &participants,
+        me,
+        |_, big_w_p: CoefficientCommitment| Ok(big_w_p),
+    )
+    .await?;
+    wshares_map.put(me, big_w_me);
+    let big_w_shares = wshares_map
         .into_vec_or_none()
         .ok_or(ProtocolError::InvalidInterpolationArguments)?;
 
-    let (threshold_plus1_identifiers, _) = identifiers
-        .split_at_checked(threshold + 1)
-        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough identifiers".to_string()))?;
-    let (threshold_plus1_verifying_shares, _) = verifying_shares
-        .split_at_checked(threshold + 1)
-        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough verifying shares".to_string()))?;
+    // Compute exponent interpolation checks
+    // Steps 3.10 to 3.12
+    if args.active_security {
+        check_big_w(&identifiers, &big_w_shares, threshold, &w)?;
+    }
 
-    // check that the exponent interpolations match what has been received
-    for (identifier, verifying_share) in identifiers
+    // Step 3.13
+    // w is non-zero due to previous check and so I can unwrap safely
+    let c_me = w.invert().unwrap() * shares.a();
+
+    // Step 3.14
+    // Some extra computation is pushed in this offline phase
+    let alpha_me = c_me + shares.d();
+
+    // Step 3.15
+    let x_me = args.keygen_out.private_share.to_scalar();
+    let beta_me = c_me * x_me;
+
+    let big_r = big_r.value().to_affine();
+    let output = PresignOutput {
+        big_r,
+        alpha: alpha_me,
+        beta: beta_me,
+        c: c_me,
+        e: shares.e(),
+        id: PresignId::derive(&big_r, &participants),
+        epoch: args.keygen_out.epoch,
+    };
+    let transcript = PresignTranscript {
+        max_malicious: args.max_malicious,
+        big_r_w_shares: participants
+            .participants()
+            .iter()
+            .copied()
+            .zip(
+                big_r_shares
+                    .into_iter()
+                    .zip(w_shares.iter().map(|w_p| w_p.0)),
+            )
+            .collect(),
+        big_w_shares: participants
+            .participants()
+            .iter()
+            .copied()
+            .zip(big_w_shares)
+            .collect(),
+    };
+    Ok((output, transcript))
+}
+
+/// Checks offline that the messages of a presigning run satisfy the
+/// exponent interpolation checks of [`presign`], without any network.
+///
+/// The checks are the ones of a live run with
+/// [`PresignArguments::active_security`] set.
+pub fn verify_presign_transcript(
+    transcript: &PresignTranscript,
+    participants: &[Participant],
+) -> Result<(), ProtocolError> {
+    let participants = ParticipantList::new(participants)
+        .ok_or_else(|| ProtocolError::InvalidInput("duplicate participants".to_string()))?;
+    if !participants
+        .participants()
         .iter()
-        .skip(threshold + 1)
-        .zip(verifying_shares.iter().skip(threshold + 1))
+        .eq(transcript.big_r_w_shares.keys())
+        || !participants
+            .participants()
+            .iter()
+            .eq(transcript.big_w_shares.keys())
     {
-        // Step 3.2
-        // exponent interpolation for (R0, .., Rt; i)
-        let big_r_i = PolynomialCommitment::eval_exponent_interpolation(
-            threshold_plus1_identifiers,
-            threshold_plus1_verifying_shares,
-            Some(identifier),
-        )?;
-
-        // check the interpolated R values match the received ones
-        if big_r_i != *verifying_share {
-            return Err(ProtocolError::AssertionFailed(
-                "Exponent interpolation check failed.".to_string(),
-            ));
-        }
+        return Err(ProtocolError::InvalidInput(
+            "the transcript does not hold one message per participant".to_string(),
+        ));
     }
-    // Step 3.3
-    // get only the first t+1 elements to interpolate
-    // we know that identifiers.len()>threshold+1
-    // evaluate the exponent interpolation on zero
-    let big_r = PolynomialCommitment::eval_exponent_interpolation(
-        threshold_plus1_identifiers,
-        threshold_plus1_verifying_shares,
-        None,
-    )?;
+    let threshold = transcript.max_malicious.value();
+    let identifiers: Vec<Scalar> = participants
+        .participants()
+        .iter()
+        .map(Participant::scalar::<C>)
+        .collect();
+    let big_r_shares: Vec<_> = transcript
+        .big_r_w_shares
+        .values()
+        .map(|(big_r_p, _)| *big_r_p)
+        .collect();
+    let w_shares: Vec<_> = transcript
+        .big_r_w_shares
+        .values()
+        .map(|(_, w_p)| SerializableScalar::<C>(*w_p))
+        .collect();
+    let big_w_shares: Vec<_> = transcript.big_w_shares.values().copied().collect();
+
+    let (_, w) = interpolate_big_r_w(&identifiers, &big_r_shares, &w_shares, threshold, true)?;
+    check_big_w(&identifiers, &big_w_shares, threshold, &w)
+}
+
+/// Interpolates `R` and `w` from the shares of the second round, ordered as `identifiers`,
+/// rejecting an identity `R`, one yielding a zero signature part `r`, and a zero `w`.
+///
+/// With active security, the shares past the first `t+1` of `R` and `2t+1` of `w`
+/// are checked to lie on the polynomials interpolated from the first ones.
+fn interpolate_big_r_w(
+    identifiers: &[Scalar],
+    big_r_shares: &[CoefficientCommitment],
+    w_shares: &[SerializableScalar<C>],
+    threshold: usize,
+    active_security: bool,
+) -> Result<(CoefficientCommitment, Scalar), ProtocolError> {
+    // Steps 3.2 and 3.3
+    // exponent interpolation for (R0, .., Rt; i), then for (R0, .., Rt; 0)
+    let big_r = exponent_interpolation(identifiers, big_r_shares, threshold, active_security)?;
 
     // Step 3.4
     // check R is not identity
@@ -230,105 +395,108 @@ async fn do_presign(
     {
         return Err(ProtocolError::IdentityElement);
     }
+    // check that R yields a nonzero signature part r
+    assert_nonzero_x_coordinate(&big_r.value().to_affine())?;
 
     // Step 3.5
     // polynomial interpolation of w
-    let (w_2tp1_identifiers, _) = identifiers
-        .split_at_checked(2 * threshold + 1)
-        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough identifiers".to_string()))?;
-    let (w_2tp1_verifying_shares, _) = signingshares
-        .split_at_checked(2 * threshold + 1)
-        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough verifying shares".to_string()))?;
-    let w = Polynomial::eval_interpolation(w_2tp1_identifiers, w_2tp1_verifying_shares, None)?;
+    let degree = threshold
+        .checked_mul(2)
+        .ok_or(ProtocolError::IntegerOverflow)?;
+    let w = interpolation(identifiers, w_shares, degree, active_security)?;
 
     // Step 3.6
     // check w is non-zero
     if w.0.is_zero().into() {
         return Err(ProtocolError::ZeroScalar);
     }
+    Ok((big_r, w.0))
+}
 
-    // Step 3.7
-    // Compute W_me = R^{a_me}
-    let big_w_me = CoefficientCommitment::new(big_r.value() * shares.a());
-    // Step 3.8
-    // Send W_me
-    let wait_round_3 = chan.next_waitpoint();
-    chan.send_many(wait_round_3, &big_w_me)?;
-
-    // Step 3.9
-    // Receive W_i
-    let mut wshares_map = ParticipantMap::new(&participants);
-    wshares_map.put(me, big_w_me);
-    while !wshares_map.full() {
-        let (from, big_w_p) = chan.recv(wait_round_3).await?;
-        wshares_map.put(from, big_w_p);
-    }
-    // Compute exponent interpolation checks
-    let wshares = wshares_map
-        .into_vec_or_none()
-        .ok_or(ProtocolError::InvalidInterpolationArguments)?;
-    let (threshold_plus1_wshares, _) = wshares
-        .split_at_checked(threshold + 1)
-        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough wshares".to_string()))?;
-
-    for (identifier, wshare) in identifiers
-        .iter()
-        .skip(threshold + 1)
-        .zip(wshares.iter().skip(threshold + 1))
-    {
-        // exponent interpolation for (W0, .., Wt; i)
-        let big_w_i = PolynomialCommitment::eval_exponent_interpolation(
-            threshold_plus1_identifiers,
-            threshold_plus1_wshares,
-            Some(identifier),
-        )?;
-        // check the interpolated W values match the received ones
-        if big_w_i != *wshare {
-            return Err(ProtocolError::AssertionFailed(
-                "Exponent interpolation check failed.".to_string(),
-            ));
-        }
-    }
-
+/// Checks that `W`, interpolated from the shares of the third round ordered as
+/// `identifiers`, is `g^w`, and that the shares past the first `t+1` lie on the
+/// polynomial interpolated from the first ones.
+fn check_big_w(
+    identifiers: &[Scalar],
+    big_w_shares: &[CoefficientCommitment],
+    threshold: usize,
+    w: &Scalar,
+) -> Result<(), ProtocolError> {
     // Step 3.10
-    // compute W as exponent interpolation for (W0, .., Wt; 0)
-    let big_w = PolynomialCommitment::eval_exponent_interpolation(
-        threshold_plus1_identifiers,
-        threshold_plus1_wshares,
-        None,
-    )?;
+    // exponent interpolation for (W0, .., Wt; i), then for (W0, .., Wt; 0)
+    let big_w = exponent_interpolation(identifiers, big_w_shares, threshold, true)?;
 
     // Step 3.12
     // check W == g^w
     if big_w
         .value()
-        .ct_ne(&(<Secp256K1Group as Group>::generator() * w.0))
+        .ct_ne(&(<Secp256K1Group as Group>::generator() * *w))
         .into()
     {
         return Err(ProtocolError::AssertionFailed(
             "Exponent interpolation check failed.".to_string(),
         ));
     }
+    Ok(())
+}
 
-    // Step 3.13
-    // w is non-zero due to previous check and so I can unwrap safely
-    let c_me = w.0.invert().unwrap() * shares.a();
-
-    // Step 3.14
-    // Some extra computation is pushed in this offline phase
-    let alpha_me = c_me + shares.d();
-
-    // Step 3.15
-    let x_me = args.keygen_out.private_share.to_scalar();
-    let beta_me = c_me * x_me;
+/// Interpolates in the exponent the value at zero of the polynomial of `degree` given by
+/// the first `degree + 1` shares, checking first that the others lie on it if `check_others`.
+fn exponent_interpolation(
+    identifiers: &[Scalar],
+    shares: &[CoefficientCommitment],
+    degree: usize,
+    check_others: bool,
+) -> Result<CoefficientCommitment, ProtocolError> {
+    let (first_identifiers, other_identifiers) = identifiers
+        .split_at_checked(degree + 1)
+        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough identifiers".to_string()))?;
+    let (first_shares, other_shares) = shares
+        .split_at_checked(degree + 1)
+        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough shares".to_string()))?;
+    if check_others {
+        for (identifier, share) in other_identifiers.iter().zip(other_shares) {
+            let expected = PolynomialCommitment::eval_exponent_interpolation(
+                first_identifiers,
+                first_shares,
+                Some(identifier),
+            )?;
+            if expected != *share {
+                return Err(ProtocolError::AssertionFailed(
+                    "Exponent interpolation check failed.".to_string(),
+                ));
+            }
+        }
+    }
+    PolynomialCommitment::eval_exponent_interpolation(first_identifiers, first_shares, None)
+}
 
-    Ok(PresignOutput {
-        big_r: big_r.value().to_affine(),
-        alpha: alpha_me,
-        beta: beta_me,
-        c: c_me,
-        e: shares.e(),
-    })
+/// Interpolates the value at zero of the polynomial of `degree` given by the first
+/// `degree + 1` shares, checking first that the others lie on it if `check_others`.
+fn interpolation(
+    identifiers: &[Scalar],
+    shares: &[SerializableScalar<C>],
+    degree: usize,
+    check_others: bool,
+) -> Result<SerializableScalar<C>, ProtocolError> {
+    let (first_identifiers, other_identifiers) = identifiers
+        .split_at_checked(degree + 1)
+        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough identifiers".to_string()))?;
+    let (first_shares, other_shares) = shares
+        .split_at_checked(degree + 1)
+        .ok_or_else(|| ProtocolError::AssertionFailed("Not enough shares".to_string()))?;
+    if check_others {
+        for (identifier, share) in other_identifiers.iter().zip(other_shares) {
+            let expected =
+                Polynomial::eval_interpolation(first_identifiers, first_shares, Some(identifier))?;
+            if expected.0 != share.0 {
+                return Err(ProtocolError::AssertionFailed(
+                    "Interpolation check failed.".to_string(),
+                ));
+            }
+        }
+    }
+    Polynomial::eval_interpolation(first_identifiers, first_shares, None)
 }
 
 /// Generates a secret polynomial where the constant term is zero
@@ -343,12 +511,15 @@ fn zero_secret_polynomial(
 /// Contains five shares used during presigniture
 /// (k, a, b, d, e)
 #[derive(serde::Deserialize, serde::Serialize)]
-struct Shares([SerializableScalar<C>; 5]);
+struct Shares([SerializableScalar<C>; SHARES_LEN]);
+
+/// The number of shares in a round 1 package
+const SHARES_LEN: usize = 5;
 
 impl Shares {
     /// Constructs a new Shares out of five polynomials
     pub(crate) fn new(
-        polynomials: &[Polynomial; 5],
+        polynomials: &[Polynomial; SHARES_LEN],
         p: Participant,
     ) -> Result<Self, ProtocolError> {
         // iterate over the polynomials and map them
@@ -361,6 +532,23 @@ impl Shares {
         Ok(Self(shares))
     }
 
+    /// Constructs Shares out of the package received from `from`,
+    /// which must contain exactly one share per polynomial
+    pub(crate) fn from_package(
+        from: Participant,
+        package: Vec<SerializableScalar<C>>,
+    ) -> Result<Self, ProtocolError> {
+        let got = package.len();
+        let shares = package
+            .try_into()
+            .map_err(|_| ProtocolError::MalformedPackage {
+                from,
+                expected: SHARES_LEN,
+                got,
+            })?;
+        Ok(Self(shares))
+    }
+
     /// Returns k element
     pub(crate) fn k(&self) -> Scalar {
         self.0[0].0
@@ -400,9 +588,13 @@ mod test {
     use frost_secp256k1::VerifyingKey;
     use k256::ProjectivePoint;
     use rand::{RngCore, SeedableRng};
+    use std::collections::BTreeMap;
 
     use crate::ecdsa::KeygenOutput;
+    use crate::errors::ErrorCategory;
     use crate::test_utils::{generate_participants, run_protocol, GenProtocol, MockCryptoRng};
+    #[cfg(feature = "custom-executor")]
+    use crate::test_utils::{run_futures, MemoryChannel};
 
     #[test]
     fn test_presign() {
@@ -424,6 +616,7 @@ mod test {
             let keygen_out = KeygenOutput {
                 private_share: SigningShare::new(private_share.0),
                 public_key: verifying_key,
+                epoch: 0,
             };
 
             let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
@@ -434,6 +627,7 @@ mod test {
                 PresignArguments {
                     keygen_out,
                     max_malicious: max_malicious.into(),
+                    active_security: true,
                 },
                 rng_p,
             )
@@ -449,4 +643,391 @@ mod test {
 
         insta::assert_json_snapshot!(result);
     }
+
+    fn run_presign_with(
+        active_security: impl Fn(usize) -> bool,
+    ) -> Vec<(Participant, PresignOutput)> {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(5);
+        let max_malicious = 2;
+        let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+
+        let mut protocols: GenProtocol<PresignOutput> = Vec::with_capacity(participants.len());
+        for (i, p) in participants.iter().enumerate() {
+            let keygen_out = KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                public_key: VerifyingKey::new(big_x),
+                epoch: 0,
+            };
+            let protocol = presign(
+                &participants,
+                *p,
+                PresignArguments {
+                    keygen_out,
+                    max_malicious: max_malicious.into(),
+                    active_security: active_security(i),
+                },
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        run_protocol(protocols).unwrap()
+    }
+
+    #[cfg(feature = "custom-executor")]
+    #[test]
+    fn test_presign_with_channel() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(5);
+        let max_malicious = 2;
+        let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+
+        // the bare futures run over a transport and an executor foreign to the crate
+        let futures = MemoryChannel::connect(&participants)
+            .into_iter()
+            .map(|(p, chan)| {
+                let keygen_out = KeygenOutput {
+                    private_share: SigningShare::new(f.eval_at_participant(p).unwrap().0),
+                    public_key: VerifyingKey::new(big_x),
+                    epoch: 0,
+                };
+                let fut = presign_with_channel(
+                    chan,
+                    &participants,
+                    p,
+                    PresignArguments {
+                        keygen_out,
+                        max_malicious: max_malicious.into(),
+                        active_security: true,
+                    },
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                )
+                .unwrap();
+                (p, fut)
+            })
+            .collect();
+        assert_eq!(run_futures(futures).unwrap(), run_presign_with(|_| true));
+    }
+
+    #[test]
+    fn test_do_presign_participants_boundary() {
+        let max_malicious: usize = 2;
+        let run = |participants: &[Participant]| {
+            let mut rng = MockCryptoRng::seed_from_u64(42);
+            let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+            let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+            let mut protocols: GenProtocol<PresignOutput> = Vec::with_capacity(participants.len());
+            for p in participants {
+                let args = PresignArguments {
+                    keygen_out: KeygenOutput {
+                        private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                        public_key: VerifyingKey::new(big_x),
+                        epoch: 0,
+                    },
+                    max_malicious: max_malicious.into(),
+                    active_security: true,
+                };
+                // bypasses the checks of `presign`
+                let ctx = Comms::new();
+                let fut = do_presign(
+                    ctx.shared_channel(),
+                    ParticipantList::new(participants).unwrap(),
+                    *p,
+                    args,
+                    MockCryptoRng::seed_from_u64(rng.next_u64()),
+                );
+                protocols.push((*p, Box::new(make_protocol(ctx, fut))));
+            }
+            run_protocol(protocols)
+        };
+
+        // exactly 2t+1 participants is enough
+        assert_eq!(run(&generate_participants(5)).unwrap().len(), 5);
+        // one less is reported rather than panicking
+        let err = run(&generate_participants(4)).unwrap_err();
+        assert_eq!(
+            err,
+            ProtocolError::AssertionFailed(
+                "robust presigning with max_malicious 2 needs at least 5 participants, got 4"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_presign_rejects_malformed_package() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(5);
+        let max_malicious: usize = 2;
+        let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+        let malicious = participants[4];
+
+        let mut protocols: GenProtocol<PresignOutput> = Vec::with_capacity(participants.len());
+        for p in &participants {
+            if *p == malicious {
+                // a peer on another version, sending one share too few
+                let ctx = Comms::new();
+                let mut chan = ctx.shared_channel();
+                let others = participants.clone();
+                let fut = async move {
+                    let waitpoint = chan.next_waitpoint();
+                    let package = vec![SerializableScalar::<C>(Scalar::ONE); SHARES_LEN - 1];
+                    for other in others.iter().filter(|other| **other != malicious) {
+                        chan.send_private(waitpoint, *other, &package)?;
+                    }
+                    // never returns, the honest participants abort first
+                    let waitpoint = chan.next_waitpoint();
+                    chan.recv::<PresignOutput>(waitpoint)
+                        .await
+                        .map(|(_, out)| out)
+                };
+                protocols.push((*p, Box::new(make_protocol(ctx, fut))));
+                continue;
+            }
+            let keygen_out = KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                public_key: VerifyingKey::new(big_x),
+                epoch: 0,
+            };
+            let protocol = presign(
+                &participants,
+                *p,
+                PresignArguments {
+                    keygen_out,
+                    max_malicious: max_malicious.into(),
+                    active_security: true,
+                },
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(
+            err,
+            ProtocolError::MalformedPackage {
+                from: malicious,
+                expected: SHARES_LEN,
+                got: SHARES_LEN - 1,
+            }
+        );
+        assert_eq!(err.category(), ErrorCategory::Attributable(malicious));
+    }
+
+    #[test]
+    fn test_shares_from_package() {
+        let from = Participant::from(1u32);
+        let share = SerializableScalar::<C>(Scalar::ONE);
+        assert!(Shares::from_package(from, vec![share; SHARES_LEN]).is_ok());
+        for got in [0, SHARES_LEN - 1, SHARES_LEN + 1] {
+            assert_eq!(
+                Shares::from_package(from, vec![share; got]).err(),
+                Some(ProtocolError::MalformedPackage {
+                    from,
+                    expected: SHARES_LEN,
+                    got,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_presign_passive_security() {
+        let active = run_presign_with(|_| true);
+        let passive = run_presign_with(|_| false);
+        // the messages do not depend on the option, so a session can mix both modes
+        let mixed = run_presign_with(|i| i % 2 == 0);
+        assert_eq!(active, passive);
+        assert_eq!(active, mixed);
+    }
+
+    #[test]
+    fn test_presign_with_transcript() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(5);
+        let max_malicious = 2;
+        let f = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let big_x = ProjectivePoint::GENERATOR * f.eval_at_zero().unwrap().0;
+
+        let mut protocols: GenProtocol<(PresignOutput, PresignTranscript)> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let keygen_out = KeygenOutput {
+                private_share: SigningShare::new(f.eval_at_participant(*p).unwrap().0),
+                public_key: VerifyingKey::new(big_x),
+                epoch: 0,
+            };
+            // the checks skipped by a passive run are caught offline
+            let protocol = presign_with_transcript(
+                &participants,
+                *p,
+                PresignArguments {
+                    keygen_out,
+                    max_malicious: max_malicious.into(),
+                    active_security: false,
+                },
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let result = run_protocol(protocols).unwrap();
+
+        // recording the transcript does not change the presignatures
+        let outputs: Vec<_> = result
+            .iter()
+            .map(|(p, (output, _))| (*p, output.clone()))
+            .collect();
+        assert_eq!(outputs, run_presign_with(|_| false));
+
+        // every participant records the same public messages
+        let transcript = &result[0].1 .1;
+        assert!(result.iter().all(|(_, (_, t))| t == transcript));
+        verify_presign_transcript(transcript, &participants).unwrap();
+    }
+
+    #[test]
+    fn test_presign_empty_participants() {
+        let keygen_out = KeygenOutput {
+            private_share: SigningShare::new(Scalar::ONE),
+            public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+            epoch: 0,
+        };
+        let result = presign(
+            &[],
+            Participant::from(0u32),
+            PresignArguments {
+                keygen_out,
+                max_malicious: 1.into(),
+                active_security: true,
+            },
+            MockCryptoRng::seed_from_u64(42),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
+
+    #[test]
+    fn test_presign_zero_threshold() {
+        let participants = generate_participants(3);
+        let keygen_out = KeygenOutput {
+            private_share: SigningShare::new(Scalar::ONE),
+            public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+            epoch: 0,
+        };
+        let result = presign(
+            &participants,
+            participants[0],
+            PresignArguments {
+                keygen_out,
+                max_malicious: 0.into(),
+                active_security: true,
+            },
+            MockCryptoRng::seed_from_u64(42),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(
+                "robust ECDSA expects max_malicious to be at least 1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_presign_max_malicious_too_large() {
+        let participants = generate_participants(6);
+        let keygen_out = KeygenOutput {
+            private_share: SigningShare::new(Scalar::ONE),
+            public_key: VerifyingKey::new(ProjectivePoint::GENERATOR),
+            epoch: 0,
+        };
+        let result = presign(
+            &participants,
+            participants[0],
+            PresignArguments {
+                keygen_out,
+                max_malicious: 3.into(),
+                active_security: true,
+            },
+            MockCryptoRng::seed_from_u64(42),
+        );
+        let err = result.err().unwrap();
+        assert_eq!(
+            err,
+            InitializationError::MaxMaliciousTooLarge {
+                max_malicious: 3,
+                participants: 6,
+                max: 2,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "max_malicious 3 exceeds the maximum of 2 for 6 participants, as 2*max_malicious+1 must be at most the participant count"
+        );
+    }
+
+    #[test]
+    fn test_verify_presign_transcript() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let max_malicious = 1;
+        let f_k = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let f_a = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let f_b = zero_secret_polynomial(2 * max_malicious, &mut rng).unwrap();
+        let big_r = ProjectivePoint::GENERATOR * f_k.eval_at_zero().unwrap().0;
+
+        // the messages sent by honest participants
+        let mut big_r_w_shares = BTreeMap::new();
+        let mut big_w_shares = BTreeMap::new();
+        for p in &participants {
+            let k = f_k.eval_at_participant(*p).unwrap().0;
+            let a = f_a.eval_at_participant(*p).unwrap().0;
+            let b = f_b.eval_at_participant(*p).unwrap().0;
+            big_r_w_shares.insert(
+                *p,
+                (
+                    CoefficientCommitment::new(ProjectivePoint::GENERATOR * k),
+                    a * k + b,
+                ),
+            );
+            big_w_shares.insert(*p, CoefficientCommitment::new(big_r * a));
+        }
+        let transcript = PresignTranscript {
+            max_malicious: max_malicious.into(),
+            big_r_w_shares,
+            big_w_shares,
+        };
+        verify_presign_transcript(&transcript, &participants).unwrap();
+
+        // a tampered share of R, W or w is caught, including the last w share
+        // which is not used to interpolate w
+        let last = participants[3];
+        let mut tampered = transcript.clone();
+        let big_r_last = &mut tampered.big_r_w_shares.get_mut(&last).unwrap().0;
+        *big_r_last = CoefficientCommitment::new(big_r_last.value() + ProjectivePoint::GENERATOR);
+        assert!(verify_presign_transcript(&tampered, &participants).is_err());
+        let mut tampered = transcript.clone();
+        tampered.big_r_w_shares.get_mut(&last).unwrap().1 += Scalar::ONE;
+        assert!(verify_presign_transcript(&tampered, &participants).is_err());
+        let mut tampered = transcript.clone();
+        tampered
+            .big_w_shares
+            .insert(last, CoefficientCommitment::new(ProjectivePoint::GENERATOR));
+        assert!(verify_presign_transcript(&tampered, &participants).is_err());
+
+        // every participant must have sent its messages
+        let mut missing = transcript;
+        missing.big_w_shares.remove(&participants[0]);
+        assert!(matches!(
+            verify_presign_transcript(&missing, &participants),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+    }
 }
@@ -2,19 +2,21 @@ use elliptic_curve::scalar::IsHigh;
 
 use crate::{
     ecdsa::{
-        robust_ecdsa::RerandomizedPresignOutput, x_coordinate, AffinePoint, Scalar,
-        Secp256K1Sha256, Signature, SignatureOption,
+        robust_ecdsa::RerandomizedPresignOutput, x_coordinate, AffinePoint, MessageHash, PresignId,
+        Scalar, Secp256K1Sha256, Signature, SignatureOption,
     },
     errors::{InitializationError, ProtocolError},
     participants::{Participant, ParticipantList},
     protocol::{
         helpers::recv_from_others,
-        internal::{make_protocol, Comms, SharedChannel},
-        Protocol,
+        internal::{make_protocol, Comms},
+        Channel, Protocol,
     },
+    thresholds::{validate_threshold, Scheme},
     MaxMalicious,
 };
 use frost_core::serialization::SerializableScalar;
+use std::future::Future;
 use subtle::ConditionallySelectable;
 type C = Secp256K1Sha256;
 
@@ -30,6 +32,25 @@ type C = Secp256K1Sha256;
 /// ensure all participants agree on `(msg_hash, tweak, participants)` when creating
 /// `RerandomizedPresignOutput`, never reuse a presignature, and do not sign with
 /// `msg_hash == 0`.
+///
+/// The signature shares lie on a polynomial of degree `2 * max_malicious`, so with
+/// exactly `2 * max_malicious + 1` signers the coordinator needs every single share.
+/// An unresponsive signer stalls signing; retry with a fresh presignature instead.
+///
+/// The message hash is a [`MessageHash`], which can only be obtained by
+/// hashing a message or from a digest.
+///
+/// `presign_id` is the id of the presignature expected by the caller, the protocol
+/// fails with [`ProtocolError::PresignMismatch`] if the presignature has another one.
+/// Likewise, `key_epoch` is the current [`KeygenOutput::epoch`](crate::KeygenOutput::epoch),
+/// a presignature made with the shares of an older epoch fails with
+/// [`ProtocolError::StalePresignature`].
+///
+/// Side channels: `msg_hash`, `big_r`, the public key and the participants are public,
+/// while `alpha`, `beta` and `e` of the presignature, hence every signature share, are
+/// secret. The shares are only combined with constant time scalar arithmetic. Only the
+/// final `s`, which is part of the output, is branched on.
+#[allow(clippy::too_many_arguments)]
 pub fn sign(
     participants: &[Participant],
     coordinator: Participant,
@@ -37,8 +58,74 @@ pub fn sign(
     me: Participant,
     public_key: AffinePoint,
     presignature: RerandomizedPresignOutput,
-    msg_hash: Scalar,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
 ) -> Result<impl Protocol<Output = SignatureOption>, InitializationError> {
+    let ctx = Comms::new();
+    let fut = build_sign(
+        ctx.shared_channel(),
+        participants,
+        coordinator,
+        max_malicious.into(),
+        me,
+        public_key,
+        presignature,
+        presign_id,
+        key_epoch,
+        msg_hash,
+    )?;
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Like [`sign`], but returns the protocol core as a future exchanging its
+/// messages over `chan`, to be awaited on the caller's own executor.
+#[cfg(feature = "custom-executor")]
+#[allow(clippy::too_many_arguments)]
+pub fn sign_with_channel(
+    chan: impl Channel,
+    participants: &[Participant],
+    coordinator: Participant,
+    max_malicious: impl Into<MaxMalicious>,
+    me: Participant,
+    public_key: AffinePoint,
+    presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
+) -> Result<impl Future<Output = Result<SignatureOption, ProtocolError>>, InitializationError> {
+    build_sign(
+        chan,
+        participants,
+        coordinator,
+        max_malicious.into(),
+        me,
+        public_key,
+        presignature,
+        presign_id,
+        key_epoch,
+        msg_hash,
+    )
+}
+
+/// Checks the inputs of [`sign`] and builds its future over `chan`
+#[allow(clippy::too_many_arguments)]
+fn build_sign(
+    chan: impl Channel,
+    participants: &[Participant],
+    coordinator: Participant,
+    max_malicious: MaxMalicious,
+    me: Participant,
+    public_key: AffinePoint,
+    presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
+    msg_hash: MessageHash,
+) -> Result<impl Future<Output = Result<SignatureOption, ProtocolError>>, InitializationError> {
+    let msg_hash = msg_hash.to_scalar();
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -65,51 +152,46 @@ pub fn sign(
     }
 
     // ensure number of participants during the signing phase is >= 2 * max_malicious + 1
-    let robust_ecdsa_threshold = max_malicious
-        .into()
-        .value()
-        .checked_mul(2)
-        .and_then(|v| v.checked_add(1))
-        .ok_or_else(|| {
-            InitializationError::BadParameters(
-                "2*threshold+1 must be less than usize::MAX".to_string(),
-            )
-        })?;
-    if robust_ecdsa_threshold > participants.len() {
-        return Err(InitializationError::BadParameters(
-            "2*max_malicious+1 must be less than or equals to participant count".to_string(),
-        ));
+    let max_malicious = max_malicious.value();
+    let max = participants.len().saturating_sub(1) / 2;
+    if max_malicious > max {
+        return Err(InitializationError::MaxMaliciousTooLarge {
+            max_malicious,
+            participants: participants.len(),
+            max,
+        });
     }
-
     // The next two conditions prevent split-view attacks
     // documented in docs/ecdsa/robust_ecdsa/signing.md
-    if participants.len() != robust_ecdsa_threshold {
-        return Err(InitializationError::BadParameters(
-            "the number of participants during signing must be exactly 2*max_malicious+1 to avoid split view attacks".to_string(),
-        ));
-    }
-    if bool::from(msg_hash.is_zero()) {
-        return Err(InitializationError::BadParameters(
-            "msg_hash cannot be 0 to avoid potential split view attacks".to_string(),
-        ));
-    }
+    validate_threshold(Scheme::RobustEcdsa, participants.len(), max_malicious)?;
+    assert_nonzero_msg_hash(&msg_hash)?;
 
-    let ctx = Comms::new();
-    let fut = fut_wrapper(
-        ctx.shared_channel(),
+    Ok(fut_wrapper(
+        chan,
         participants,
         coordinator,
         me,
         public_key,
         presignature,
+        presign_id,
+        key_epoch,
         msg_hash,
-    );
-    Ok(make_protocol(ctx, fut))
+    ))
+}
+
+/// Rejects a zero message hash, which would allow split view attacks.
+pub(super) fn assert_nonzero_msg_hash(msg_hash: &Scalar) -> Result<(), InitializationError> {
+    if bool::from(msg_hash.is_zero()) {
+        return Err(InitializationError::BadParameters(
+            "msg_hash cannot be 0 to avoid potential split view attacks".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 /// Performs signing from any participant's perspective (except the coordinator)
 fn do_sign_participant(
-    mut chan: SharedChannel,
+    mut chan: impl Channel,
     participants: &ParticipantList,
     coordinator: Participant,
     me: Participant,
@@ -125,7 +207,7 @@ fn do_sign_participant(
 
 /// Performs signing from only the coordinator's perspective
 async fn do_sign_coordinator(
-    mut chan: SharedChannel,
+    mut chan: impl Channel,
     participants: ParticipantList,
     me: Participant,
     public_key: AffinePoint,
@@ -135,13 +217,18 @@ async fn do_sign_coordinator(
     let mut s = compute_signature_share(&presignature, msg_hash, &participants, me)?.0;
     let wait_round = chan.next_waitpoint();
 
-    for (_, s_i) in
-        recv_from_others::<SerializableScalar<C>>(&chan, wait_round, &participants, me).await?
-    {
+    // The shares lie on a polynomial of degree 2 * max_malicious and there are
+    // exactly 2 * max_malicious + 1 of them, so no subset is enough to interpolate s.
+    // recv_from_others only returns once every other participant sent its share.
+    let shares =
+        recv_from_others::<SerializableScalar<C>>(&chan, wait_round, &participants, me).await?;
+
+    for (_, s_i) in shares {
         // Sum the linearized shares
         s += s_i.0;
     }
 
+    // s is public from here on, as it is part of the signature
     // raise error if s is zero
     if s.is_zero().into() {
         return Err(ProtocolError::AssertionFailed(
@@ -157,15 +244,16 @@ async fn do_sign_coordinator(
     };
 
     if !sig.verify(&public_key, &msg_hash) {
-        return Err(ProtocolError::AssertionFailed(
-            "signature failed to verify".to_string(),
-        ));
+        return Err(ProtocolError::SignatureVerificationFailed);
     }
 
     Ok(Some(sig))
 }
 
 /// A common computation done by both the coordinator and the other participants
+///
+/// The presignature shares are secret, so they only go through constant time
+/// scalar operations. The Lagrange coefficient depends on public identifiers only.
 fn compute_signature_share(
     presignature: &RerandomizedPresignOutput,
     msg_hash: Scalar,
@@ -185,15 +273,27 @@ fn compute_signature_share(
 }
 
 /// Wraps the coordinator and the participant into a single functions to be called
+#[allow(clippy::too_many_arguments)]
 async fn fut_wrapper(
-    chan: SharedChannel,
+    chan: impl Channel,
     participants: ParticipantList,
     coordinator: Participant,
     me: Participant,
     public_key: AffinePoint,
     presignature: RerandomizedPresignOutput,
+    presign_id: PresignId,
+    key_epoch: u64,
     msg_hash: Scalar,
 ) -> Result<SignatureOption, ProtocolError> {
+    if presignature.id != presign_id {
+        return Err(ProtocolError::PresignMismatch);
+    }
+    if presignature.epoch != key_epoch {
+        return Err(ProtocolError::StalePresignature {
+            presignature: presignature.epoch,
+            key: key_epoch,
+        });
+    }
     if me == coordinator {
         do_sign_coordinator(chan, participants, me, public_key, presignature, msg_hash).await
     } else {
@@ -220,9 +320,9 @@ mod test {
             test::{run_sign_with_rerandomization, run_sign_without_rerandomization},
             PresignOutput,
         },
-        Field, Polynomial, ProjectivePoint, Secp256K1ScalarField,
+        Field, MessageHash, Polynomial, ProjectivePoint, Secp256K1ScalarField,
     };
-    use crate::test_utils::{generate_participants, MockCryptoRng};
+    use crate::test_utils::{generate_participants, run_protocol, GenProtocol, MockCryptoRng};
 
     type PresigSimulationOutput = (Scalar, Polynomial, Polynomial, Polynomial, ProjectivePoint);
 
@@ -285,6 +385,8 @@ mod test {
                 beta,
                 e,
                 c: c_i,
+                id: PresignId([0; 32]),
+                epoch: 0,
             };
             participants_presign.push((*p, presignature));
         }
@@ -335,6 +437,8 @@ mod test {
                 beta,
                 e,
                 c: c_i,
+                id: PresignId([0; 32]),
+                epoch: 0,
             };
             participants_presign.push((*p, presignature));
         }
@@ -359,6 +463,187 @@ mod test {
         insta::assert_json_snapshot!(signature);
     }
 
+    #[test]
+    fn test_sign_requires_every_participant() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious = 2;
+        let fx = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let public_key = ProjectivePoint::GENERATOR * fx.eval_at_zero().unwrap().0;
+        let (w_invert, fa, fd, fe, big_r) = simulate_presignature(max_malicious, &mut rng);
+        let participants = generate_participants(5);
+        let coordinator = participants[0];
+        let msg_hash = crate::crypto::hash::test::scalar_hash_secp256k1(b"hello world");
+
+        // the last participant never answers
+        let mut protocols: GenProtocol<SignatureOption> = Vec::new();
+        for p in &participants[..4] {
+            let c_i = w_invert * fa.eval_at_participant(*p).unwrap().0;
+            let presignature = PresignOutput {
+                big_r: big_r.to_affine(),
+                alpha: c_i + fd.eval_at_participant(*p).unwrap().0,
+                beta: c_i * fx.eval_at_participant(*p).unwrap().0,
+                e: fe.eval_at_participant(*p).unwrap().0,
+                c: c_i,
+                id: PresignId([0; 32]),
+                epoch: 0,
+            };
+            let protocol = sign(
+                &participants,
+                coordinator,
+                max_malicious,
+                *p,
+                public_key.to_affine(),
+                RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                presignature.id,
+                0,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let result = run_protocol(protocols);
+        assert!(matches!(result, Err(ProtocolError::Other(_))));
+    }
+
+    #[test]
+    fn test_sign_rejects_mismatching_presign_id() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious = 1;
+        let fx = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let public_key = ProjectivePoint::GENERATOR * fx.eval_at_zero().unwrap().0;
+        let (w_invert, fa, fd, fe, big_r) = simulate_presignature(max_malicious, &mut rng);
+        let participants = generate_participants(3);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let msg_hash = crate::crypto::hash::test::scalar_hash_secp256k1(b"hello world");
+
+        let mut protocols: GenProtocol<SignatureOption> = Vec::new();
+        for p in &participants {
+            let c_i = w_invert * fa.eval_at_participant(*p).unwrap().0;
+            let presignature = PresignOutput {
+                big_r: big_r.to_affine(),
+                alpha: c_i + fd.eval_at_participant(*p).unwrap().0,
+                beta: c_i * fx.eval_at_participant(*p).unwrap().0,
+                e: fe.eval_at_participant(*p).unwrap().0,
+                c: c_i,
+                id: PresignId::derive(&big_r.to_affine(), &participant_list),
+                epoch: 0,
+            };
+            // the caller expects the presignature of another session
+            let other_id =
+                PresignId::derive(&ProjectivePoint::GENERATOR.to_affine(), &participant_list);
+            let protocol = sign(
+                &participants,
+                participants[0],
+                max_malicious,
+                *p,
+                public_key.to_affine(),
+                RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                other_id,
+                0,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let result = run_protocol(protocols);
+        assert_eq!(result.err().unwrap(), ProtocolError::PresignMismatch);
+    }
+
+    #[test]
+    fn test_sign_rejects_stale_presignature() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious = 1;
+        let fx = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let public_key = ProjectivePoint::GENERATOR * fx.eval_at_zero().unwrap().0;
+        let (w_invert, fa, fd, fe, big_r) = simulate_presignature(max_malicious, &mut rng);
+        let participants = generate_participants(3);
+        let participant_list = ParticipantList::new(&participants).unwrap();
+        let msg_hash = crate::crypto::hash::test::scalar_hash_secp256k1(b"hello world");
+
+        let mut protocols: GenProtocol<SignatureOption> = Vec::new();
+        for p in &participants {
+            let c_i = w_invert * fa.eval_at_participant(*p).unwrap().0;
+            // made before the shares were reshared into epoch 2
+            let presignature = PresignOutput {
+                big_r: big_r.to_affine(),
+                alpha: c_i + fd.eval_at_participant(*p).unwrap().0,
+                beta: c_i * fx.eval_at_participant(*p).unwrap().0,
+                e: fe.eval_at_participant(*p).unwrap().0,
+                c: c_i,
+                id: PresignId::derive(&big_r.to_affine(), &participant_list),
+                epoch: 1,
+            };
+            let protocol = sign(
+                &participants,
+                participants[0],
+                max_malicious,
+                *p,
+                public_key.to_affine(),
+                RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                presignature.id,
+                2,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let result = run_protocol(protocols);
+        assert_eq!(
+            result.err().unwrap(),
+            ProtocolError::StalePresignature {
+                presignature: 1,
+                key: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_sign_fails_verification_with_wrong_public_key() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious = 1;
+        let msg = b"Hello? Is it me you're looking for?";
+
+        let fx = Polynomial::generate_polynomial(None, max_malicious, &mut rng).unwrap();
+        let x = fx.eval_at_zero().unwrap().0;
+        // a public key that does not match the shared secret key
+        let wrong_public_key = ProjectivePoint::GENERATOR * (x + Secp256K1ScalarField::one());
+
+        let (w_invert, fa, fd, fe, big_r) = simulate_presignature(max_malicious, &mut rng);
+        let participants = generate_participants(3);
+        let participants_presign = participants
+            .iter()
+            .map(|p| {
+                let c_i = w_invert * fa.eval_at_participant(*p).unwrap().0;
+                let presignature = PresignOutput {
+                    big_r: big_r.to_affine(),
+                    alpha: c_i + fd.eval_at_participant(*p).unwrap().0,
+                    beta: c_i * fx.eval_at_participant(*p).unwrap().0,
+                    e: fe.eval_at_participant(*p).unwrap().0,
+                    c: c_i,
+                    id: PresignId([0; 32]),
+                    epoch: 0,
+                };
+                (*p, presignature)
+            })
+            .collect::<Vec<_>>();
+
+        let err = run_sign_without_rerandomization(
+            &participants_presign,
+            max_malicious.into(),
+            wrong_public_key,
+            msg,
+            &mut rng,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ProtocolError>(),
+            Some(&ProtocolError::SignatureVerificationFailed)
+        );
+    }
+
     #[test]
     fn test_sign_fails_if_s_is_zero() {
         let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -377,6 +662,8 @@ mod test {
                         beta: Secp256K1ScalarField::zero(),
                         c: Secp256K1ScalarField::zero(),
                         e: Secp256K1ScalarField::zero(),
+                        id: PresignId([0; 32]),
+                        epoch: 0,
                     },
                 )
             })
@@ -423,6 +710,8 @@ mod test {
                         beta: Secp256K1ScalarField::zero(),
                         c: Secp256K1ScalarField::zero(),
                         e: Secp256K1ScalarField::zero(),
+                        id: PresignId([0; 32]),
+                        epoch: 0,
                     },
                 )
             })
@@ -443,10 +732,39 @@ mod test {
             Err(err) => {
                 let text = err.to_string();
                 assert!(
-                    text.contains("bad parameters: 2*max_malicious+1 must be less than or equals to participant count"),
+                    text.contains("max_malicious 1 exceeds the maximum of 0 for 2 participants"),
                     "unexpected error type: {text}"
                 );
             }
         }
     }
+
+    #[test]
+    fn test_sign_empty_participants() {
+        let me = Participant::from(0u32);
+        let presignature = PresignOutput {
+            big_r: ProjectivePoint::GENERATOR.to_affine(),
+            alpha: Secp256K1ScalarField::one(),
+            beta: Secp256K1ScalarField::one(),
+            c: Secp256K1ScalarField::one(),
+            e: Secp256K1ScalarField::zero(),
+            id: PresignId([0; 32]),
+            epoch: 0,
+        };
+        let result = sign(
+            &[],
+            me,
+            1,
+            me,
+            ProjectivePoint::GENERATOR.to_affine(),
+            RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+            presignature.id,
+            0,
+            MessageHash::from_prehashed(Secp256K1ScalarField::one().to_bytes().into()),
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
 }
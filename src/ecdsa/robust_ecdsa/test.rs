@@ -3,20 +3,25 @@ use std::error::Error;
 use super::{presign::presign, sign::sign, PresignArguments, PresignOutput};
 
 use crate::crypto::hash::test::scalar_hash_secp256k1;
+#[cfg(feature = "libsecp256k1-interop")]
+use crate::ecdsa::libsecp256k1_interop::verify_with_libsecp256k1;
 use crate::ecdsa::robust_ecdsa::RerandomizedPresignOutput;
 use crate::ecdsa::{
-    Element, ParticipantList, RerandomizationArguments, Secp256K1Sha256, Signature,
-    SignatureOption, Tweak,
+    batch_verify_ecdsa, has_even_y, ot_based_ecdsa, Element, MessageHash, ParticipantList,
+    Polynomial, PresignId, ProjectivePoint, RerandomizationArguments, Scalar, Secp256K1Sha256,
+    Signature, SignatureOption, Tweak,
 };
+use crate::errors::ProtocolError;
 use crate::participants::Participant;
 use crate::protocol::Protocol;
 use crate::test_utils::{
-    assert_public_key_invariant, check_one_coordinator_output, generate_participants,
-    generate_participants_with_random_ids, run_keygen, run_protocol, run_refresh, run_reshare,
-    run_sign, GenOutput, GenProtocol, MockCryptoRng,
+    assert_public_key_invariant, check_one_coordinator_output, check_rerandomize_presign_many,
+    generate_participants, generate_participants_with_random_ids, run_keygen, run_protocol,
+    run_refresh, run_reshare, run_sign, GenOutput, GenProtocol, MockCryptoRng,
 };
 use crate::thresholds::MaxMalicious;
 
+use elliptic_curve::scalar::IsHigh;
 use rand::seq::SliceRandom as _;
 use rand_core::{CryptoRngCore, SeedableRng};
 
@@ -55,13 +60,19 @@ pub fn run_sign_without_rerandomization(
                 me,
                 pk,
                 rerand_presig,
-                msg_hash,
+                presignature.id,
+                presignature.epoch,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
             )
             .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
         },
     )?;
     // test one single some for the coordinator
     let signature = check_one_coordinator_output(result, coordinator)?;
+    #[cfg(feature = "libsecp256k1-interop")]
+    if !verify_with_libsecp256k1(&signature, &public_key.to_affine(), &msg_hash) {
+        return Err("libsecp256k1 rejects the signature".into());
+    }
 
     Ok((coordinator, signature))
 }
@@ -127,6 +138,9 @@ pub fn run_sign_with_rerandomization(
         msg_hash,
         |participants, coordinator, me, pk, presignature, msg_hash| {
             let pk = pk.to_affine();
+            let presign_id = presignature.id;
+            // the presignatures are made with the current shares
+            let epoch = presignature.epoch;
             sign(
                 participants,
                 coordinator,
@@ -134,13 +148,19 @@ pub fn run_sign_with_rerandomization(
                 me,
                 pk,
                 presignature,
-                msg_hash,
+                presign_id,
+                epoch,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
             )
             .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
         },
     )?;
     // test one single some for the coordinator
     let signature = check_one_coordinator_output(result, coordinator)?;
+    #[cfg(feature = "libsecp256k1-interop")]
+    if !verify_with_libsecp256k1(&signature, &derived_pk.to_affine(), &msg_hash) {
+        return Err("libsecp256k1 rejects the signature".into());
+    }
     Ok((tweak, coordinator, signature))
 }
 
@@ -161,6 +181,7 @@ pub fn run_presign<R: CryptoRngCore + SeedableRng + Send + 'static>(
             PresignArguments {
                 keygen_out,
                 max_malicious: max_malicious.into(),
+                active_security: true,
             },
             rng_p,
         )
@@ -310,6 +331,238 @@ fn test_e2e() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// The signers normalize `s` without negating `big_r`, so their signatures
+/// may hold either sign of `big_r`
+#[test]
+fn test_batch_verify_signatures() -> Result<(), Box<dyn Error>> {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(3);
+    let max_malicious = 1;
+
+    let keygen_result = run_keygen(&participants, max_malicious + 1, &mut rng);
+    let public_key = keygen_result[0].1.public_key.to_element();
+
+    let mut entries = Vec::new();
+    for i in 0..8u8 {
+        let presign_result = run_presign(keygen_result.clone(), max_malicious, &mut rng);
+        let msg = [i; 32];
+        let (_, sig) = run_sign_without_rerandomization(
+            &presign_result,
+            max_malicious.into(),
+            public_key,
+            &msg,
+            &mut rng,
+        )?;
+        entries.push((public_key.to_affine(), scalar_hash_secp256k1(&msg), sig));
+    }
+    assert_eq!(batch_verify_ecdsa(&entries, &mut rng), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn test_e2e_even_y() -> Result<(), Box<dyn Error>> {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(5);
+    let max_malicious = 2;
+
+    let keygen_result = run_keygen(&participants, max_malicious + 1, &mut rng);
+    let public_key = keygen_result[0].1.public_key;
+
+    // presign until both parities of R were seen
+    let mut seen = [false; 2];
+    while !(seen[0] && seen[1]) {
+        let mut presign_result = run_presign(keygen_result.clone(), max_malicious, &mut rng);
+        seen[usize::from(has_even_y(&presign_result[0].1.big_r))] = true;
+        for (_, presignature) in &mut presign_result {
+            presignature.force_even_y();
+            assert!(has_even_y(&presignature.big_r));
+        }
+
+        let msg = b"hello world";
+        run_sign_without_rerandomization(
+            &presign_result,
+            max_malicious.into(),
+            public_key.to_element(),
+            msg,
+            &mut rng,
+        )?;
+    }
+    Ok(())
+}
+
+/// Signs with the same nonce and key under both schemes, from presignatures dealt
+/// by hand, and checks that both normalize `s` the same way.
+#[test]
+fn test_ot_based_and_robust_signatures_agree() -> Result<(), Box<dyn Error>> {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(3);
+    let participant_list = ParticipantList::new(&participants).unwrap();
+    let max_malicious = 1;
+    let degree = 2 * max_malicious;
+
+    let x = frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut rng);
+    let public_key = ProjectivePoint::GENERATOR * x;
+    let msg = b"hello world";
+    let msg_hash = scalar_hash_secp256k1(msg);
+
+    let k = frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut rng);
+    let mut r = None;
+    // k and -k give the same r and opposite values of s, one of which is high
+    for k in [k, -k] {
+        let f_k = Polynomial::generate_polynomial(Some(k), degree, &mut rng)?;
+        let f_sigma = Polynomial::generate_polynomial(Some(k * x), degree, &mut rng)?;
+        let big_r = (ProjectivePoint::GENERATOR * k.invert().unwrap()).to_affine();
+        let id = PresignId::derive(&big_r, &participant_list);
+
+        let mut ot_presign = Vec::new();
+        let mut robust_presign = Vec::new();
+        for p in &participants {
+            let k_p = f_k.eval_at_participant(*p)?.0;
+            let sigma_p = f_sigma.eval_at_participant(*p)?.0;
+            ot_presign.push((
+                *p,
+                ot_based_ecdsa::PresignOutput {
+                    big_r,
+                    k: k_p,
+                    sigma: sigma_p,
+                    id,
+                    provenance: None,
+                    epoch: 0,
+                },
+            ));
+            robust_presign.push((
+                *p,
+                PresignOutput {
+                    big_r,
+                    c: k_p,
+                    e: Scalar::ZERO,
+                    alpha: k_p,
+                    beta: sigma_p,
+                    id,
+                    epoch: 0,
+                },
+            ));
+        }
+
+        let (_, ot_sig) = ot_based_ecdsa::test::run_sign_without_rerandomization(
+            &ot_presign,
+            participants.len().into(),
+            public_key,
+            msg,
+            &mut rng,
+        );
+        let (_, robust_sig) = run_sign_without_rerandomization(
+            &robust_presign,
+            max_malicious.into(),
+            public_key,
+            msg,
+            &mut rng,
+        )?;
+
+        assert_eq!(ot_sig, robust_sig);
+        assert!(!bool::from(robust_sig.s.is_high()));
+        assert!(robust_sig.verify(&public_key.to_affine(), &msg_hash));
+        assert_eq!(
+            *r.get_or_insert(robust_sig.r_scalar()),
+            robust_sig.r_scalar()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_usable() {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(5);
+    let max_malicious = 2;
+
+    let keygen_result = run_keygen(&participants, max_malicious + 1, &mut rng);
+    let public_key = keygen_result[0].1.public_key.to_element().to_affine();
+    let presign_result = run_presign(keygen_result, max_malicious, &mut rng);
+    let msg_hash = scalar_hash_secp256k1(b"hello world");
+
+    let mut presignature =
+        RerandomizedPresignOutput::new_without_rerandomization(&presign_result[0].1);
+    presignature.check_usable(&public_key, &msg_hash).unwrap();
+
+    assert!(matches!(
+        presignature.check_usable(&public_key, &Scalar::ZERO),
+        Err(ProtocolError::InvalidInput(_))
+    ));
+    assert_eq!(
+        presignature.check_usable(&ProjectivePoint::IDENTITY.to_affine(), &msg_hash),
+        Err(ProtocolError::IdentityElement)
+    );
+    presignature.big_r = ProjectivePoint::IDENTITY.to_affine();
+    assert_eq!(
+        presignature.check_usable(&public_key, &msg_hash),
+        Err(ProtocolError::IdentityElement)
+    );
+}
+
+#[test]
+fn test_rerandomize_presign_many() {
+    check_rerandomize_presign_many(
+        |big_r, participants, rng| {
+            let mut random_scalar = || frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut *rng);
+            PresignOutput {
+                big_r,
+                c: random_scalar(),
+                e: random_scalar(),
+                alpha: random_scalar(),
+                beta: random_scalar(),
+                id: PresignId::derive(&big_r, participants),
+                epoch: 0,
+            }
+        },
+        RerandomizedPresignOutput::rerandomize_presign,
+        RerandomizedPresignOutput::rerandomize_presign_many,
+        |rerandomized| {
+            (
+                rerandomized.big_r,
+                vec![rerandomized.e, rerandomized.alpha, rerandomized.beta],
+            )
+        },
+    );
+}
+
+#[test]
+fn test_presign_output_versioned_serialization() {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = ParticipantList::new(&generate_participants(3)).unwrap();
+    let mut random_scalar = || frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut rng);
+    let big_r = (ProjectivePoint::GENERATOR * random_scalar()).to_affine();
+    let presignature = PresignOutput {
+        big_r,
+        c: random_scalar(),
+        e: random_scalar(),
+        alpha: random_scalar(),
+        beta: random_scalar(),
+        id: PresignId::derive(&big_r, &participants),
+        epoch: 0,
+    };
+
+    let json = serde_json::to_string(&presignature).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PresignOutput>(&json).unwrap(),
+        presignature
+    );
+
+    // the version comes right after the array header
+    let mut bytes = rmp_serde::to_vec(&presignature).unwrap();
+    assert_eq!(bytes[1], 1);
+    assert_eq!(
+        rmp_serde::from_slice::<PresignOutput>(&bytes).unwrap(),
+        presignature
+    );
+
+    bytes[1] = 2;
+    let err = rmp_serde::from_slice::<PresignOutput>(&bytes).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("unsupported presignature version 2"));
+}
+
 #[test]
 fn test_e2e_random_identifiers() -> Result<(), Box<dyn Error>> {
     let mut rng = MockCryptoRng::seed_from_u64(42);
@@ -28,8 +28,10 @@ pub enum ProtocolError {
     #[error("panicked while encoding an input.")]
     ErrorEncoding,
 
-    #[error("the given bytes are not mappable to a scalar without modular reduction")]
-    ErrorReducingBytesToScalar,
+    #[error(
+        "participant {0:?} sent bytes that are not mappable to a scalar without modular reduction"
+    )]
+    ErrorReducingBytesToScalar(Participant),
 
     #[error("encountered the identity element (identity point)")]
     IdentityElement,
@@ -61,6 +63,9 @@ pub enum ProtocolError {
     #[error("participant {0:?} sent an invalid secret share")]
     InvalidSecretShare(Participant),
 
+    #[error("participant {0:?} sent a signature share that does not verify")]
+    InvalidSignatureShare(Participant),
+
     #[error("the element you are trying to construct is malformed")]
     MalformedElement,
 
@@ -83,6 +88,24 @@ pub enum ProtocolError {
     #[error("encountered a zero scalar")]
     ZeroScalar,
 
+    #[error("participant {0:?} sent a contribution identical to another participant's")]
+    DuplicateContribution(Participant),
+
+    #[error("participant {0:?} sent an identity commitment")]
+    IdentityCommitment(Participant),
+
+    #[error("the contribution of participant {0:?} is missing")]
+    MissingContribution(Participant),
+
+    #[error("the interpolation point at index {0} is a duplicate")]
+    DuplicateInterpolationPoint(usize),
+
+    #[error("the x coordinate of the presignature point reduces to zero, retry presigning")]
+    ZeroRCoordinate,
+
+    #[error("the produced signature failed to verify, retry presigning")]
+    SignatureVerificationFailed,
+
     #[error("this should never happen, please report upstream")]
     Unreachable,
 
@@ -92,11 +115,99 @@ pub enum ProtocolError {
     #[error("deserialization failed: {0}")]
     DeserializationError(String),
 
+    #[error("participant {from:?} sent a malformed message at waitpoint {waitpoint}")]
+    MalformedMessage { from: Participant, waitpoint: u64 },
+
+    #[error("participant {from:?} sent a package of {got} elements, {expected} were expected")]
+    MalformedPackage {
+        from: Participant,
+        expected: usize,
+        got: usize,
+    },
+
+    #[error("the presignature does not match the id given to the signing session")]
+    PresignMismatch,
+
+    #[error(
+        "the presignature was made in key epoch {presignature}, but the key is in epoch {key}"
+    )]
+    StalePresignature { presignature: u64, key: u64 },
+
+    #[error("only {got} participants contributed signature shares, {needed} are needed")]
+    InsufficientSigners { got: usize, needed: usize },
+
     // catch-all for foreign errors
     #[error("{0}")]
     Other(String),
 }
 
+/// How an orchestrator should react to a [`ProtocolError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// A transient failure: rerunning the protocol with fresh inputs may succeed.
+    Recoverable,
+    /// The protocol must be aborted, without a participant to blame.
+    Fatal,
+    /// The protocol must be aborted, and the given participant misbehaved.
+    Attributable(Participant),
+}
+
+impl ProtocolError {
+    /// Classifies the error, so that callers can decide between retrying
+    /// and aborting without matching every variant.
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ErrorReducingBytesToScalar(p)
+            | Self::InvalidProofOfKnowledge(p)
+            | Self::InvalidSecretShare(p)
+            | Self::InvalidSignatureShare(p)
+            | Self::MaliciousParticipant(p)
+            | Self::DuplicateContribution(p)
+            | Self::IdentityCommitment(p)
+            | Self::MissingContribution(p)
+            | Self::MalformedMessage { from: p, .. }
+            | Self::MalformedPackage { from: p, .. } => ErrorCategory::Attributable(*p),
+
+            // negligible probability events, or a bad signature that a new presignature fixes
+            Self::ZeroRCoordinate
+            | Self::ZeroScalar
+            | Self::SignatureVerificationFailed
+            | Self::IoError(_) => ErrorCategory::Recoverable,
+
+            Self::AssertionFailed(_)
+            | Self::DKGNotSupported
+            | Self::EmptyOrZeroCoefficients
+            | Self::ErrorExtractVerificationKey
+            | Self::ErrorFrostRerandomizingParameters
+            | Self::ErrorFrostSigningFailed
+            | Self::ErrorFrostAggregation
+            | Self::ErrorEncoding
+            | Self::IdentityElement
+            | Self::IncompatibleRerandomizationInputs
+            | Self::InvalidCommitmentHash
+            | Self::InvalidIndex
+            | Self::InvalidInput(_)
+            | Self::InvalidInterpolationArguments
+            | Self::DuplicateInterpolationPoint(_)
+            | Self::IncorrectNumberOfCommitments
+            | Self::MalformedElement
+            | Self::MalformedSigningKey
+            | Self::PointSerialization
+            | Self::HashingError
+            | Self::Unreachable
+            | Self::IntegerOverflow
+            | Self::DeserializationError(_)
+            | Self::PresignMismatch
+            | Self::StalePresignature { .. }
+            | Self::InsufficientSigners { .. }
+            | Self::Other(_) => ErrorCategory::Fatal,
+
+            #[cfg(feature = "test-utils")]
+            Self::MismatchCoordinatorOutput => ErrorCategory::Fatal,
+        }
+    }
+}
+
 impl From<Box<dyn error::Error + Send + Sync>> for ProtocolError {
     fn from(err: Box<dyn error::Error + Send + Sync>) -> Self {
         Self::Other(err.to_string())
@@ -116,6 +227,9 @@ pub enum InitializationError {
     #[error("participant list cannot contain duplicates")]
     DuplicateParticipants,
 
+    #[error("participant list cannot be empty")]
+    EmptyParticipants,
+
     #[error("participant list must contain {role}: {participant:?}")]
     MissingParticipant {
         role: &'static str,
@@ -137,12 +251,73 @@ pub enum InitializationError {
         participants: usize,
     },
 
+    #[error("not enough intersecting old/new participants ({participants}) to reconstruct private key shared with old threshold ({old_threshold}) for resharing with a threshold ({threshold}) not bigger than the old one")]
+    NotEnoughOldParticipantsForLowerThreshold {
+        threshold: usize,
+        old_threshold: usize,
+        participants: usize,
+    },
+
     #[error("threshold {threshold} is too small, it must be at least {min}")]
     ThresholdTooSmall { threshold: usize, min: usize },
 
     #[error("threshold {threshold} is too large, it must be at most {max}")]
     ThresholdTooLarge { threshold: usize, max: usize },
 
+    #[error("max_malicious {max_malicious} exceeds the maximum of {max} for {participants} participants, as 2*max_malicious+1 must be at most the participant count")]
+    MaxMaliciousTooLarge {
+        max_malicious: usize,
+        participants: usize,
+        max: usize,
+    },
+
     #[error("participant has an invalid index")]
     InvalidParticipantIndex,
+
+    #[error("participant {participant:?} is not eligible as coordinator")]
+    IneligibleCoordinator { participant: Participant },
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ErrorCategory, ProtocolError};
+    use crate::participants::Participant;
+
+    #[test]
+    fn test_error_category() {
+        let p = Participant::from(3u32);
+        for err in [
+            ProtocolError::ErrorReducingBytesToScalar(p),
+            ProtocolError::InvalidProofOfKnowledge(p),
+            ProtocolError::InvalidSecretShare(p),
+            ProtocolError::InvalidSignatureShare(p),
+            ProtocolError::MaliciousParticipant(p),
+            ProtocolError::DuplicateContribution(p),
+            ProtocolError::IdentityCommitment(p),
+            ProtocolError::MissingContribution(p),
+            ProtocolError::MalformedMessage {
+                from: p,
+                waitpoint: 0,
+            },
+            ProtocolError::MalformedPackage {
+                from: p,
+                expected: 5,
+                got: 4,
+            },
+        ] {
+            assert_eq!(err.category(), ErrorCategory::Attributable(p));
+        }
+        assert_eq!(
+            ProtocolError::ZeroRCoordinate.category(),
+            ErrorCategory::Recoverable
+        );
+        assert_eq!(
+            ProtocolError::SignatureVerificationFailed.category(),
+            ErrorCategory::Recoverable
+        );
+        assert_eq!(
+            ProtocolError::AssertionFailed("stuck".to_string()).category(),
+            ErrorCategory::Fatal
+        );
+    }
 }
@@ -0,0 +1,319 @@
+//! Adaptor signatures for Ed25519, e.g. for atomic swaps.
+//!
+//! This is **not** the standard signer of [`super::sign`]. The coordinator outputs a
+//! pre-signature bound to an adaptor point `T = t * G`, which only becomes a valid
+//! Ed25519 signature once adapted with the secret `t`. Conversely, anyone holding
+//! both the pre-signature and the final signature learns `t`.
+//!
+//! Pre-signing consumes a presignature, i.e. the FROST nonces of [`super::presign`],
+//! which must never be reused, exactly as for standard signing.
+use super::{KeygenOutput, PresignOutput, Signature, VerifyingKey};
+use crate::{
+    errors::{InitializationError, ProtocolError},
+    frost::{assert_sign_inputs, binding_factors, challenge, commitment_element},
+    protocol::{
+        helpers::recv_from_others,
+        internal::{make_protocol, Comms, SharedChannel},
+        Protocol,
+    },
+    Participant, ParticipantList, ReconstructionLowerBound, SignInput,
+};
+
+use frost_core::{serialization::SerializableScalar, Field, Group};
+use frost_ed25519::{round1::Nonce, Ed25519Group, Ed25519ScalarField, Ed25519Sha512};
+
+type C = Ed25519Sha512;
+pub type Element = frost_core::Element<C>;
+pub type Scalar = frost_core::Scalar<C>;
+
+/// An Ed25519 pre-signature: the nonce commitment is the one of the final
+/// signature, while the `s` part is offset by the discrete log of the adaptor point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptorSignature {
+    /// The nonce commitment of the final signature, i.e. `R + T`.
+    pub big_r: Element,
+    /// The offset signature part `s - t`.
+    pub s_hat: Scalar,
+    /// The adaptor point `T = t * G`.
+    pub adaptor_point: Element,
+}
+
+/// Runs the pre-signing protocol, outputting an [`AdaptorSignature`] of `message`
+/// for the coordinator and `None` for the other participants.
+///
+/// Only [`SignInput::Raw`] messages are accepted, as for [`super::sign::sign_v2`].
+#[allow(clippy::too_many_arguments)]
+pub fn pre_sign(
+    participants: &[Participant],
+    threshold: impl Into<ReconstructionLowerBound>,
+    me: Participant,
+    coordinator: Participant,
+    keygen_output: KeygenOutput,
+    presignature: PresignOutput,
+    message: impl Into<SignInput>,
+    adaptor_point: Element,
+) -> Result<impl Protocol<Output = Option<AdaptorSignature>>, InitializationError> {
+    let participants = assert_sign_inputs(participants, threshold, me, coordinator)?;
+    let message = message.into().into_raw("EdDSA")?;
+    if adaptor_point == Ed25519Group::identity() {
+        return Err(InitializationError::BadParameters(
+            "the adaptor point cannot be the identity".to_string(),
+        ));
+    }
+
+    let comms = Comms::new();
+    let fut = do_pre_sign(
+        comms.shared_channel(),
+        participants,
+        me,
+        coordinator,
+        keygen_output,
+        presignature,
+        message,
+        adaptor_point,
+    );
+    Ok(make_protocol(comms, fut))
+}
+
+/// Checks a pre-signature against the group public key, i.e. `s' * G = R + c * A`
+/// where `R + T` is the nonce commitment of the final signature.
+pub fn verify(
+    public_key: &VerifyingKey,
+    message: &[u8],
+    adaptor_sig: &AdaptorSignature,
+) -> Result<(), ProtocolError> {
+    let c = challenge(&adaptor_sig.big_r, public_key, message)?;
+    let big_r = adaptor_sig.big_r - adaptor_sig.adaptor_point;
+    if Ed25519Group::generator() * adaptor_sig.s_hat != big_r + public_key.to_element() * c {
+        return Err(ProtocolError::SignatureVerificationFailed);
+    }
+    Ok(())
+}
+
+/// Completes a pre-signature into a standard Ed25519 signature,
+/// given the discrete log `secret` of its adaptor point.
+pub fn adapt(adaptor_sig: &AdaptorSignature, secret: &Scalar) -> Result<Signature, ProtocolError> {
+    if Ed25519Group::generator() * *secret != adaptor_sig.adaptor_point {
+        return Err(ProtocolError::InvalidInput(
+            "the secret does not match the adaptor point".to_string(),
+        ));
+    }
+    let mut bytes = serialize_element(&adaptor_sig.big_r)?.to_vec();
+    bytes.extend(Ed25519ScalarField::serialize(&(adaptor_sig.s_hat + secret)));
+    Signature::deserialize(&bytes).map_err(|_| ProtocolError::MalformedElement)
+}
+
+/// Recovers the discrete log of the adaptor point from a pre-signature
+/// and the signature it was adapted into.
+pub fn extract(
+    adaptor_sig: &AdaptorSignature,
+    signature: &Signature,
+) -> Result<Scalar, ProtocolError> {
+    let bytes = signature
+        .serialize()
+        .map_err(|_| ProtocolError::ErrorEncoding)?;
+    let (big_r, s) = bytes
+        .split_at_checked(32)
+        .ok_or(ProtocolError::MalformedElement)?;
+    if big_r != serialize_element(&adaptor_sig.big_r)?.as_slice() {
+        return Err(ProtocolError::InvalidInput(
+            "the signature does not complete the pre-signature".to_string(),
+        ));
+    }
+    let s: [u8; 32] = s.try_into().map_err(|_| ProtocolError::MalformedElement)?;
+    let s = Ed25519ScalarField::deserialize(&s).map_err(|_| ProtocolError::MalformedElement)?;
+    let secret = s - adaptor_sig.s_hat;
+    if Ed25519Group::generator() * secret != adaptor_sig.adaptor_point {
+        return Err(ProtocolError::InvalidInput(
+            "the signature does not complete the pre-signature".to_string(),
+        ));
+    }
+    Ok(secret)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn do_pre_sign(
+    mut chan: SharedChannel,
+    participants: ParticipantList,
+    me: Participant,
+    coordinator: Participant,
+    keygen_output: KeygenOutput,
+    presignature: PresignOutput,
+    message: Vec<u8>,
+    adaptor_point: Element,
+) -> Result<Option<AdaptorSignature>, ProtocolError> {
+    let (s_me, big_r) = compute_signature_share(
+        &participants,
+        me,
+        &keygen_output,
+        &presignature,
+        &message,
+        adaptor_point,
+    )?;
+
+    let wait0 = chan.next_waitpoint();
+    if me != coordinator {
+        chan.send_private(wait0, coordinator, &SerializableScalar::<C>(s_me))?;
+        return Ok(None);
+    }
+
+    let mut s_hat = s_me;
+    for (_, s_p) in
+        recv_from_others::<SerializableScalar<C>>(&chan, wait0, &participants, me).await?
+    {
+        s_hat = s_hat + s_p.0;
+    }
+    let adaptor_sig = AdaptorSignature {
+        big_r,
+        s_hat,
+        adaptor_point,
+    };
+    verify(&keygen_output.public_key, &message, &adaptor_sig)?;
+    Ok(Some(adaptor_sig))
+}
+
+/// Computes our share of `s - t` following FROST round 2, except that the
+/// challenge commits to `R + T` instead of `R`. Also returns `R + T`.
+fn compute_signature_share(
+    participants: &ParticipantList,
+    me: Participant,
+    keygen_output: &KeygenOutput,
+    presignature: &PresignOutput,
+    message: &[u8],
+    adaptor_point: Element,
+) -> Result<(Scalar, Element), ProtocolError> {
+    let commitments_map = &presignature.commitments_map;
+    // the presignature must hold the commitments of the signers exactly
+    if commitments_map.len() != participants.len() {
+        return Err(ProtocolError::AssertionFailed(
+            "the presignature commitments do not match the participants".to_string(),
+        ));
+    }
+    for p in participants.participants() {
+        if !commitments_map.contains_key(&p.to_identifier::<C>()?) {
+            return Err(ProtocolError::AssertionFailed(
+                "the presignature commitments do not match the participants".to_string(),
+            ));
+        }
+    }
+    let me_id = me.to_identifier::<C>()?;
+    if commitments_map.get(&me_id) != Some(presignature.nonces.commitments()) {
+        return Err(ProtocolError::AssertionFailed(
+            "the presignature nonces do not match our commitments".to_string(),
+        ));
+    }
+
+    let binding_factors = binding_factors(&keygen_output.public_key, commitments_map, message)?;
+    let mut big_r = Ed25519Group::identity();
+    for (id, commitments) in commitments_map {
+        let rho = binding_factors.get(id).ok_or(ProtocolError::Unreachable)?;
+        big_r = big_r
+            + commitment_element(commitments.hiding())?
+            + commitment_element(commitments.binding())? * *rho;
+    }
+    let big_r = big_r + adaptor_point;
+
+    let c = challenge(&big_r, &keygen_output.public_key, message)?;
+    let lambda = participants.lagrange::<C>(me)?;
+    let rho = binding_factors
+        .get(&me_id)
+        .ok_or(ProtocolError::Unreachable)?;
+    let d = nonce_scalar(presignature.nonces.hiding())?;
+    let e = nonce_scalar(presignature.nonces.binding())?;
+    let s_me = d + e * *rho + lambda * keygen_output.private_share.to_scalar() * c;
+    Ok((s_me, big_r))
+}
+
+fn serialize_element(element: &Element) -> Result<[u8; 32], ProtocolError> {
+    Ed25519Group::serialize(element).map_err(|_| ProtocolError::PointSerialization)
+}
+
+fn nonce_scalar(nonce: &Nonce) -> Result<Scalar, ProtocolError> {
+    SerializableScalar::<C>::deserialize(&nonce.serialize())
+        .map(|scalar| scalar.0)
+        .map_err(|_| ProtocolError::MalformedElement)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frost::eddsa::test::{build_key_packages_with_dealer, run_presign};
+    use crate::test_utils::{
+        check_one_coordinator_output, run_protocol, GenProtocol, MockCryptoRng,
+    };
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_adaptor_signature() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let threshold: usize = 2;
+        let keys = build_key_packages_with_dealer(3, 2, &mut rng);
+        let presignatures = run_presign(&keys, threshold, keys.len(), rng.clone()).unwrap();
+        let participants = keys.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        let coordinator = participants[0];
+        let public_key = keys[0].1.public_key;
+        let message = b"swap 1 BTC for 20 ETH".to_vec();
+
+        let secret = Ed25519ScalarField::random(&mut rng);
+        let adaptor_point = Ed25519Group::generator() * secret;
+
+        let mut protocols: GenProtocol<Option<AdaptorSignature>> =
+            Vec::with_capacity(participants.len());
+        for ((p, keygen_output), (_, presignature)) in keys.iter().zip(presignatures) {
+            let protocol = pre_sign(
+                &participants,
+                threshold,
+                *p,
+                coordinator,
+                keygen_output.clone(),
+                presignature,
+                message.clone(),
+                adaptor_point,
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let result = run_protocol(protocols).unwrap();
+        let adaptor_sig = check_one_coordinator_output(result, coordinator).unwrap();
+        assert_eq!(verify(&public_key, &message, &adaptor_sig), Ok(()));
+
+        // the adapted signature is a standard Ed25519 signature
+        let signature = adapt(&adaptor_sig, &secret).unwrap();
+        assert!(public_key.verify(&message, &signature).is_ok());
+        assert_eq!(extract(&adaptor_sig, &signature).unwrap(), secret);
+
+        // a wrong secret cannot adapt the pre-signature
+        let wrong_secret = secret + Ed25519ScalarField::one();
+        assert!(matches!(
+            adapt(&adaptor_sig, &wrong_secret),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+        // nor is the pre-signature valid for another message
+        assert_eq!(
+            verify(&public_key, b"swap 1 BTC for 30 ETH", &adaptor_sig),
+            Err(ProtocolError::SignatureVerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_pre_sign_rejects_identity_adaptor_point() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(2, 2, &mut rng);
+        let presignatures = run_presign(&keys, 2usize, keys.len(), rng).unwrap();
+        let participants = keys.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        let result = pre_sign(
+            &participants,
+            2usize,
+            participants[0],
+            participants[0],
+            keys[0].1.clone(),
+            presignatures[0].1.clone(),
+            b"hello".to_vec(),
+            Ed25519Group::identity(),
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(_)
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 //! This module serves as a wrapper for Ed25519 scheme.
+pub mod adaptor;
 pub mod sign;
 #[cfg(test)]
 mod test;
@@ -7,12 +8,12 @@ use crate::{
     crypto::ciphersuite::{BytesOrder, ScalarSerializationFormat},
     errors::InitializationError,
     participants::Participant,
-    protocol::Protocol,
-    Ciphersuite,
+    protocol::{Protocol, RoleOutput},
+    Ciphersuite, Scalar,
 };
 use rand_core::CryptoRngCore;
 
-pub use frost_ed25519::Ed25519Sha512;
+pub use frost_ed25519::{Ed25519Sha512, Error, Signature, VerifyingKey};
 
 impl ScalarSerializationFormat for Ed25519Sha512 {
     fn bytes_order() -> BytesOrder {
@@ -20,14 +21,36 @@ impl ScalarSerializationFormat for Ed25519Sha512 {
     }
 }
 
-impl Ciphersuite for Ed25519Sha512 {}
+impl Ciphersuite for Ed25519Sha512 {
+    fn scalar_from_uniform_bytes(bytes: &[u8; 64]) -> Scalar<Self> {
+        curve25519_dalek::Scalar::from_bytes_mod_order_wide(bytes)
+    }
+}
 
+/// The output of [`sign::sign_v1`] and [`sign::sign_v2`], only the coordinator gets the signature
+pub type SignatureRoleOutput = RoleOutput<Signature>;
 /// Signature would be Some for coordinator and None for other participants
-pub type SignatureOption = Option<frost_ed25519::Signature>;
+#[deprecated(
+    note = "`sign_v1` and `sign_v2` now return a `SignatureRoleOutput`, use `RoleOutput::into_coordinator_output`"
+)]
+pub type SignatureOption = Option<Signature>;
 
 pub type KeygenOutput = super::KeygenOutput<Ed25519Sha512>;
 pub type PresignArguments = super::PresignArguments<Ed25519Sha512>;
 pub type PresignOutput = super::PresignOutput<Ed25519Sha512>;
+pub type CommitteeKeyPackage = super::CommitteeKeyPackage<Ed25519Sha512>;
+
+/// Verifies an Ed25519 signature of `msg` under the public key `pk`
+pub fn verify(pk: &VerifyingKey, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+    pk.verify(msg, sig)
+}
+
+impl KeygenOutput {
+    /// Verifies an Ed25519 signature of `msg` under the group public key
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        verify(&self.public_key, msg, sig)
+    }
+}
 
 /// Ed25519 presigning function
 pub fn presign(
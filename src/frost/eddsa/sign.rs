@@ -1,25 +1,26 @@
 //! This module wraps a signature generation functionality from `Frost` library
 //!  into `cait-sith::Protocol` representation.
-use super::{KeygenOutput, PresignOutput, SignatureOption};
+use super::{CommitteeKeyPackage, KeygenOutput, PresignOutput, SignatureRoleOutput};
 use crate::{
+    crypto::secret::Zeroizing,
     errors::{InitializationError, ProtocolError},
-    frost::assert_sign_inputs,
+    frost::{assert_committee_inputs, assert_sign_inputs, verify_signature_shares},
     protocol::{
         helpers::recv_from_others,
         internal::{make_protocol, Comms, SharedChannel},
-        Protocol,
+        Protocol, RoleOutput,
     },
-    Participant, ParticipantList, ReconstructionLowerBound,
+    Participant, ParticipantList, ReconstructionLowerBound, SignInput,
 };
 
+use frost_core::Group;
 use frost_ed25519::{
     aggregate,
     keys::{KeyPackage, PublicKeyPackage, SigningShare},
-    rand_core, round1, round2, SigningPackage, VerifyingKey,
+    rand_core, round1, round2, Ed25519Group, SigningPackage, VerifyingKey,
 };
 use rand_core::CryptoRngCore;
 use std::collections::BTreeMap;
-use zeroize::Zeroizing;
 
 // for backwards compatibility
 pub use sign_v1 as sign;
@@ -34,17 +35,29 @@ pub use sign_v1 as sign;
 /// creating a specific ciphersuite for this, and not just sending the hash
 /// as if it were the message.
 /// For reference, see how RFC 8032 handles "pre-hashing".
+/// Hence only [`SignInput::Raw`] messages are accepted.
+///
+/// The `committee` published after the key generation is only used by the
+/// coordinator: when given, it checks every signature share against it before
+/// aggregating, and fails with [`ProtocolError::InvalidSignatureShare`] naming
+/// the signer at fault. Otherwise a bad share only makes the aggregation fail.
+#[allow(clippy::too_many_arguments)]
 pub fn sign_v1(
     participants: &[Participant],
     threshold: impl Into<ReconstructionLowerBound>,
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
-    message: Vec<u8>,
+    committee: Option<CommitteeKeyPackage>,
+    message: impl Into<SignInput>,
     rng: impl CryptoRngCore + Send + 'static,
-) -> Result<impl Protocol<Output = SignatureOption>, InitializationError> {
+) -> Result<impl Protocol<Output = SignatureRoleOutput>, InitializationError> {
     let threshold = threshold.into();
     let participants = assert_sign_inputs(participants, threshold, me, coordinator)?;
+    if let Some(committee) = &committee {
+        assert_committee_inputs(committee, &participants, &keygen_output.public_key)?;
+    }
+    let message = message.into().into_raw("EdDSA")?;
 
     let comms = Comms::new();
     let chan = comms.shared_channel();
@@ -55,22 +68,30 @@ pub fn sign_v1(
         me,
         coordinator,
         keygen_output,
+        committee,
         message,
         rng,
     );
     Ok(make_protocol(comms, fut))
 }
 
+/// Like [`sign_v1`], but consumes a presignature. Only [`SignInput::Raw`] messages are accepted.
+#[allow(clippy::too_many_arguments)]
 pub fn sign_v2(
     participants: &[Participant],
     threshold: impl Into<ReconstructionLowerBound> + Copy,
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
-    message: Vec<u8>,
-) -> Result<impl Protocol<Output = SignatureOption>, InitializationError> {
+    message: impl Into<SignInput>,
+) -> Result<impl Protocol<Output = SignatureRoleOutput>, InitializationError> {
     let participants = assert_sign_inputs(participants, threshold, me, coordinator)?;
+    if let Some(committee) = &committee {
+        assert_committee_inputs(committee, &participants, &keygen_output.public_key)?;
+    }
+    let message = message.into().into_raw("EdDSA")?;
 
     let comms = Comms::new();
     let chan = comms.shared_channel();
@@ -81,6 +102,7 @@ pub fn sign_v2(
         me,
         coordinator,
         keygen_output,
+        committee,
         presignature,
         message,
     );
@@ -96,15 +118,17 @@ pub fn sign_v2(
 /// creating a specific ciphersuite for this, and not just sending the hash
 /// as if it were the message.
 /// For reference, see how RFC 8032 handles "pre-hashing".
+#[allow(clippy::too_many_arguments)]
 async fn do_sign_coordinator_v1(
     mut chan: SharedChannel,
     participants: ParticipantList,
     threshold: ReconstructionLowerBound,
     me: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     message: Vec<u8>,
     rng: &mut impl CryptoRngCore,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1.
     // * Wait for other parties' commitments.
 
@@ -147,8 +171,20 @@ async fn do_sign_coordinator_v1(
 
     // Step 2.5 (2.4 is implicit)
     signature_shares.insert(me.to_identifier()?, signature_share);
-    for (from, signature_share) in recv_from_others(&chan, r2_wait_point, &participants, me).await?
-    {
+    let received: Vec<(Participant, round2::SignatureShare)> =
+        recv_from_others(&chan, r2_wait_point, &participants, me).await?;
+    // Step 2.6
+    if let Some(committee) = &committee {
+        verify_signature_shares(
+            &participants,
+            committee,
+            &signing_package,
+            &vk_package,
+            Ed25519Group::identity(),
+            &received,
+        )?;
+    }
+    for (from, signature_share) in received {
         signature_shares.insert(from.to_identifier()?, signature_share);
     }
 
@@ -156,15 +192,15 @@ async fn do_sign_coordinator_v1(
     // * Converted collected signature shares into the signature.
     // * Signature is verified internally during `aggregate()` call.
 
-    // Step 2.6 and 2.7
+    // Step 2.7 and 2.8
     // We supply empty map as `verifying_shares` because we have disabled "cheater-detection" feature flag.
-    // Feature "cheater-detection" only points to a malicious participant, if there's such.
-    // It doesn't bring any additional guarantees.
+    // Feature "cheater-detection" only points to a malicious participant, if there's such,
+    // which `verify_signature_shares` does above when the committee is known.
     let public_key_package = PublicKeyPackage::new(BTreeMap::new(), vk_package);
     let signature = aggregate(&signing_package, &signature_shares, &public_key_package)
         .map_err(|e| ProtocolError::AssertionFailed(e.to_string()))?;
 
-    Ok(Some(signature))
+    Ok(RoleOutput::Coordinator(signature))
 }
 
 /// Returns a future that executes signature protocol for *the Coordinator*.
@@ -176,15 +212,17 @@ async fn do_sign_coordinator_v1(
 /// creating a specific ciphersuite for this, and not just sending the hash
 /// as if it were the message.
 /// For reference, see how RFC 8032 handles "pre-hashing".
+#[allow(clippy::too_many_arguments)]
 async fn do_sign_coordinator_v2(
     mut chan: SharedChannel,
     participants: ParticipantList,
     threshold: ReconstructionLowerBound,
     me: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
     message: Vec<u8>,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1
     let signing_package =
         frost_ed25519::SigningPackage::new(presignature.commitments_map, message.as_slice());
@@ -203,9 +241,19 @@ async fn do_sign_coordinator_v2(
     signature_shares.insert(me.to_identifier()?, signature_share);
 
     let sign_waitpoint = chan.next_waitpoint();
-    for (from, signature_share) in
-        recv_from_others(&chan, sign_waitpoint, &participants, me).await?
-    {
+    let received: Vec<(Participant, round2::SignatureShare)> =
+        recv_from_others(&chan, sign_waitpoint, &participants, me).await?;
+    if let Some(committee) = &committee {
+        verify_signature_shares(
+            &participants,
+            committee,
+            &signing_package,
+            &vk_package,
+            Ed25519Group::identity(),
+            &received,
+        )?;
+    }
+    for (from, signature_share) in received {
         signature_shares.insert(from.to_identifier()?, signature_share);
     }
 
@@ -213,13 +261,13 @@ async fn do_sign_coordinator_v2(
     // * Converted collected signature shares into the signature.
     // * Signature is verified internally during `aggregate()` call.
     // We supply empty map as `verifying_shares` because we have disabled "cheater-detection" feature flag.
-    // Feature "cheater-detection" only points to a malicious participant, if there's such.
-    // It doesn't bring any additional guarantees.
+    // Feature "cheater-detection" only points to a malicious participant, if there's such,
+    // which `verify_signature_shares` does above when the committee is known.
     let public_key_package = PublicKeyPackage::new(BTreeMap::new(), vk_package);
     let signature = aggregate(&signing_package, &signature_shares, &public_key_package)
         .map_err(|e| ProtocolError::AssertionFailed(e.to_string()))?;
 
-    Ok(Some(signature))
+    Ok(RoleOutput::Coordinator(signature))
 }
 
 /// Returns a future that executes signature protocol for *a Participant*.
@@ -239,7 +287,7 @@ async fn do_sign_participant_v1(
     keygen_output: KeygenOutput,
     message: Vec<u8>,
     rng: &mut impl CryptoRngCore,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1.
     if coordinator == me {
         return Err(ProtocolError::AssertionFailed(
@@ -298,7 +346,7 @@ async fn do_sign_participant_v1(
     // Step 2.4
     chan.send_private(r2_wait_point, coordinator, &signature_share)?;
 
-    Ok(None)
+    Ok(RoleOutput::Participant)
 }
 
 /// Returns a future that executes signature protocol for *a Participant*.
@@ -318,7 +366,7 @@ fn do_sign_participant_v2(
     keygen_output: &KeygenOutput,
     presignature: PresignOutput,
     message: &[u8],
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1.
     // * Send our signature share.
     if coordinator == me {
@@ -343,7 +391,7 @@ fn do_sign_participant_v2(
     let sign_waitpoint = chan.next_waitpoint();
     chan.send_private(sign_waitpoint, coordinator, &signature_share)?;
 
-    Ok(None)
+    Ok(RoleOutput::Participant)
 }
 
 /// A function that takes a signing share and a keygenOutput
@@ -376,9 +424,10 @@ async fn fut_wrapper_v1(
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     message: Vec<u8>,
     mut rng: impl CryptoRngCore,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     if me == coordinator {
         do_sign_coordinator_v1(
             chan,
@@ -386,6 +435,7 @@ async fn fut_wrapper_v1(
             threshold,
             me,
             keygen_output,
+            committee,
             message,
             &mut rng,
         )
@@ -412,9 +462,10 @@ async fn fut_wrapper_v2(
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
     message: Vec<u8>,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     if me == coordinator {
         do_sign_coordinator_v2(
             chan,
@@ -422,6 +473,7 @@ async fn fut_wrapper_v2(
             threshold,
             me,
             keygen_output,
+            committee,
             presignature,
             message,
         )
@@ -442,18 +494,20 @@ async fn fut_wrapper_v2(
 #[cfg(test)]
 mod test {
     use crate::test_utils::{
-        assert_public_key_invariant, generate_participants, generate_participants_with_random_ids,
-        one_coordinator_output, run_keygen, run_refresh, run_reshare, MockCryptoRng,
+        assert_public_key_invariant, corrupt_participant, generate_participants,
+        generate_participants_with_random_ids, one_coordinator_output, run_keygen, run_protocol,
+        run_refresh, run_reshare, GenProtocol, MaliciousDriver, MockCryptoRng, TamperPolicy,
     };
     use crate::{
         crypto::hash::hash,
+        errors::{InitializationError, ProtocolError},
         frost::eddsa::{
             sign::{sign_v1, sign_v2},
             test::{build_key_packages_with_dealer, run_presign, run_sign_v1, run_sign_v2},
-            SignatureOption,
+            CommitteeKeyPackage, KeygenOutput, SignatureRoleOutput,
         },
         participants::{Participant, ParticipantList},
-        Protocol,
+        Protocol, SignInput,
     };
     use frost_core::{Field, Group, Scalar};
     use frost_ed25519::{Ed25519Group, Ed25519ScalarField, Ed25519Sha512, VerifyingKey};
@@ -547,10 +601,11 @@ mod test {
                     me,
                     coordinator,
                     keygen_output,
+                    None,
                     msg.clone(),
                     p_rng,
                 )
-                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureRoleOutput>>)
             },
         )
         .unwrap();
@@ -592,10 +647,11 @@ mod test {
                     me,
                     coordinator,
                     keygen_output,
+                    None,
                     presign_output.clone(),
                     msg.clone(),
                 )
-                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureRoleOutput>>)
             },
         )
         .unwrap();
@@ -630,7 +686,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -665,7 +720,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -716,7 +770,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -777,7 +830,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -839,7 +891,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -901,7 +952,6 @@ mod test {
             // externally verify with the signature
             assert!(key_packages[0]
                 .1
-                .public_key
                 .verify(msg_hash.as_ref(), &signature)
                 .is_ok());
             // test refresh
@@ -931,4 +981,150 @@ mod test {
             test_public_key(&participants, pub_key, &shares);
         }
     }
+
+    #[test]
+    fn test_sign_v1_empty_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let me = Participant::from(0u32);
+        let keygen_output = KeygenOutput {
+            private_share: frost_core::keys::SigningShare::new(Ed25519ScalarField::one()),
+            public_key: VerifyingKey::new(Ed25519Group::generator()),
+            epoch: 0,
+        };
+        let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+        let result = sign_v1(
+            &[],
+            2,
+            me,
+            me,
+            keygen_output,
+            None,
+            b"hello_near".to_vec(),
+            rng_p,
+        );
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_prehashed_messages() {
+        let participants = generate_participants(2);
+        let keygen_output = KeygenOutput {
+            private_share: frost_core::keys::SigningShare::new(Ed25519ScalarField::one()),
+            public_key: VerifyingKey::new(Ed25519Group::generator()),
+            epoch: 0,
+        };
+        let result = sign_v1(
+            &participants,
+            2,
+            participants[0],
+            participants[0],
+            keygen_output,
+            None,
+            SignInput::Prehashed([0u8; 32]),
+            MockCryptoRng::seed_from_u64(42),
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(_)
+        ));
+    }
+
+    /// Builds the `sign_v2` protocols of the `keys` holders, where `malicious` signs
+    /// with `malicious_key` and only the coordinator is given the committee of `keys`
+    fn sign_v2_with_committee(
+        keys: &[(Participant, KeygenOutput)],
+        coordinator: Participant,
+        malicious: Participant,
+        malicious_key: KeygenOutput,
+    ) -> GenProtocol<SignatureRoleOutput> {
+        let rng = MockCryptoRng::seed_from_u64(42);
+        let presignatures = run_presign(keys, 3usize, keys.len(), rng).unwrap();
+        let committee = CommitteeKeyPackage::from_keygen_outputs(keys, 3usize).unwrap();
+        let participants = keys.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+
+        let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(keys.len());
+        for ((p, keygen_output), (_, presignature)) in keys.iter().zip(presignatures) {
+            let keygen_output = if *p == malicious {
+                malicious_key.clone()
+            } else {
+                keygen_output.clone()
+            };
+            let protocol = sign_v2(
+                &participants,
+                3usize,
+                *p,
+                coordinator,
+                keygen_output,
+                (*p == coordinator).then(|| committee.clone()),
+                presignature,
+                b"hello".to_vec(),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        protocols
+    }
+
+    #[test]
+    fn test_sign_v2_tampered_share_is_attributed() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let coordinator = keys[0].0;
+        let (malicious, malicious_key) = keys[2].clone();
+
+        let protocols = sign_v2_with_committee(&keys, coordinator, malicious, malicious_key);
+        let protocols = corrupt_participant(protocols, malicious, |protocol| {
+            MaliciousDriver::new(protocol, TamperPolicy::FlipBytes).private_only()
+        });
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(err, ProtocolError::InvalidSignatureShare(malicious));
+    }
+
+    #[test]
+    fn test_sign_v2_share_of_another_key_is_attributed() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let coordinator = keys[0].0;
+        let (malicious, key) = keys[2].clone();
+        // a consistent share, but of a verifying share the committee does not hold
+        let malicious_key = KeygenOutput {
+            private_share: frost_core::keys::SigningShare::new(
+                key.private_share.to_scalar() + Ed25519ScalarField::one(),
+            ),
+            public_key: key.public_key,
+            epoch: key.epoch,
+        };
+
+        let protocols = sign_v2_with_committee(&keys, coordinator, malicious, malicious_key);
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(err, ProtocolError::InvalidSignatureShare(malicious));
+    }
+
+    #[test]
+    fn test_sign_v2_rejects_committee_of_another_key() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let other_keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let presignatures = run_presign(&keys, 3usize, keys.len(), rng).unwrap();
+        let committee = CommitteeKeyPackage::from_keygen_outputs(&other_keys, 3usize).unwrap();
+        let participants = keys.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+
+        let result = sign_v2(
+            &participants,
+            3usize,
+            participants[0],
+            participants[0],
+            keys[0].1.clone(),
+            Some(committee),
+            presignatures[0].1.clone(),
+            b"hello".to_vec(),
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(_)
+        ));
+    }
 }
@@ -2,7 +2,7 @@ use crate::{
     crypto::hash::HashOutput,
     frost::eddsa::{
         sign::{sign_v1, sign_v2},
-        KeygenOutput, PresignOutput, SignatureOption,
+        KeygenOutput, PresignOutput, SignatureRoleOutput,
     },
     test_utils::{generate_participants, run_protocol, GenOutput, GenProtocol, MockCryptoRng},
     Participant, ReconstructionLowerBound,
@@ -53,6 +53,7 @@ pub fn build_key_packages_with_dealer(
                 KeygenOutput {
                     private_share: *share.signing_share(),
                     public_key: *pubkey_package.verifying_key(),
+                    epoch: 0,
                 },
             )
         })
@@ -66,8 +67,8 @@ pub fn run_sign_v1(
     threshold: impl Into<ReconstructionLowerBound> + Copy + 'static,
     msg_hash: HashOutput,
     rng: &mut impl CryptoRngCore,
-) -> Result<Vec<(Participant, SignatureOption)>, Box<dyn Error>> {
-    let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+) -> Result<Vec<(Participant, SignatureRoleOutput)>, Box<dyn Error>> {
+    let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(participants.len());
 
     let participants_list = participants
         .iter()
@@ -88,6 +89,7 @@ pub fn run_sign_v1(
             *participant,
             coordinator,
             key_pair.clone(),
+            None,
             msg_hash.as_ref().to_vec(),
             rng_p,
         )?;
@@ -115,9 +117,9 @@ pub fn run_sign_v2(
     threshold: impl Into<ReconstructionLowerBound> + Copy + 'static,
     msg_hash: HashOutput,
     rng: impl CryptoRngCore + Send + Clone + 'static,
-) -> Result<Vec<(Participant, SignatureOption)>, Box<dyn Error>> {
+) -> Result<Vec<(Participant, SignatureRoleOutput)>, Box<dyn Error>> {
     let presig = run_presign(participants, threshold, actual_signers, rng)?;
-    let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+    let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(participants.len());
 
     let participants_list = participants
         .iter()
@@ -144,6 +146,7 @@ pub fn run_sign_v2(
             *participant,
             coordinator,
             key_pair.clone(),
+            None,
             presignature.clone(),
             msg_hash.as_ref().to_vec(),
         )?;
@@ -165,6 +168,7 @@ fn keygen_output__should_be_serializable() {
     let keygen_output = KeygenOutput {
         private_share: SigningShare::new(Scalar::<C>::from(7_u32)),
         public_key: VerifyingKey::from(signing_key),
+        epoch: 0,
     };
 
     // When
@@ -243,3 +247,57 @@ fn test_reshare_threshold_limits() {
     let mut rng = MockCryptoRng::seed_from_u64(42);
     crate::dkg::test::reshare__should_fail_if_threshold_is_below_limit::<C, _>(&mut rng);
 }
+
+#[test]
+fn test_import_frost_key_packages() {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let (shares, pubkey_package) = frost_ed25519::keys::generate_with_dealer(
+        4,
+        3,
+        frost_ed25519::keys::IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+
+    let mut key_packages = Vec::with_capacity(shares.len());
+    for (id, share) in shares {
+        let key_package = frost_ed25519::keys::KeyPackage::try_from(share).unwrap();
+        let (participant, keygen_output) =
+            KeygenOutput::from_frost_key_package(&key_package).unwrap();
+        assert_eq!(participant.to_identifier::<C>().unwrap(), id);
+        assert_eq!(keygen_output.public_key, *pubkey_package.verifying_key());
+        assert_eq!(keygen_output.private_share, *key_package.signing_share());
+        key_packages.push((participant, keygen_output));
+    }
+
+    // the imported shares sign for the dealer's key
+    let msg_hash = crate::crypto::hash::hash(&"hello_near").unwrap();
+    let coordinator = key_packages[0].0;
+    let data = run_sign_v1(&key_packages, 3, coordinator, 3usize, msg_hash, &mut rng).unwrap();
+    let signature = crate::test_utils::one_coordinator_output(data, coordinator).unwrap();
+    assert!(pubkey_package
+        .verifying_key()
+        .verify(msg_hash.as_ref(), &signature)
+        .is_ok());
+
+    // a share which does not match its verifying share is rejected
+    let key_package = frost_ed25519::keys::KeyPackage::new(
+        Participant::from(0u32).to_identifier().unwrap(),
+        SigningShare::new(Scalar::<C>::from(7_u32)),
+        SigningShare::new(Scalar::<C>::from(8_u32)).into(),
+        *pubkey_package.verifying_key(),
+        3,
+    );
+    assert!(KeygenOutput::from_frost_key_package(&key_package).is_err());
+
+    // as is an identifier which no participant maps to
+    let share = SigningShare::new(Scalar::<C>::from(7_u32));
+    let key_package = frost_ed25519::keys::KeyPackage::new(
+        frost_ed25519::Identifier::derive(b"external").unwrap(),
+        share,
+        share.into(),
+        *pubkey_package.verifying_key(),
+        3,
+    );
+    assert!(KeygenOutput::from_frost_key_package(&key_package).is_err());
+}
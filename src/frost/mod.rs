@@ -1,11 +1,13 @@
 use frost_core::{
-    keys::SigningShare,
-    round1::{commit, SigningCommitments, SigningNonces},
-    Field, Group, Identifier,
+    keys::{CoefficientCommitment, SigningShare},
+    round1::{commit, NonceCommitment, SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+    serialization::SerializableScalar,
+    Field, Group, Identifier, SigningPackage, VerifyingKey,
 };
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     errors::{InitializationError, ProtocolError},
@@ -15,7 +17,7 @@ use crate::{
         internal::{make_protocol, Comms, SharedChannel},
         Protocol,
     },
-    Ciphersuite, KeygenOutput, ReconstructionLowerBound,
+    Ciphersuite, CommitteeKeyPackage, Element, KeygenOutput, ReconstructionLowerBound, Scalar,
 };
 
 pub mod eddsa;
@@ -52,6 +54,107 @@ where
     <<<C as frost_core::Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send,
     <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: std::marker::Send,
 {
+    let participants = assert_presign_inputs(participants, me, args)?;
+
+    let ctx = Comms::new();
+    let fut = do_presign(
+        ctx.shared_channel(),
+        participants,
+        me,
+        args.keygen_out.private_share,
+        rng,
+    );
+    Ok(make_protocol(ctx, fut))
+}
+
+/// Keeps the secret presignature nonces outside of [`PresignOutput`],
+/// e.g. in an HSM, between presigning and signing.
+///
+/// The store is moved into the presigning protocol, so it is typically a cheap handle
+/// to the actual storage.
+pub trait NonceStore<C: Ciphersuite> {
+    /// The reference given back in place of the nonces.
+    type Handle;
+
+    /// Stores freshly generated nonces and returns a handle to them.
+    fn store(&mut self, nonces: SigningNonces<C>) -> Result<Self::Handle, ProtocolError>;
+
+    /// Removes and returns the nonces behind `handle`, if any.
+    /// Nonces must never be used twice, hence they are taken out of the store.
+    fn take(&mut self, handle: &Self::Handle) -> Option<SigningNonces<C>>;
+}
+
+/// The output of [`presign_with_store`]: a [`PresignOutput`] whose nonces
+/// were handed to a [`NonceStore`].
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StoredPresignOutput<C: Ciphersuite + Send + 'static, H> {
+    /// The handle to our nonces in the store.
+    pub nonces_handle: H,
+    pub commitments_map: BTreeMap<Identifier<C>, SigningCommitments<C>>,
+}
+
+impl<C: Ciphersuite + Send + 'static, H> StoredPresignOutput<C, H> {
+    /// Takes the nonces back out of `store` right before signing.
+    pub fn into_presign_output<S>(
+        self,
+        store: &mut S,
+    ) -> Result<PresignOutput<C>, InitializationError>
+    where
+        S: NonceStore<C, Handle = H>,
+    {
+        let nonces = store.take(&self.nonces_handle).ok_or_else(|| {
+            InitializationError::BadParameters("unknown nonces handle".to_string())
+        })?;
+        Ok(PresignOutput {
+            nonces,
+            commitments_map: self.commitments_map,
+        })
+    }
+}
+
+/// Runs Presigning of either `EdDSA` or `RedDSA`, handing our nonces to `store`
+/// as soon as they are generated, before any message is exchanged.
+///
+/// If the protocol fails afterwards, the stored nonces are never referenced again.
+pub fn presign_with_store<C, S>(
+    participants: &[Participant],
+    me: Participant,
+    args: &PresignArguments<C>,
+    mut store: S,
+    mut rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = StoredPresignOutput<C, S::Handle>>, InitializationError>
+where
+    C: Ciphersuite + Send,
+    <<<C as frost_core::Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send,
+    <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: std::marker::Send,
+    S: NonceStore<C> + Send + 'static,
+    S::Handle: Send,
+{
+    let participants = assert_presign_inputs(participants, me, args)?;
+
+    let ctx = Comms::new();
+    let chan = ctx.shared_channel();
+    let private_share = args.keygen_out.private_share;
+    let fut = async move {
+        let (nonces, commitments) = commit(&private_share, &mut rng);
+        let nonces_handle = store.store(nonces)?;
+        let commitments_map = exchange_commitments(chan, &participants, me, commitments).await?;
+        Ok(StoredPresignOutput {
+            nonces_handle,
+            commitments_map,
+        })
+    };
+    Ok(make_protocol(ctx, fut))
+}
+
+fn assert_presign_inputs<C: Ciphersuite>(
+    participants: &[Participant],
+    me: Participant,
+    args: &PresignArguments<C>,
+) -> Result<ParticipantList, InitializationError> {
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -76,29 +179,34 @@ where
         });
     }
 
-    let ctx = Comms::new();
-    let fut = do_presign(
-        ctx.shared_channel(),
-        participants,
-        me,
-        args.keygen_out.private_share,
-        rng,
-    );
-    Ok(make_protocol(ctx, fut))
+    Ok(participants)
 }
 
 async fn do_presign<C: Ciphersuite + Send>(
-    mut chan: SharedChannel,
+    chan: SharedChannel,
     participants: ParticipantList,
     me: Participant,
     signing_share: SigningShare<C>,
     mut rng: impl CryptoRngCore,
 ) -> Result<PresignOutput<C>, ProtocolError> {
-    // Round 1
-    let mut commitments_map: BTreeMap<Identifier<C>, SigningCommitments<C>> = BTreeMap::new();
-
     // Creating two commitments and corresponding nonces
     let (nonces, commitments) = commit(&signing_share, &mut rng);
+    let commitments_map = exchange_commitments(chan, &participants, me, commitments).await?;
+
+    Ok(PresignOutput {
+        nonces,
+        commitments_map,
+    })
+}
+
+/// Round 1 of presigning: sends our nonce commitments to all and collects theirs.
+async fn exchange_commitments<C: Ciphersuite>(
+    mut chan: SharedChannel,
+    participants: &ParticipantList,
+    me: Participant,
+    commitments: SigningCommitments<C>,
+) -> Result<BTreeMap<Identifier<C>, SigningCommitments<C>>, ProtocolError> {
+    let mut commitments_map: BTreeMap<Identifier<C>, SigningCommitments<C>> = BTreeMap::new();
     commitments_map.insert(me.to_identifier()?, commitments);
 
     let commit_waitpoint = chan.next_waitpoint();
@@ -106,14 +214,191 @@ async fn do_presign<C: Ciphersuite + Send>(
     chan.send_many(commit_waitpoint, &commitments)?;
 
     // Collecting the commitments
-    for (from, commitment) in recv_from_others(&chan, commit_waitpoint, &participants, me).await? {
+    let received = recv_from_others(&chan, commit_waitpoint, participants, me).await?;
+    assert_unique_commitments(&commitments, &received)?;
+    for (from, commitment) in received {
         commitments_map.insert(from.to_identifier()?, commitment);
     }
 
-    Ok(PresignOutput {
-        nonces,
-        commitments_map,
-    })
+    Ok(commitments_map)
+}
+
+/// Rejects identity nonce commitments as well as nonce commitments
+/// identical to one already seen, which indicate a replayed or copied nonce.
+/// The participant sending the identity or repeated commitment is blamed.
+fn assert_unique_commitments<C: Ciphersuite>(
+    my_commitments: &SigningCommitments<C>,
+    received: &[(Participant, SigningCommitments<C>)],
+) -> Result<(), ProtocolError> {
+    let mut seen = BTreeSet::new();
+    // serialization fails on the identity element
+    for commitment in [my_commitments.hiding(), my_commitments.binding()] {
+        let bytes = commitment
+            .serialize()
+            .map_err(|_| ProtocolError::IdentityElement)?;
+        seen.insert(bytes);
+    }
+    for (from, commitments) in received {
+        for commitment in [commitments.hiding(), commitments.binding()] {
+            let bytes = commitment
+                .serialize()
+                .map_err(|_| ProtocolError::IdentityCommitment(*from))?;
+            if !seen.insert(bytes) {
+                return Err(ProtocolError::DuplicateContribution(*from));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks a single signature share `z_i` before aggregation, i.e. that
+/// `z_i * G == R_i + challenge * lambda_i * Y_i`.
+///
+/// `verifying_share` is the signer's public share `Y_i = x_i * G`, `commitment` its
+/// nonce commitment `R_i = D_i + rho_i * E_i` with `rho_i` its binding factor,
+/// `lambda` its Lagrange coefficient in the signing set and `challenge` the Schnorr
+/// challenge of the (rerandomized) group key.
+/// Every input is public, hence the variable time check.
+pub fn verify_signature_share<C: Ciphersuite>(
+    share: &Scalar<C>,
+    commitment: &Element<C>,
+    verifying_share: &Element<C>,
+    lambda: &Scalar<C>,
+    challenge: &Scalar<C>,
+) -> bool {
+    C::Group::generator() * *share == *commitment + *verifying_share * (*challenge * *lambda)
+}
+
+/// Checks the signature shares received by the coordinator before aggregating them,
+/// failing with the first signer whose share does not verify.
+///
+/// The verifying shares are taken from the `committee` published after the key
+/// generation, so that a signer cannot pick the one its share is checked against.
+/// For rerandomized FROST, `verifying_key` is the rerandomized key and `offset` the
+/// randomizer point added to every verifying share. Otherwise `offset` is the identity.
+pub(crate) fn verify_signature_shares<C: Ciphersuite>(
+    participants: &ParticipantList,
+    committee: &CommitteeKeyPackage<C>,
+    signing_package: &SigningPackage<C>,
+    verifying_key: &VerifyingKey<C>,
+    offset: Element<C>,
+    shares: &[(Participant, SignatureShare<C>)],
+) -> Result<(), ProtocolError> {
+    let message = signing_package.message();
+    let commitments = signer_commitments(
+        verifying_key,
+        signing_package.signing_commitments(),
+        message,
+    )?;
+    let big_r = commitments
+        .values()
+        .fold(C::Group::identity(), |acc, commitment| acc + *commitment);
+    let challenge = challenge(&big_r, verifying_key, message)?;
+
+    for (p, share) in shares {
+        let commitment = commitments
+            .get(&p.to_identifier()?)
+            .ok_or(ProtocolError::IncorrectNumberOfCommitments)?;
+        let verifying_share = committee
+            .verifying_share(*p)
+            .and_then(|verifying_share| verifying_share.serialize().ok())
+            .and_then(|bytes| CoefficientCommitment::<C>::deserialize(&bytes).ok())
+            .ok_or_else(|| {
+                ProtocolError::InvalidInput(format!("no verifying share of {p:?} in the committee"))
+            })?;
+        let share = SerializableScalar::<C>::deserialize(&share.serialize())
+            .map_err(|_| ProtocolError::InvalidSignatureShare(*p))?;
+        let lambda = participants.lagrange::<C>(*p)?;
+        if !verify_signature_share::<C>(
+            &share.0,
+            commitment,
+            &(verifying_share.value() + offset),
+            &lambda,
+            &challenge,
+        ) {
+            return Err(ProtocolError::InvalidSignatureShare(*p));
+        }
+    }
+    Ok(())
+}
+
+/// The binding factor of every signer, as in RFC 9591 Section 4.4
+pub(crate) fn binding_factors<C: Ciphersuite>(
+    verifying_key: &VerifyingKey<C>,
+    commitments_map: &BTreeMap<Identifier<C>, SigningCommitments<C>>,
+    message: &[u8],
+) -> Result<BTreeMap<Identifier<C>, Scalar<C>>, ProtocolError> {
+    let mut encoded_commitments = Vec::new();
+    for (id, commitments) in commitments_map {
+        encoded_commitments.extend(id.serialize());
+        for commitment in [commitments.hiding(), commitments.binding()] {
+            let bytes = commitment
+                .serialize()
+                .map_err(|_| ProtocolError::PointSerialization)?;
+            encoded_commitments.extend(bytes);
+        }
+    }
+
+    let mut prefix = serialize_element::<C>(&verifying_key.to_element())?;
+    prefix.extend(C::H4(message).as_ref());
+    prefix.extend(C::H5(&encoded_commitments).as_ref());
+    Ok(commitments_map
+        .keys()
+        .map(|id| {
+            let mut input = prefix.clone();
+            input.extend(id.serialize());
+            (*id, C::H1(&input))
+        })
+        .collect())
+}
+
+/// The nonce commitment `R_i = D_i + rho_i * E_i` of every signer,
+/// which add up to the group commitment of RFC 9591 Section 4.5
+pub(crate) fn signer_commitments<C: Ciphersuite>(
+    verifying_key: &VerifyingKey<C>,
+    commitments_map: &BTreeMap<Identifier<C>, SigningCommitments<C>>,
+    message: &[u8],
+) -> Result<BTreeMap<Identifier<C>, Element<C>>, ProtocolError> {
+    let binding_factors = binding_factors(verifying_key, commitments_map, message)?;
+    commitments_map
+        .iter()
+        .map(|(id, commitments)| {
+            let rho = binding_factors.get(id).ok_or(ProtocolError::Unreachable)?;
+            let commitment = commitment_element(commitments.hiding())?
+                + commitment_element(commitments.binding())? * *rho;
+            Ok((*id, commitment))
+        })
+        .collect()
+}
+
+/// The Schnorr challenge `H2(R || Y || m)`
+pub(crate) fn challenge<C: Ciphersuite>(
+    big_r: &Element<C>,
+    verifying_key: &VerifyingKey<C>,
+    message: &[u8],
+) -> Result<Scalar<C>, ProtocolError> {
+    let mut preimage = serialize_element::<C>(big_r)?;
+    preimage.extend(serialize_element::<C>(&verifying_key.to_element())?);
+    preimage.extend_from_slice(message);
+    Ok(C::H2(&preimage))
+}
+
+fn serialize_element<C: Ciphersuite>(element: &Element<C>) -> Result<Vec<u8>, ProtocolError> {
+    C::Group::serialize(element)
+        .map(|bytes| bytes.as_ref().to_vec())
+        .map_err(|_| ProtocolError::PointSerialization)
+}
+
+/// The element committed to by a nonce commitment, which `frost_core` keeps private
+pub(crate) fn commitment_element<C: Ciphersuite>(
+    commitment: &NonceCommitment<C>,
+) -> Result<Element<C>, ProtocolError> {
+    let bytes = commitment
+        .serialize()
+        .map_err(|_| ProtocolError::PointSerialization)?;
+    CoefficientCommitment::<C>::deserialize(&bytes)
+        .map(|commitment| commitment.value())
+        .map_err(|_| ProtocolError::MalformedElement)
 }
 
 /// Verifies that the sign inputs are valid
@@ -124,6 +409,9 @@ pub fn assert_sign_inputs(
     coordinator: Participant,
 ) -> Result<ParticipantList, InitializationError> {
     let threshold = threshold.into();
+    if participants.is_empty() {
+        return Err(InitializationError::EmptyParticipants);
+    }
     if participants.len() < 2 {
         return Err(InitializationError::NotEnoughParticipants {
             participants: participants.len(),
@@ -158,3 +446,211 @@ pub fn assert_sign_inputs(
     }
     Ok(participants)
 }
+
+/// Checks that the committee given to the coordinator to verify the signature
+/// shares holds the group key being signed with and a verifying share for every signer
+pub(crate) fn assert_committee_inputs<C: Ciphersuite>(
+    committee: &CommitteeKeyPackage<C>,
+    participants: &ParticipantList,
+    public_key: &VerifyingKey<C>,
+) -> Result<(), InitializationError> {
+    if committee.group_public_key() != public_key {
+        return Err(InitializationError::BadParameters(
+            "the committee holds a different public key".to_string(),
+        ));
+    }
+    if let Some(p) = participants
+        .participants()
+        .iter()
+        .find(|p| committee.verifying_share(**p).is_none())
+    {
+        return Err(InitializationError::BadParameters(format!(
+            "the committee has no verifying share of {p:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        assert_sign_inputs, assert_unique_commitments, presign, presign_with_store,
+        verify_signature_share, NonceStore, PresignArguments,
+    };
+    use crate::{
+        errors::{InitializationError, ProtocolError},
+        participants::Participant,
+        protocol::Protocol,
+        test_utils::{generate_participants, run_keygen, run_protocol, MockCryptoRng},
+        KeygenOutput,
+    };
+    use frost_core::{
+        keys::SigningShare,
+        round1::{commit, Nonce, SigningNonces},
+        Group, VerifyingKey,
+    };
+    use frost_ed25519::{Ed25519Group, Ed25519Sha512};
+    use rand_core::{RngCore, SeedableRng};
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Clone, Default)]
+    struct SharedNonceStore(Arc<Mutex<BTreeMap<u64, SigningNonces<Ed25519Sha512>>>>);
+
+    impl NonceStore<Ed25519Sha512> for SharedNonceStore {
+        type Handle = u64;
+
+        fn store(
+            &mut self,
+            nonces: SigningNonces<Ed25519Sha512>,
+        ) -> Result<Self::Handle, ProtocolError> {
+            let mut map = self.0.lock().unwrap();
+            let handle = map.len() as u64;
+            map.insert(handle, nonces);
+            Ok(handle)
+        }
+
+        fn take(&mut self, handle: &Self::Handle) -> Option<SigningNonces<Ed25519Sha512>> {
+            self.0.lock().unwrap().remove(handle)
+        }
+    }
+
+    #[test]
+    fn test_presign_with_store() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let key_packages = run_keygen::<Ed25519Sha512, _>(&participants, 2, &mut rng);
+
+        let mut protocols = Vec::new();
+        let mut stores = BTreeMap::new();
+        for (p, keygen_out) in &key_packages {
+            let args = PresignArguments {
+                keygen_out: keygen_out.clone(),
+                threshold: 2.into(),
+            };
+            let store = SharedNonceStore::default();
+            stores.insert(*p, store.clone());
+            let protocol = presign_with_store(
+                &participants,
+                *p,
+                &args,
+                store,
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            let protocol: Box<dyn Protocol<Output = _>> = Box::new(protocol);
+            protocols.push((*p, protocol));
+        }
+
+        for (p, stored) in run_protocol(protocols).unwrap() {
+            let store = stores.get_mut(&p).unwrap();
+            let presignature = stored.clone().into_presign_output(store).unwrap();
+            let identifier = p.to_identifier::<Ed25519Sha512>().unwrap();
+            assert_eq!(
+                stored.commitments_map[&identifier],
+                *presignature.nonces.commitments()
+            );
+            // the nonces left the store
+            assert!(matches!(
+                stored.into_presign_output(store),
+                Err(InitializationError::BadParameters(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_assert_unique_commitments() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let share = SigningShare::<Ed25519Sha512>::new(frost_core::random_nonzero::<
+            Ed25519Sha512,
+            _,
+        >(&mut rng));
+        let (_, mine) = commit(&share, &mut rng);
+        let (_, other) = commit(&share, &mut rng);
+        let p1 = Participant::from(1u32);
+        let p2 = Participant::from(2u32);
+
+        assert!(assert_unique_commitments(&mine, &[(p1, other)]).is_ok());
+        // copying my commitments
+        assert_eq!(
+            assert_unique_commitments(&mine, &[(p1, mine)]),
+            Err(ProtocolError::DuplicateContribution(p1))
+        );
+        // copying another participant's commitments
+        assert_eq!(
+            assert_unique_commitments(&mine, &[(p1, other), (p2, other)]),
+            Err(ProtocolError::DuplicateContribution(p2))
+        );
+        // identity commitments, from zero nonces
+        let zero = Nonce::deserialize(&[0u8; 32]).unwrap();
+        let identity = SigningNonces::from_nonces(zero.clone(), zero)
+            .commitments()
+            .clone();
+        assert_eq!(
+            assert_unique_commitments(&mine, &[(p1, other), (p2, identity)]),
+            Err(ProtocolError::IdentityCommitment(p2))
+        );
+    }
+
+    #[test]
+    fn test_empty_participants() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let me = Participant::from(0u32);
+        let args = PresignArguments {
+            keygen_out: KeygenOutput::<Ed25519Sha512> {
+                private_share: SigningShare::new(frost_core::random_nonzero::<Ed25519Sha512, _>(
+                    &mut rng,
+                )),
+                public_key: VerifyingKey::new(Ed25519Group::generator()),
+                epoch: 0,
+            },
+            threshold: 2.into(),
+        };
+        let result = presign(&[], me, &args, rng);
+        assert_eq!(
+            result.err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+        assert_eq!(
+            assert_sign_inputs(&[], 2, me, me).err().unwrap(),
+            InitializationError::EmptyParticipants
+        );
+    }
+
+    #[test]
+    fn test_verify_signature_share() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let mut random = || frost_core::random_nonzero::<Ed25519Sha512, _>(&mut rng);
+        let (x_i, r_i, lambda, challenge) = (random(), random(), random(), random());
+        let verifying_share = Ed25519Group::generator() * x_i;
+        let commitment = Ed25519Group::generator() * r_i;
+        let share = r_i + challenge * lambda * x_i;
+
+        assert!(verify_signature_share(
+            &share,
+            &commitment,
+            &verifying_share,
+            &lambda,
+            &challenge
+        ));
+        // a share computed with another key
+        let other = Ed25519Group::generator() * r_i;
+        assert!(!verify_signature_share(
+            &share,
+            &commitment,
+            &other,
+            &lambda,
+            &challenge
+        ));
+        // a tampered share
+        assert!(!verify_signature_share(
+            &(share + lambda),
+            &commitment,
+            &verifying_share,
+            &lambda,
+            &challenge
+        ));
+    }
+}
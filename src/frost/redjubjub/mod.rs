@@ -10,12 +10,12 @@ use crate::{
     crypto::ciphersuite::{BytesOrder, ScalarSerializationFormat},
     errors::InitializationError,
     participants::Participant,
-    protocol::Protocol,
-    Ciphersuite,
+    protocol::{Protocol, RoleOutput},
+    Ciphersuite, Scalar,
 };
 
 use rand_core::CryptoRngCore;
-use reddsa::frost::redjubjub::Signature;
+use reddsa::frost::redjubjub::{Error, RandomizedParams, Randomizer, Signature, VerifyingKey};
 
 // JubJub + Blake2b512 Ciphersuite
 pub use reddsa::frost::redjubjub::JubjubBlake2b512;
@@ -25,15 +25,46 @@ impl ScalarSerializationFormat for JubjubBlake2b512 {
         BytesOrder::LittleEndian
     }
 }
-impl Ciphersuite for JubjubBlake2b512 {}
+impl Ciphersuite for JubjubBlake2b512 {
+    fn scalar_from_uniform_bytes(bytes: &[u8; 64]) -> Scalar<Self> {
+        jubjub::Fr::from_bytes_wide(bytes)
+    }
+}
 
 pub type KeygenOutput = super::KeygenOutput<JubjubBlake2b512>;
 pub type PresignArguments = super::PresignArguments<JubjubBlake2b512>;
 pub type PresignOutput = super::PresignOutput<JubjubBlake2b512>;
+pub type CommitteeKeyPackage = super::CommitteeKeyPackage<JubjubBlake2b512>;
 
+/// The output of [`sign::sign`], only the coordinator gets the signature
+pub type SignatureRoleOutput = RoleOutput<Signature>;
 /// Signature would be Some for coordinator and None for other participants
+#[deprecated(
+    note = "`sign` now returns a `SignatureRoleOutput`, use `RoleOutput::into_coordinator_output`"
+)]
 pub type SignatureOption = Option<Signature>;
 
+/// Verifies a `RedJubJub` signature of `msg` under the public key `pk` rerandomized by `randomizer`
+pub fn verify(
+    pk: &VerifyingKey,
+    randomizer: Randomizer,
+    msg: &[u8],
+    sig: &Signature,
+) -> Result<(), Error> {
+    let randomized_params = RandomizedParams::from_randomizer(pk, randomizer);
+    verify_with_randomized_key(randomized_params.randomized_verifying_key(), msg, sig)
+}
+
+/// Verifies a `RedJubJub` signature of `msg` under an already randomized key (`rk`),
+/// without deriving the `RandomizedParams` again
+pub fn verify_with_randomized_key(
+    randomized_vk: &VerifyingKey,
+    msg: &[u8],
+    sig: &Signature,
+) -> Result<(), Error> {
+    randomized_vk.verify(msg, sig)
+}
+
 /// `RedJubJub` presigning function
 pub fn presign(
     participants: &[Participant],
@@ -1,15 +1,16 @@
 //! This module and the frost one are supposed to have the same helper function
-use super::{KeygenOutput, PresignOutput, SignatureOption};
+use super::{CommitteeKeyPackage, KeygenOutput, PresignOutput, SignatureRoleOutput};
 use crate::{
+    crypto::secret::Zeroizing,
     errors::{InitializationError, ProtocolError},
-    frost::assert_sign_inputs,
+    frost::{assert_committee_inputs, assert_sign_inputs, verify_signature_shares},
     participants::{Participant, ParticipantList},
     protocol::{
         helpers::recv_from_others,
         internal::{make_protocol, Comms, SharedChannel},
-        Protocol,
+        Protocol, RoleOutput,
     },
-    ReconstructionLowerBound,
+    ReconstructionLowerBound, SignInput,
 };
 
 use reddsa::frost::redjubjub::{
@@ -20,7 +21,6 @@ use reddsa::frost::redjubjub::{
     Identifier, RandomizedParams, Randomizer, SigningPackage,
 };
 use std::collections::BTreeMap;
-use zeroize::Zeroizing;
 
 /// Depending on whether the current participant is a coordinator or not,
 /// runs the signature protocol as either a participant or a coordinator.
@@ -33,6 +33,13 @@ use zeroize::Zeroizing;
 /// as if it were the message.
 /// For reference, see how RFC 8032 handles "pre-hashing".
 ///
+/// Hence only [`SignInput::Raw`] messages are accepted.
+///
+/// Like the `randomizer`, the `committee` published after the key generation is
+/// only used by the coordinator: when given, it checks every signature share
+/// against it before aggregating, and fails with
+/// [`ProtocolError::InvalidSignatureShare`] naming the signer at fault.
+///
 /// /!\ Warning: the threshold in this scheme is the exactly the
 ///              same as the max number of malicious parties.
 #[allow(clippy::too_many_arguments)]
@@ -42,12 +49,17 @@ pub fn sign(
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
-    message: Vec<u8>,
+    message: impl Into<SignInput>,
     randomizer: Option<Randomizer>,
-) -> Result<impl Protocol<Output = SignatureOption>, InitializationError> {
+) -> Result<impl Protocol<Output = SignatureRoleOutput>, InitializationError> {
     let threshold = threshold.into();
     let participants = assert_sign_inputs(participants, threshold, me, coordinator)?;
+    if let Some(committee) = &committee {
+        assert_committee_inputs(committee, &participants, &keygen_output.public_key)?;
+    }
+    let message = message.into().into_raw("RedDSA")?;
 
     let comms = Comms::new();
     let chan = comms.shared_channel();
@@ -58,6 +70,7 @@ pub fn sign(
         me,
         coordinator,
         keygen_output,
+        committee,
         presignature,
         message,
         randomizer,
@@ -73,10 +86,11 @@ async fn fut_wrapper(
     me: Participant,
     coordinator: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
     message: Vec<u8>,
     randomizer: Option<Randomizer>,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     if me == coordinator {
         match randomizer {
             Some(randomizer) => {
@@ -86,6 +100,7 @@ async fn fut_wrapper(
                     threshold,
                     me,
                     keygen_output,
+                    committee,
                     presignature,
                     message,
                     randomizer,
@@ -133,10 +148,11 @@ async fn do_sign_coordinator(
     threshold: ReconstructionLowerBound,
     me: Participant,
     keygen_output: KeygenOutput,
+    committee: Option<CommitteeKeyPackage>,
     presignature: PresignOutput,
     message: Vec<u8>,
     randomizer: Randomizer,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1
     let key_package = construct_key_package(threshold, me, &keygen_output)?;
     let key_package = Zeroizing::new(key_package);
@@ -161,9 +177,23 @@ async fn do_sign_coordinator(
     let sign_waitpoint = chan.next_waitpoint();
     let mut signature_shares: BTreeMap<Identifier, SignatureShare> = BTreeMap::new();
     signature_shares.insert(me.to_identifier()?, signature_share);
-    for (from, signature_share) in
-        recv_from_others(&chan, sign_waitpoint, &participants, me).await?
-    {
+    let received: Vec<(Participant, SignatureShare)> =
+        recv_from_others(&chan, sign_waitpoint, &participants, me).await?;
+    if let Some(committee) = &committee {
+        // the shares were computed with the rerandomized key package,
+        // whose verifying shares are all offset by the randomizer point
+        let randomized_vk = randomized_params.randomized_verifying_key();
+        let randomizer_point = randomized_vk.to_element() - keygen_output.public_key.to_element();
+        verify_signature_shares(
+            &participants,
+            committee,
+            &signing_package,
+            randomized_vk,
+            randomizer_point,
+            &received,
+        )?;
+    }
+    for (from, signature_share) in received {
         signature_shares.insert(from.to_identifier()?, signature_share);
     }
 
@@ -172,7 +202,8 @@ async fn do_sign_coordinator(
     // * Signature is verified internally during `aggregate()` call.
 
     // We use empty BTreeMap because "cheater-detection" feature is disabled
-    // Feature "cheater-detection" unveils existant malicious participants
+    // Feature "cheater-detection" unveils existant malicious participants,
+    // which `verify_signature_shares` does above when the committee is known
     let pk_package = PublicKeyPackage::new(BTreeMap::new(), keygen_output.public_key);
 
     let signature = aggregate(
@@ -182,7 +213,7 @@ async fn do_sign_coordinator(
         &randomized_params,
     )
     .map_err(|_| ProtocolError::ErrorFrostAggregation)?;
-    Ok(Some(signature))
+    Ok(RoleOutput::Coordinator(signature))
 }
 
 /// Returns a future that executes signature protocol for *a Participant*.
@@ -202,7 +233,7 @@ async fn do_sign_participant(
     keygen_output: KeygenOutput,
     presignature: PresignOutput,
     message: Vec<u8>,
-) -> Result<SignatureOption, ProtocolError> {
+) -> Result<SignatureRoleOutput, ProtocolError> {
     // --- Round 1.
     if coordinator == me {
         return Err(ProtocolError::InvalidInput(
@@ -232,7 +263,7 @@ async fn do_sign_participant(
     let sign_waitpoint = chan.next_waitpoint();
     chan.send_private(sign_waitpoint, coordinator, &signature_share)?;
 
-    Ok(None)
+    Ok(RoleOutput::Participant)
 }
 
 /// A function that takes a signing share and a keygenOutput
@@ -264,13 +295,17 @@ fn construct_key_package(
 mod test {
     use crate::{
         crypto::hash::hash,
+        errors::ProtocolError,
         frost::redjubjub::{
             sign::sign,
-            test::{build_key_packages_with_dealer, run_sign_with_presign},
-            PresignOutput, SignatureOption,
+            test::{build_key_packages_with_dealer, run_presign, run_sign_with_presign},
+            CommitteeKeyPackage, KeygenOutput, PresignOutput, SignatureRoleOutput,
+        },
+        test_utils::{
+            corrupt_participant, one_coordinator_output, run_protocol, GenProtocol,
+            MaliciousDriver, MockCryptoRng, TamperPolicy,
         },
-        test_utils::{one_coordinator_output, MockCryptoRng},
-        Protocol,
+        Participant, Protocol,
     };
     use frost_core::Field;
     use rand::{seq::SliceRandom as _, SeedableRng};
@@ -359,15 +394,89 @@ mod test {
                     me,
                     coordinator,
                     keygen_output,
+                    None,
                     presignature,
                     msg.clone(),
                     randomize,
                 )
-                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureRoleOutput>>)
             },
         )
         .unwrap();
         let signature = one_coordinator_output(result, coordinator).unwrap();
         insta::assert_json_snapshot!(signature);
     }
+
+    /// Builds the sign protocols of the `keys` holders, where `malicious` signs
+    /// with `malicious_key` and only the coordinator is given the committee of `keys`
+    fn sign_with_committee(
+        keys: &[(Participant, KeygenOutput)],
+        coordinator: Participant,
+        malicious: Participant,
+        malicious_key: KeygenOutput,
+    ) -> GenProtocol<SignatureRoleOutput> {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let randomizer = Randomizer::from_scalar(JubjubScalarField::random(&mut rng));
+        let presignatures = run_presign(keys, 3usize, keys.len(), rng).unwrap();
+        let committee = CommitteeKeyPackage::from_keygen_outputs(keys, 3usize).unwrap();
+        let participants = keys.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+
+        let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(keys.len());
+        for ((p, keygen_output), (_, presignature)) in keys.iter().zip(presignatures) {
+            let keygen_output = if *p == malicious {
+                malicious_key.clone()
+            } else {
+                keygen_output.clone()
+            };
+            let protocol = sign(
+                &participants,
+                3usize,
+                *p,
+                coordinator,
+                keygen_output,
+                (*p == coordinator).then(|| committee.clone()),
+                presignature,
+                b"hello".to_vec(),
+                (*p == coordinator).then_some(randomizer),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        protocols
+    }
+
+    #[test]
+    fn test_tampered_share_is_attributed() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let coordinator = keys[0].0;
+        let (malicious, malicious_key) = keys[2].clone();
+
+        let protocols = sign_with_committee(&keys, coordinator, malicious, malicious_key);
+        let protocols = corrupt_participant(protocols, malicious, |protocol| {
+            MaliciousDriver::new(protocol, TamperPolicy::FlipBytes).private_only()
+        });
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(err, ProtocolError::InvalidSignatureShare(malicious));
+    }
+
+    #[test]
+    fn test_share_of_another_key_is_attributed() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let keys = build_key_packages_with_dealer(4, 3, &mut rng);
+        let coordinator = keys[0].0;
+        let (malicious, key) = keys[2].clone();
+        // a consistent share, but of a verifying share the committee does not hold
+        let malicious_key = KeygenOutput {
+            private_share: frost_core::keys::SigningShare::new(
+                key.private_share.to_scalar() + JubjubScalarField::one(),
+            ),
+            public_key: key.public_key,
+            epoch: key.epoch,
+        };
+
+        let protocols = sign_with_committee(&keys, coordinator, malicious, malicious_key);
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(err, ProtocolError::InvalidSignatureShare(malicious));
+    }
 }
@@ -1,6 +1,9 @@
 use crate::{
     crypto::hash::{hash, HashOutput},
-    frost::redjubjub::{sign::sign, KeygenOutput, PresignOutput, SignatureOption},
+    frost::redjubjub::{
+        sign::sign, verify, verify_with_randomized_key, KeygenOutput, PresignOutput,
+        SignatureRoleOutput,
+    },
     Participant, ReconstructionLowerBound,
 };
 
@@ -18,7 +21,7 @@ use rand::SeedableRng;
 use rand_core::CryptoRngCore;
 use reddsa::frost::redjubjub::{
     keys::{generate_with_dealer, IdentifierList, SigningShare},
-    JubjubBlake2b512, JubjubScalarField, Randomizer, SigningKey, VerifyingKey,
+    JubjubBlake2b512, JubjubScalarField, RandomizedParams, Randomizer, SigningKey, VerifyingKey,
 };
 
 type C = JubjubBlake2b512;
@@ -58,6 +61,7 @@ pub fn build_key_packages_with_dealer(
                 KeygenOutput {
                     private_share: *share.signing_share(),
                     public_key: *pubkey_package.verifying_key(),
+                    epoch: 0,
                 },
             )
         })
@@ -81,13 +85,13 @@ pub fn run_sign_with_presign(
     coordinator: Participant,
     threshold: impl Into<ReconstructionLowerBound> + Copy + 'static,
     msg_hash: HashOutput,
-) -> Result<Vec<(Participant, SignatureOption)>, Box<dyn Error>> {
+) -> Result<Vec<(Participant, SignatureRoleOutput)>, Box<dyn Error>> {
     let mut rng = MockCryptoRng::seed_from_u64(644_221);
     let randomizer_scalar = JubjubScalarField::random(&mut rng);
     // only for testing
     let randomizer = Randomizer::from_scalar(randomizer_scalar);
 
-    let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+    let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(participants.len());
     let presig = run_presign(participants, threshold, actual_signers, rng)?;
 
     let participants_list = participants
@@ -114,6 +118,7 @@ pub fn run_sign_with_presign(
             *participant,
             coordinator,
             key_pair.clone(),
+            None,
             presignature.clone(),
             msg_hash.as_ref().to_vec(),
             randomize,
@@ -136,6 +141,7 @@ fn keygen_output__should_be_serializable() {
     let keygen_output = KeygenOutput {
         private_share: SigningShare::new(Scalar::<C>::from(7_u64)),
         public_key: VerifyingKey::from(signing_key),
+        epoch: 0,
     };
 
     // When
@@ -331,6 +337,31 @@ fn dkg_reshare_less_participants_sign_test() {
     }
 }
 
+#[test]
+fn test_verify_with_randomized_key() {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = generate_participants(3);
+    let threshold = 2;
+    let key_packages = run_keygen(&participants, threshold, &mut rng);
+    let coordinator = key_packages[0].0;
+    let public_key = key_packages[0].1.public_key;
+
+    let msg_hash = hash(&"hello_near").unwrap();
+    let data = run_sign_with_presign(&key_packages, 3, coordinator, threshold, msg_hash).unwrap();
+    let signature = one_coordinator_output(data, coordinator).unwrap();
+
+    // same randomizer as the one drawn in run_sign_with_presign
+    let mut rng = MockCryptoRng::seed_from_u64(644_221);
+    let randomizer = Randomizer::from_scalar(JubjubScalarField::random(&mut rng));
+    let randomized_params = RandomizedParams::from_randomizer(&public_key, randomizer);
+    let randomized_vk = randomized_params.randomized_verifying_key();
+
+    verify(&public_key, randomizer, msg_hash.as_ref(), &signature).unwrap();
+    verify_with_randomized_key(randomized_vk, msg_hash.as_ref(), &signature).unwrap();
+    // the signature is bound to the randomized key, not the group key
+    assert!(verify_with_randomized_key(&public_key, msg_hash.as_ref(), &signature).is_err());
+}
+
 #[test]
 fn check_presignatures_terms() {
     let mut rng = MockCryptoRng::seed_from_u64(42);
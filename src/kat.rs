@@ -0,0 +1,341 @@
+//! Known-answer tests checking the produced signatures byte-for-byte against
+//! vectors computed by an independent implementation.
+//!
+//! The vectors live in `src/kat/`. The FROST ones are the published vectors of
+//! RFC 9591, Appendix E, while the robust ECDSA ones, for which no published
+//! vectors exist, are generated by `scripts/generate-kat-vectors.py`.
+use std::collections::BTreeMap;
+
+use frost_core::{
+    aggregate,
+    keys::{KeyPackage, PublicKeyPackage, SigningShare},
+    round1::{Nonce, SigningCommitments, SigningNonces},
+    round2, Field, Group, Identifier, SigningPackage,
+};
+use frost_ed25519::{Ed25519Sha512, VerifyingKey};
+use k256::{elliptic_curve::PrimeField, AffinePoint, ProjectivePoint, PublicKey};
+use serde::Deserialize;
+
+use crate::ecdsa::{
+    hash_message,
+    robust_ecdsa::{sign::sign, PresignOutput, RerandomizedPresignOutput},
+    x_coordinate, MessageHash, PresignId, Scalar, Secp256K1Sha256, SignatureOption,
+};
+use crate::frost::eddsa::{self, sign::sign_v2};
+use crate::participants::{Participant, ParticipantList};
+use crate::protocol::Protocol;
+use crate::test_utils::{check_one_coordinator_output, run_protocol, run_sign, GenProtocol};
+use crate::{Ciphersuite, MaxMalicious, ReconstructionLowerBound};
+
+#[derive(Deserialize)]
+struct RobustEcdsaShare {
+    id: u32,
+    alpha: String,
+    beta: String,
+    e: String,
+}
+
+#[derive(Deserialize)]
+struct EcdsaSignature {
+    r: String,
+    s: String,
+}
+
+#[derive(Deserialize)]
+struct RobustEcdsaVector {
+    name: String,
+    max_malicious: usize,
+    coordinator: u32,
+    public_key: String,
+    message: String,
+    msg_hash: String,
+    big_r: String,
+    shares: Vec<RobustEcdsaShare>,
+    signature: EcdsaSignature,
+}
+
+#[derive(Deserialize)]
+struct RfcParticipantShare {
+    identifier: u32,
+    participant_share: String,
+}
+
+#[derive(Deserialize)]
+struct RfcNonces {
+    identifier: u32,
+    hiding_nonce: String,
+    binding_nonce: String,
+}
+
+#[derive(Deserialize)]
+struct RfcSignatureShare {
+    identifier: u32,
+    sig_share: String,
+}
+
+/// A FROST vector of RFC 9591, Appendix E, keeping the names of the RFC
+#[derive(Deserialize)]
+struct Rfc9591Vector {
+    suite: String,
+    min_participants: usize,
+    participant_list: Vec<u32>,
+    group_secret_key: String,
+    group_public_key: String,
+    message: String,
+    participant_shares: Vec<RfcParticipantShare>,
+    round_one_outputs: Vec<RfcNonces>,
+    round_two_outputs: Vec<RfcSignatureShare>,
+    sig: String,
+}
+
+fn decode(hex_str: &str) -> Vec<u8> {
+    hex::decode(hex_str).unwrap()
+}
+
+fn secp256k1_scalar(hex_str: &str) -> Scalar {
+    let bytes: [u8; 32] = decode(hex_str).try_into().unwrap();
+    Scalar::from_repr(bytes.into()).unwrap()
+}
+
+/// The RFC numbers participants from 1, while the identifier of a participant is its id plus 1
+fn rfc_participant(identifier: u32) -> Participant {
+    Participant::from(identifier - 1)
+}
+
+fn rfc_share<C: Ciphersuite>(vector: &Rfc9591Vector, p: Participant) -> SigningShare<C> {
+    let share = vector
+        .participant_shares
+        .iter()
+        .find(|share| rfc_participant(share.identifier) == p)
+        .unwrap();
+    SigningShare::deserialize(&decode(&share.participant_share)).unwrap()
+}
+
+/// Checks that our participant identifiers and Lagrange coefficients recombine
+/// the shares of the signers into the group secret of the vector
+fn assert_recombines_group_secret<C: Ciphersuite>(vector: &Rfc9591Vector) -> Vec<Participant> {
+    let signers = vector
+        .participant_list
+        .iter()
+        .map(|identifier| rfc_participant(*identifier))
+        .collect::<Vec<_>>();
+    let signers_list = ParticipantList::new(&signers).unwrap();
+    let mut secret = <C::Group as Group>::Field::zero();
+    for p in &signers {
+        secret = secret
+            + signers_list.lagrange::<C>(*p).unwrap() * rfc_share::<C>(vector, *p).to_scalar();
+    }
+    let group_secret: SigningShare<C> =
+        SigningShare::deserialize(&decode(&vector.group_secret_key)).unwrap();
+    assert_eq!(
+        secret,
+        group_secret.to_scalar(),
+        "group secret mismatch for {}",
+        vector.suite
+    );
+    signers
+}
+
+fn rfc_nonces<C: Ciphersuite>(vector: &Rfc9591Vector) -> BTreeMap<Participant, SigningNonces<C>> {
+    vector
+        .round_one_outputs
+        .iter()
+        .map(|nonces| {
+            let hiding = Nonce::deserialize(&decode(&nonces.hiding_nonce)).unwrap();
+            let binding = Nonce::deserialize(&decode(&nonces.binding_nonce)).unwrap();
+            (
+                rfc_participant(nonces.identifier),
+                SigningNonces::from_nonces(hiding, binding),
+            )
+        })
+        .collect()
+}
+
+fn commitments_map<C: Ciphersuite>(
+    nonces: &BTreeMap<Participant, SigningNonces<C>>,
+) -> BTreeMap<Identifier<C>, SigningCommitments<C>> {
+    nonces
+        .iter()
+        .map(|(p, nonces)| (p.to_identifier::<C>().unwrap(), *nonces.commitments()))
+        .collect()
+}
+
+/// Checks the signature shares of the vector, computed with the key packages built
+/// from our participant identifiers, and their aggregation into the signature
+fn assert_signature_shares<C: Ciphersuite>(vector: &Rfc9591Vector) {
+    let public_key =
+        frost_core::VerifyingKey::<C>::deserialize(&decode(&vector.group_public_key)).unwrap();
+    let message = decode(&vector.message);
+    let min_signers = u16::try_from(vector.min_participants).unwrap();
+    let nonces = rfc_nonces::<C>(vector);
+    let signing_package = SigningPackage::new(commitments_map(&nonces), &message);
+
+    let mut signature_shares = BTreeMap::new();
+    for (p, nonces) in &nonces {
+        let identifier = p.to_identifier::<C>().unwrap();
+        let signing_share = rfc_share::<C>(vector, *p);
+        let key_package = KeyPackage::new(
+            identifier,
+            signing_share,
+            signing_share.into(),
+            public_key,
+            min_signers,
+        );
+        let signature_share = round2::sign(&signing_package, nonces, &key_package).unwrap();
+        let expected = vector
+            .round_two_outputs
+            .iter()
+            .find(|share| rfc_participant(share.identifier) == *p)
+            .unwrap();
+        assert_eq!(
+            signature_share.serialize(),
+            decode(&expected.sig_share),
+            "signature share mismatch for {}",
+            vector.suite
+        );
+        signature_shares.insert(identifier, signature_share);
+    }
+
+    let public_key_package = PublicKeyPackage::new(BTreeMap::new(), public_key);
+    let signature = aggregate(&signing_package, &signature_shares, &public_key_package).unwrap();
+    assert_eq!(
+        signature.serialize().unwrap(),
+        decode(&vector.sig),
+        "signature mismatch for {}",
+        vector.suite
+    );
+}
+
+fn secp256k1_point(hex_str: &str) -> AffinePoint {
+    *PublicKey::from_sec1_bytes(&decode(hex_str))
+        .unwrap()
+        .as_affine()
+}
+
+#[test]
+fn test_robust_ecdsa_vectors() {
+    let vectors: Vec<RobustEcdsaVector> =
+        serde_json::from_str(include_str!("kat/robust_ecdsa.json")).unwrap();
+    for vector in vectors {
+        let msg_hash = secp256k1_scalar(&vector.msg_hash);
+        assert_eq!(
+            hash_message(&decode(&vector.message)),
+            msg_hash,
+            "message hash mismatch for {}",
+            vector.name
+        );
+
+        let big_r = secp256k1_point(&vector.big_r);
+        let public_key = ProjectivePoint::from(secp256k1_point(&vector.public_key));
+        let max_malicious = MaxMalicious::from(vector.max_malicious);
+        let coordinator = Participant::from(vector.coordinator);
+        let participants_presign = vector
+            .shares
+            .iter()
+            .map(|share| {
+                let presignature = PresignOutput {
+                    big_r,
+                    // only used during rerandomization
+                    c: Scalar::ZERO,
+                    e: secp256k1_scalar(&share.e),
+                    alpha: secp256k1_scalar(&share.alpha),
+                    beta: secp256k1_scalar(&share.beta),
+                    id: PresignId([0; 32]),
+                    epoch: 0,
+                };
+                (Participant::from(share.id), presignature)
+            })
+            .collect::<Vec<_>>();
+
+        let result = run_sign::<Secp256K1Sha256, _, _, _>(
+            participants_presign,
+            coordinator,
+            public_key,
+            msg_hash,
+            |participants, coordinator, me, pk, presignature, msg_hash| {
+                let rerand_presig =
+                    RerandomizedPresignOutput::new_without_rerandomization(&presignature);
+                sign(
+                    participants,
+                    coordinator,
+                    max_malicious,
+                    me,
+                    pk.to_affine(),
+                    rerand_presig,
+                    presignature.id,
+                    0,
+                    MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+                )
+                .map(|sig| Box::new(sig) as Box<dyn Protocol<Output = SignatureOption>>)
+            },
+        )
+        .unwrap();
+        let signature = check_one_coordinator_output(result, coordinator).unwrap();
+
+        assert_eq!(
+            x_coordinate(&signature.big_r).to_bytes().as_slice(),
+            decode(&vector.signature.r),
+            "r mismatch for {}",
+            vector.name
+        );
+        assert_eq!(
+            signature.s.to_bytes().as_slice(),
+            decode(&vector.signature.s),
+            "s mismatch for {}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn test_rfc9591_frost_ed25519() {
+    let vector: Rfc9591Vector =
+        serde_json::from_str(include_str!("kat/rfc9591_frost_ed25519.json")).unwrap();
+    let signers = assert_recombines_group_secret::<Ed25519Sha512>(&vector);
+    assert_signature_shares::<Ed25519Sha512>(&vector);
+
+    // the signature produced by our signing protocol
+    let public_key = VerifyingKey::deserialize(&decode(&vector.group_public_key)).unwrap();
+    let message = decode(&vector.message);
+    let coordinator = signers[0];
+    let threshold = ReconstructionLowerBound::from(vector.min_participants);
+    let nonces = rfc_nonces::<Ed25519Sha512>(&vector);
+    let commitments_map = commitments_map(&nonces);
+
+    let mut protocols: GenProtocol<eddsa::SignatureRoleOutput> = Vec::with_capacity(nonces.len());
+    for (p, nonces) in nonces {
+        let keygen_output = eddsa::KeygenOutput {
+            private_share: rfc_share(&vector, p),
+            public_key,
+            epoch: 0,
+        };
+        let presignature = eddsa::PresignOutput {
+            nonces,
+            commitments_map: commitments_map.clone(),
+        };
+        let protocol = sign_v2(
+            &signers,
+            threshold,
+            p,
+            coordinator,
+            keygen_output,
+            None,
+            presignature,
+            message.clone(),
+        )
+        .unwrap();
+        protocols.push((p, Box::new(protocol)));
+    }
+
+    let result = run_protocol(protocols).unwrap();
+    let signature = check_one_coordinator_output(result, coordinator).unwrap();
+    assert_eq!(signature.serialize().unwrap(), decode(&vector.sig));
+}
+
+#[test]
+fn test_rfc9591_frost_secp256k1() {
+    let vector: Rfc9591Vector =
+        serde_json::from_str(include_str!("kat/rfc9591_frost_secp256k1.json")).unwrap();
+    assert_recombines_group_secret::<Secp256K1Sha256>(&vector);
+    assert_signature_shares::<Secp256K1Sha256>(&vector);
+}
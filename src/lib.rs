@@ -5,10 +5,17 @@ pub mod confidential_key_derivation;
 pub mod ecdsa;
 pub mod errors;
 pub mod frost;
+pub mod pipeline;
+pub mod pool;
+pub mod signature;
+pub mod signer;
 
 #[cfg(feature = "test-utils")]
 pub mod test_utils;
 
+#[cfg(test)]
+mod kat;
+
 // TODO: We should probably no expose the full modules, but only the types
 // that make sense for our library
 pub use blstrs;
@@ -21,39 +28,187 @@ pub use participants::ParticipantList;
 // For benchmark
 pub use crypto::polynomials::{
     batch_compute_lagrange_coefficients, batch_invert, compute_lagrange_coefficient,
+    interpolate_at, LagrangeContext,
 };
-use zeroize::ZeroizeOnDrop;
 
+mod communication;
 mod dkg;
 pub mod protocol;
 mod thresholds;
 
-use crate::dkg::{assert_key_invariants, assert_reshare_keys_invariants, do_keygen, do_reshare};
+pub use crate::communication::{comm_cost, CommCost, PhaseCost};
+use crate::crypto::secret::zeroize_on_drop;
+use crate::dkg::{
+    assert_key_invariants, assert_reshare_keys_invariants, do_keygen, do_reshare, next_epoch,
+};
+pub use crate::dkg::{
+    committee_commitment, prove_membership, reshare_plan, update_public_key, verify_keygen_output,
+    verify_membership, CommitteeKeyPackage, MembershipProof, MerkleTree, ResharePlan,
+};
 use crate::errors::InitializationError;
 use crate::participants::Participant;
 use crate::protocol::internal::{make_protocol, Comms};
 use crate::protocol::Protocol;
-pub use crate::thresholds::{MaxMalicious, ReconstructionLowerBound};
+pub use crate::signer::{new_signer, CurveId, ThresholdSigner};
+pub use crate::thresholds::{
+    recommended_params, validate_threshold, MaxMalicious, ReconstructionLowerBound, Scheme,
+};
 use rand_core::CryptoRngCore;
 use std::marker::Send;
 
 use frost_core::serialization::SerializableScalar;
-use frost_core::{keys::SigningShare, Group, VerifyingKey};
+use frost_core::{
+    keys::{KeyPackage, SigningShare, VerifyingShare},
+    Group, VerifyingKey,
+};
 
 use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConstantTimeEq};
 
 pub type Scalar<C> = frost_core::Scalar<C>;
 pub type Element<C> = frost_core::Element<C>;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, ZeroizeOnDrop)]
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(bound = "C: Ciphersuite")]
 /// Generic type of key pairs
+///
+/// The derived `PartialEq` compares the private share in variable time.
+/// Prefer [`ConstantTimeEq::ct_eq`] over `==` for any comparison involving secret material.
 pub struct KeygenOutput<C: Ciphersuite> {
     pub private_share: SigningShare<C>,
-    #[zeroize[skip]]
+    pub public_key: VerifyingKey<C>,
+    /// The epoch of the shares, which reshare and refresh advance whenever
+    /// they rotate them. Presignatures carry the epoch of the key they were made with.
+    #[serde(default, skip_serializing_if = "is_initial_epoch")]
+    pub epoch: u64,
+}
+
+zeroize_on_drop!(KeygenOutput<C: Ciphersuite>, [private_share]);
+
+impl<C: Ciphersuite> KeygenOutput<C> {
+    /// Sets the epoch of the shares, e.g. when importing shares rotated elsewhere.
+    ///
+    /// Keygen outputs epoch 0, and reshare and refresh output the epoch following
+    /// the `old_epoch` they are given.
+    #[must_use]
+    pub const fn with_epoch(mut self, epoch: u64) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Imports the key package of a stock FROST deployment, returning it along
+    /// with the participant it belongs to.
+    ///
+    /// The identifier of the package must be the one of a [`Participant`], and its
+    /// verifying share must match its signing share. The threshold of the package
+    /// is not kept, as the protocols of this crate take it as an argument.
+    pub fn from_frost_key_package(
+        key_package: &KeyPackage<C>,
+    ) -> Result<(Participant, Self), InitializationError> {
+        let participant =
+            Participant::try_from_identifier(key_package.identifier()).ok_or_else(|| {
+                InitializationError::BadParameters(
+                    "the key package identifier does not belong to a participant".to_string(),
+                )
+            })?;
+        let private_share = *key_package.signing_share();
+        if VerifyingShare::from(private_share) != *key_package.verifying_share() {
+            return Err(InitializationError::BadParameters(
+                "the key package verifying share does not match its signing share".to_string(),
+            ));
+        }
+        let keygen_output = Self {
+            private_share,
+            public_key: *key_package.verifying_key(),
+            epoch: 0,
+        };
+        Ok((participant, keygen_output))
+    }
+}
+
+/// The key pair of a participant output by [`keygen_additive`]: the secret key is the
+/// plain sum of the private shares, see [`reconstruct_additive_secret`].
+///
+/// These are not Shamir shares, hence a distinct type from [`KeygenOutput`],
+/// so that they cannot be fed to the threshold signing, refresh or reshare protocols.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(bound = "C: Ciphersuite")]
+pub struct AdditiveKeygenOutput<C: Ciphersuite> {
+    pub private_share: SigningShare<C>,
     pub public_key: VerifyingKey<C>,
 }
 
+zeroize_on_drop!(AdditiveKeygenOutput<C: Ciphersuite>, [private_share]);
+
+/// Keeps the encoding of keys in the initial epoch unchanged.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+pub(crate) const fn is_initial_epoch(epoch: &u64) -> bool {
+    *epoch == 0
+}
+
+impl<C: Ciphersuite> ConstantTimeEq for KeygenOutput<C> {
+    /// Compares the serialized private shares, public keys and epochs in constant time
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let share = <C::Group as Group>::Field::serialize(&self.private_share.to_scalar());
+        let other_share = <C::Group as Group>::Field::serialize(&other.private_share.to_scalar());
+        let share_eq = share.as_ref().ct_eq(other_share.as_ref());
+        // the public key is not secret, but serialization only fails on the identity
+        let public_key_eq = match (self.public_key.serialize(), other.public_key.serialize()) {
+            (Ok(pk), Ok(other_pk)) => pk.as_slice().ct_eq(other_pk.as_slice()),
+            _ => Choice::from(0),
+        };
+        share_eq & public_key_eq & self.epoch.ct_eq(&other.epoch)
+    }
+}
+
+/// The message given to the signing protocols.
+///
+/// Each scheme signs a single kind of input and its signers reject the other one:
+/// the ECDSA signers take a `Prehashed` digest, e.g. from [`ecdsa::hash_message`],
+/// while the `EdDSA` and `RedDSA` signers take the `Raw` message, as no prehash
+/// ciphersuite is supported for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignInput {
+    /// The message itself, hashed by the signature scheme
+    Raw(Vec<u8>),
+    /// A 32 bytes digest of the message, computed by the caller
+    Prehashed([u8; 32]),
+}
+
+impl SignInput {
+    /// Returns the message for the schemes hashing it themselves
+    pub(crate) fn into_raw(self, scheme: &str) -> Result<Vec<u8>, InitializationError> {
+        match self {
+            Self::Raw(message) => Ok(message),
+            Self::Prehashed(_) => Err(InitializationError::BadParameters(format!(
+                "{scheme} signs raw messages, prehashed messages are not supported"
+            ))),
+        }
+    }
+
+    /// Returns the digest for the schemes signing prehashed messages
+    pub(crate) fn into_prehashed(self, scheme: &str) -> Result<[u8; 32], InitializationError> {
+        match self {
+            Self::Prehashed(digest) => Ok(digest),
+            Self::Raw(_) => Err(InitializationError::BadParameters(format!(
+                "{scheme} signs prehashed messages, the raw message must be hashed first"
+            ))),
+        }
+    }
+}
+
+impl From<Vec<u8>> for SignInput {
+    fn from(message: Vec<u8>) -> Self {
+        Self::Raw(message)
+    }
+}
+
+impl From<[u8; 32]> for SignInput {
+    fn from(digest: [u8; 32]) -> Self {
+        Self::Prehashed(digest)
+    }
+}
+
 /// This is a necessary element to be able to derive different keys
 /// from signing shares.
 /// We do not bind the user with the way to compute the inner scalar of the tweak
@@ -97,20 +252,168 @@ where
 {
     let comms = Comms::new();
     let participants = assert_key_invariants(participants, me, threshold)?;
-    let fut = do_keygen::<C>(comms.shared_channel(), participants, me, threshold, rng);
+    let fut = do_keygen::<C>(
+        comms.shared_channel(),
+        participants,
+        me,
+        threshold,
+        None,
+        rng,
+    );
+    Ok(make_protocol(comms, fut))
+}
+
+/// Like [`keygen`], but binds the key generation to a session id the participants
+/// agreed upon out of band, e.g. a block hash, saving the round generating it at random.
+///
+/// Every participant must run this function with the same `session_id`, otherwise
+/// the protocol aborts. The session id must be unique to this key generation.
+pub fn keygen_with_session_id<C: Ciphersuite>(
+    participants: &[Participant],
+    me: Participant,
+    threshold: impl Into<ReconstructionLowerBound> + Send + Copy + 'static,
+    session_id: [u8; 32],
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = KeygenOutput<C>>, InitializationError>
+where
+    Element<C>: Send,
+    Scalar<C>: Send,
+{
+    let comms = Comms::new();
+    let participants = assert_key_invariants(participants, me, threshold)?;
+    let fut = do_keygen::<C>(
+        comms.shared_channel(),
+        participants,
+        me,
+        threshold,
+        Some(session_id),
+        rng,
+    );
     Ok(make_protocol(comms, fut))
 }
 
+/// Like [`keygen`], but returns the protocol core as a future exchanging its
+/// messages over `chan`, to be awaited on the caller's own executor.
+#[cfg(feature = "custom-executor")]
+pub fn keygen_with_channel<C: Ciphersuite>(
+    chan: impl protocol::Channel,
+    participants: &[Participant],
+    me: Participant,
+    threshold: impl Into<ReconstructionLowerBound> + Copy,
+    rng: impl CryptoRngCore,
+) -> Result<
+    impl std::future::Future<Output = Result<KeygenOutput<C>, errors::ProtocolError>>,
+    InitializationError,
+>
+where
+    Element<C>: Send,
+    Scalar<C>: Send,
+{
+    let participants = assert_key_invariants(participants, me, threshold)?;
+    Ok(do_keygen::<C>(chan, participants, me, threshold, None, rng))
+}
+
+/// Like [`keygen`], but outputs plain additive (`n`-of-`n`) shares:
+/// the secret key is the sum of the private shares, see [`reconstruct_additive_secret`].
+///
+/// This runs the DKG with a threshold of `participants.len()` and scales each share
+/// by its Lagrange coefficient, so no interpolation is needed afterwards.
+/// The trade-off is that there is no fault tolerance: every participant is needed to
+/// use the key, and losing a single share loses the key.
+pub fn keygen_additive<C: Ciphersuite>(
+    participants: &[Participant],
+    me: Participant,
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = AdditiveKeygenOutput<C>>, InitializationError>
+where
+    Element<C>: Send,
+    Scalar<C>: Send,
+{
+    let comms = Comms::new();
+    let threshold = participants.len();
+    let participants = assert_key_invariants(participants, me, threshold)?;
+    let lambda = participants
+        .lagrange::<C>(me)
+        .map_err(|err| InitializationError::BadParameters(err.to_string()))?;
+    let chan = comms.shared_channel();
+    let fut = async move {
+        let output = do_keygen::<C>(chan, participants, me, threshold, None, rng).await?;
+        Ok(AdditiveKeygenOutput {
+            private_share: SigningShare::new(lambda * output.private_share.to_scalar()),
+            public_key: output.public_key,
+        })
+    };
+    Ok(make_protocol(comms, fut))
+}
+
+/// Sums the additive shares output by [`keygen_additive`] into the secret key.
+pub fn reconstruct_additive_secret<C: Ciphersuite>(keys: &[AdditiveKeygenOutput<C>]) -> Scalar<C> {
+    keys.iter()
+        .fold(<C::Group as Group>::Field::zero(), |sum, key| {
+            sum + key.private_share.to_scalar()
+        })
+}
+
 /// Performs the key reshare protocol
+///
+/// The new shares are in the epoch following `old_epoch`, the epoch of the old shares.
 #[allow(clippy::too_many_arguments)]
 pub fn reshare<C: Ciphersuite>(
     old_participants: &[Participant],
     old_threshold: impl Into<ReconstructionLowerBound> + Send + 'static,
     old_signing_key: Option<SigningShare<C>>,
     old_public_key: VerifyingKey<C>,
+    old_epoch: u64,
+    new_participants: &[Participant],
+    new_threshold: impl Into<ReconstructionLowerBound> + Copy + Send + 'static,
+    me: Participant,
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = KeygenOutput<C>>, InitializationError>
+where
+    Element<C>: Send,
+    Scalar<C>: Send,
+{
+    let comms = Comms::new();
+    let threshold = new_threshold;
+    let (participants, old_participants) = assert_reshare_keys_invariants::<C>(
+        new_participants,
+        me,
+        threshold,
+        old_signing_key,
+        old_threshold,
+        old_participants,
+    )?;
+    let fut = do_reshare(
+        comms.shared_channel(),
+        participants,
+        me,
+        threshold,
+        old_signing_key,
+        old_public_key,
+        next_epoch(old_epoch)?,
+        old_participants,
+        None,
+        rng,
+    );
+    Ok(make_protocol(comms, fut))
+}
+
+/// Like [`reshare`], but binds the resharing to a session id the participants
+/// agreed upon out of band, saving the round generating it at random.
+///
+/// Every participant must run this function with the same `session_id`, otherwise
+/// the protocol aborts. The session id must be unique to this resharing.
+#[allow(clippy::too_many_arguments)]
+pub fn reshare_with_session_id<C: Ciphersuite>(
+    old_participants: &[Participant],
+    old_threshold: impl Into<ReconstructionLowerBound> + Send + 'static,
+    old_signing_key: Option<SigningShare<C>>,
+    old_public_key: VerifyingKey<C>,
+    old_epoch: u64,
     new_participants: &[Participant],
     new_threshold: impl Into<ReconstructionLowerBound> + Copy + Send + 'static,
     me: Participant,
+    session_id: [u8; 32],
     rng: impl CryptoRngCore + Send + 'static,
 ) -> Result<impl Protocol<Output = KeygenOutput<C>>, InitializationError>
 where
@@ -134,16 +437,21 @@ where
         threshold,
         old_signing_key,
         old_public_key,
+        next_epoch(old_epoch)?,
         old_participants,
+        Some(session_id),
         rng,
     );
     Ok(make_protocol(comms, fut))
 }
 
 /// Performs the refresh protocol
+///
+/// The new shares are in the epoch following `old_epoch`, the epoch of the old shares.
 pub fn refresh<C: Ciphersuite>(
     old_signing_key: Option<SigningShare<C>>,
     old_public_key: VerifyingKey<C>,
+    old_epoch: u64,
     old_participants: &[Participant],
     old_threshold: impl Into<ReconstructionLowerBound> + Copy + Send + 'static,
     me: Participant,
@@ -177,7 +485,9 @@ where
         threshold,
         old_signing_key,
         old_public_key,
+        next_epoch(old_epoch)?,
         old_participants,
+        None,
         rng,
     );
     Ok(make_protocol(comms, fut))
@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::crypto::ciphersuite::BytesOrder;
 use crate::crypto::{ciphersuite::Ciphersuite, polynomials::compute_lagrange_coefficient};
-use crate::errors::ProtocolError;
+use crate::errors::{InitializationError, ProtocolError};
 use crate::Scalar;
 
 /// Represents a participant in the protocol.
@@ -69,12 +69,41 @@ impl Participant {
         // cannot panic as the previous line ensures id is neq zero
         Identifier::new(id).map_err(|_| ProtocolError::IdentityElement)
     }
+
+    /// Returns the participant whose [`Self::to_identifier`] is `id`, if any.
+    ///
+    /// Participant `i` has the identifier `i + 1`, which is below the group order
+    /// of every ciphersuite for any `u32`, so distinct participants never share an
+    /// identifier. Identifiers outside of this range, e.g. derived by frost from a
+    /// string, have no participant.
+    pub fn try_from_identifier<C: Ciphersuite>(id: &Identifier<C>) -> Option<Self> {
+        let bytes = id.serialize();
+        let (value, rest) = match C::bytes_order() {
+            BytesOrder::BigEndian => {
+                let (rest, value) = bytes.split_at_checked(bytes.len().checked_sub(8)?)?;
+                (u64::from_be_bytes(value.try_into().ok()?), rest)
+            }
+            BytesOrder::LittleEndian => {
+                let (value, rest) = bytes.split_at_checked(8)?;
+                (u64::from_le_bytes(value.try_into().ok()?), rest)
+            }
+        };
+        if rest.iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        let participant = Self(u32::try_from(value.checked_sub(1)?).ok()?);
+        (participant.to_identifier::<C>().ok()? == *id).then_some(participant)
+    }
 }
 
 /// Represents a sorted list of participants.
 ///
 /// The advantage of this data structure is that it can be hashed in the protocol transcript,
 /// since everybody will agree on its order.
+///
+/// Whatever the order of the input, the participants are always iterated in ascending order,
+/// which is also the order of their scalar identifiers used in interpolation.
+/// Positions given by [`ParticipantList::position`] refer to this order.
 #[derive(Clone, Debug, Serialize)]
 pub struct ParticipantList {
     participants: Vec<Participant>,
@@ -129,13 +158,17 @@ impl ParticipantList {
         self.participants.iter().filter(move |x| **x != me).copied()
     }
 
+    /// Return the position of a given participant in ascending order,
+    /// or None if the participant is not in the list.
+    pub fn position(&self, participant: Participant) -> Option<usize> {
+        self.indices.get(&participant).copied()
+    }
+
     /// Return the index of a given participant.
     ///
     /// Basically, the order they appear in a sorted list
     pub fn index(&self, participant: Participant) -> Result<usize, ProtocolError> {
-        self.indices
-            .get(&participant)
-            .copied()
+        self.position(participant)
             .ok_or(ProtocolError::InvalidIndex)
     }
 
@@ -171,7 +204,7 @@ impl ParticipantList {
             .expect("We know that no duplicates will be created, so unwrapping is safe")
     }
 
-    // Returns all the participants in the list
+    // Returns all the participants in the list, in ascending order
     pub fn participants(&self) -> &[Participant] {
         self.participants.as_slice()
     }
@@ -195,6 +228,123 @@ impl From<ParticipantList> for Vec<Participant> {
     }
 }
 
+/// The roles of a committee member, as a bitmap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Roles(u8);
+
+impl Roles {
+    /// A plain signer.
+    pub const NONE: Self = Self(0);
+    /// The member may coordinate the protocols.
+    pub const COORDINATOR: Self = Self(1);
+    /// The member follows the protocols without taking part in them.
+    pub const OBSERVER: Self = Self(1 << 1);
+
+    /// Check if all the roles of `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The roles of both `self` and `other`.
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// A participant list along with the roles of each member.
+///
+/// The roles only carry the policy of the orchestrator,
+/// the protocols themselves still take the raw participant list.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    into = "Vec<(Participant, Roles)>",
+    try_from = "Vec<(Participant, Roles)>"
+)]
+pub struct Committee {
+    participants: ParticipantList,
+    /// The roles of each member, in the order of `participants`.
+    roles: Vec<Roles>,
+}
+
+impl Committee {
+    /// Create a committee from its members and their roles.
+    ///
+    /// This will return None if the members have duplicates.
+    pub fn new(members: &[(Participant, Roles)]) -> Option<Self> {
+        let participants =
+            ParticipantList::new(&members.iter().map(|(p, _)| *p).collect::<Vec<_>>())?;
+        let mut roles = vec![Roles::NONE; members.len()];
+        for (p, role) in members {
+            *roles.get_mut(participants.position(*p)?)? = *role;
+        }
+        Some(Self {
+            participants,
+            roles,
+        })
+    }
+
+    /// The members of the committee, whatever their roles.
+    pub const fn participants(&self) -> &ParticipantList {
+        &self.participants
+    }
+
+    /// The roles of a member, or None if they are not in the committee.
+    pub fn roles(&self, participant: Participant) -> Option<Roles> {
+        self.roles
+            .get(self.participants.position(participant)?)
+            .copied()
+    }
+
+    /// The members that may coordinate, in ascending order.
+    pub fn coordinators(&self) -> Vec<Participant> {
+        self.participants
+            .participants()
+            .iter()
+            .zip(&self.roles)
+            .filter(|(_, roles)| roles.contains(Roles::COORDINATOR))
+            .map(|(p, _)| *p)
+            .collect()
+    }
+
+    /// Check that `coordinator` is a member allowed to coordinate,
+    /// before handing the participant list to a signing or CKD protocol.
+    pub fn check_coordinator(&self, coordinator: Participant) -> Result<(), InitializationError> {
+        let roles = self
+            .roles(coordinator)
+            .ok_or(InitializationError::MissingParticipant {
+                role: "coordinator",
+                participant: coordinator,
+            })?;
+        if !roles.contains(Roles::COORDINATOR) {
+            return Err(InitializationError::IneligibleCoordinator {
+                participant: coordinator,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl From<Committee> for Vec<(Participant, Roles)> {
+    fn from(committee: Committee) -> Self {
+        committee
+            .participants
+            .participants()
+            .iter()
+            .copied()
+            .zip(committee.roles)
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<(Participant, Roles)>> for Committee {
+    type Error = InitializationError;
+
+    fn try_from(members: Vec<(Participant, Roles)>) -> Result<Self, Self::Error> {
+        Self::new(&members).ok_or(InitializationError::DuplicateParticipants)
+    }
+}
+
 /// A map from participants to elements.
 ///
 /// The idea is that you have one element for each participant.
@@ -235,7 +385,7 @@ impl<'a, T> ParticipantMap<'a, T> {
     ///
     /// This will do nothing if the participant is unknown, or already has a value
     pub fn put(&mut self, participant: Participant, data: T) {
-        if let Some(&i) = self.participants.indices.get(&participant) {
+        if let Some(i) = self.participants.position(participant) {
             if let Some(data_i) = self.data.get_mut(i) {
                 if data_i.is_none() {
                     *data_i = Some(data);
@@ -245,7 +395,8 @@ impl<'a, T> ParticipantMap<'a, T> {
         }
     }
 
-    // Consumes the Map returning only the vector of the unwrapped data
+    // Consumes the Map returning only the vector of the unwrapped data,
+    // ordered as the participants of the list
     // If one of the data is still none, then return None
     pub fn into_vec_or_none(self) -> Option<Vec<T>> {
         self.data.into_iter().collect::<Option<Vec<_>>>()
@@ -260,11 +411,36 @@ impl<'a, T> ParticipantMap<'a, T> {
             .collect::<Option<Vec<_>>>()
     }
 
+    // Returns the data put so far, ordered as the participants of the list
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter().flatten()
+    }
+
+    // Consumes the map returning the participants which put data along with it,
+    // ordered as the participants of the list
+    pub fn into_entries(self) -> Vec<(Participant, T)> {
+        self.participants
+            .participants()
+            .iter()
+            .copied()
+            .zip(self.data)
+            .filter_map(|(participant, data)| data.map(|data| (participant, data)))
+            .collect()
+    }
+
     // Returns the set of included participants
     pub fn participants(&self) -> &[Participant] {
         self.participants.participants()
     }
 
+    /// Returns the data put for a participant, if any.
+    pub fn get(&self, participant: Participant) -> Option<&T> {
+        self.participants
+            .position(participant)
+            .and_then(|i| self.data.get(i))
+            .and_then(Option::as_ref)
+    }
+
     pub fn index(&self, index: Participant) -> Result<&T, ProtocolError> {
         let index = self.participants.index(index)?;
         self.data
@@ -281,8 +457,11 @@ impl<'a, T> ParticipantMap<'a, T> {
 /// This data-structure will let you put a participant in, and then tell you if this
 /// participant was newly inserted or not, allowing you to thus process the
 /// first message received from them.
+///
+/// It is public for the receive loops of integrators driving the protocols on their
+/// own executor, which can then report with [`Self::missing`] who did not respond.
 #[derive(Debug, Clone)]
-pub(crate) struct ParticipantCounter<'a> {
+pub struct ParticipantCounter<'a> {
     participants: &'a ParticipantList,
     seen: Vec<bool>,
     counter: usize,
@@ -308,9 +487,8 @@ impl<'a> ParticipantCounter<'a> {
     ///
     /// This can be checked to not process a message twice.
     pub fn put(&mut self, participant: Participant) -> bool {
-        let i = match self.participants.indices.get(&participant) {
-            None => return false,
-            Some(&i) => i,
+        let Some(i) = self.participants.position(participant) else {
+            return false;
         };
 
         // Need the old value to be false.
@@ -329,12 +507,24 @@ impl<'a> ParticipantCounter<'a> {
     pub fn full(&self) -> bool {
         self.counter == 0
     }
+
+    /// Returns the participants not seen yet, e.g. to report who did not respond
+    pub fn missing(&self) -> Vec<Participant> {
+        self.participants
+            .participants()
+            .iter()
+            .zip(&self.seen)
+            .filter(|(_, seen)| !**seen)
+            .map(|(p, _)| *p)
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::test_utils::generate_participants;
+    use frost_core::{Field, Group};
 
     #[test]
     fn test_get_index_participant_error() {
@@ -343,6 +533,33 @@ mod test {
         assert!(participants.index(Participant::from(1234_u32)).is_err());
     }
 
+    #[test]
+    fn test_try_from_identifier() {
+        fn check<C: Ciphersuite>() {
+            for p in [0, 1, 42, u32::MAX].map(Participant::from) {
+                let id = p.to_identifier::<C>().unwrap();
+                assert_eq!(Participant::try_from_identifier(&id), Some(p));
+            }
+            // frost numbers its identifiers from one
+            let id = Identifier::<C>::try_from(5u16).unwrap();
+            assert_eq!(
+                Participant::try_from_identifier(&id),
+                Some(Participant::from(4u32))
+            );
+            // beyond the participant range, on both ends of the serialization
+            let one = Participant::from(0u32).scalar::<C>();
+            let past_last = Participant::from(u32::MAX).scalar::<C>() + one;
+            let minus_one = <C::Group as Group>::Field::zero() - one;
+            for scalar in [past_last, minus_one] {
+                let id = Identifier::<C>::new(scalar).unwrap();
+                assert_eq!(Participant::try_from_identifier(&id), None);
+            }
+        }
+        check::<frost_secp256k1::Secp256K1Sha256>();
+        check::<frost_ed25519::Ed25519Sha512>();
+        check::<crate::confidential_key_derivation::ciphersuite::BLS12381SHA256>();
+    }
+
     #[test]
     fn test_get_index_data_error() {
         let participants = generate_participants(5);
@@ -353,4 +570,126 @@ mod test {
         // no data test
         assert!(map.index(Participant::from(1_u32)).is_err());
     }
+
+    #[test]
+    fn test_participant_map_get() {
+        let participants = generate_participants(5);
+        let participants = ParticipantList::new(&participants).unwrap();
+        let mut map: ParticipantMap<'_, u32> = ParticipantMap::new(&participants);
+        map.put(Participant::from(2_u32), 7);
+        assert_eq!(map.get(Participant::from(2_u32)), Some(&7));
+        // no data
+        assert_eq!(map.get(Participant::from(1_u32)), None);
+        // no participant
+        assert_eq!(map.get(Participant::from(1233_u32)), None);
+    }
+
+    #[test]
+    fn test_order_independent_of_input() {
+        let ids = [7_u32, 2, 1_000_000, 0, 42];
+        let sorted = [0_u32, 2, 7, 42, 1_000_000].map(Participant::from);
+        let participants = ParticipantList::new(&ids.map(Participant::from)).unwrap();
+        let mut reversed_ids = ids.map(Participant::from);
+        reversed_ids.reverse();
+        let reversed = ParticipantList::new(&reversed_ids).unwrap();
+        assert_eq!(participants.participants(), sorted);
+        assert_eq!(reversed.participants(), sorted);
+
+        for (i, p) in sorted.iter().enumerate() {
+            assert_eq!(participants.position(*p), Some(i));
+            assert_eq!(participants.get_participant(i), Some(*p));
+        }
+        assert_eq!(participants.position(Participant::from(3_u32)), None);
+
+        // identifiers are ascending too
+        let scalars = sorted
+            .iter()
+            .map(|p| {
+                p.scalar::<frost_secp256k1::Secp256K1Sha256>()
+                    .to_bytes()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert!(scalars.windows(2).all(|w| w[0] < w[1]));
+
+        // the map data follows the order of the list, not the insertion order
+        let mut map = ParticipantMap::new(&participants);
+        for id in ids {
+            map.put(Participant::from(id), id);
+        }
+        assert_eq!(
+            map.into_vec_or_none().unwrap(),
+            sorted.map(u32::from).to_vec()
+        );
+
+        let others = ParticipantList::new(&[sorted[4], sorted[1], sorted[3]]).unwrap();
+        assert_eq!(
+            participants.intersection(&others).participants(),
+            [sorted[1], sorted[3], sorted[4]]
+        );
+    }
+
+    #[test]
+    fn test_participant_counter_missing() {
+        let participants = generate_participants(5);
+        let list = ParticipantList::new(&participants).unwrap();
+        let mut counter = ParticipantCounter::new(&list);
+        assert_eq!(counter.missing(), participants);
+
+        assert!(counter.put(participants[1]));
+        assert!(counter.put(participants[3]));
+        // ignored puts do not change the missing set
+        assert!(!counter.put(participants[3]));
+        assert!(!counter.put(Participant::from(1234u32)));
+        assert_eq!(
+            counter.missing(),
+            vec![participants[0], participants[2], participants[4]]
+        );
+
+        for p in &participants {
+            counter.put(*p);
+        }
+        assert!(counter.full());
+        assert!(counter.missing().is_empty());
+    }
+
+    #[test]
+    fn test_committee() {
+        let ids = generate_participants(4);
+        let committee = Committee::new(&[
+            (ids[3], Roles::COORDINATOR),
+            (ids[0], Roles::NONE),
+            (ids[2], Roles::COORDINATOR.union(Roles::OBSERVER)),
+            (ids[1], Roles::OBSERVER),
+        ])
+        .unwrap();
+        assert_eq!(committee.participants().participants(), ids.as_slice());
+        assert_eq!(committee.coordinators(), [ids[2], ids[3]]);
+        assert_eq!(committee.roles(ids[1]), Some(Roles::OBSERVER));
+        assert_eq!(committee.roles(Participant::from(1234_u32)), None);
+
+        assert!(committee.check_coordinator(ids[2]).is_ok());
+        assert_eq!(
+            committee.check_coordinator(ids[1]),
+            Err(InitializationError::IneligibleCoordinator {
+                participant: ids[1]
+            })
+        );
+        assert!(matches!(
+            committee.check_coordinator(Participant::from(1234_u32)),
+            Err(InitializationError::MissingParticipant { .. })
+        ));
+
+        let json = serde_json::to_string(&committee).unwrap();
+        let decoded: Committee = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.coordinators(), committee.coordinators());
+        assert_eq!(
+            Vec::<(Participant, Roles)>::from(decoded),
+            Vec::<(Participant, Roles)>::from(committee)
+        );
+
+        assert!(Committee::new(&[(ids[0], Roles::NONE), (ids[0], Roles::COORDINATOR)]).is_none());
+        let duplicates = format!("[[{0},0],[{0},1]]", u32::from(ids[0]));
+        assert!(serde_json::from_str::<Committee>(&duplicates).is_err());
+    }
 }
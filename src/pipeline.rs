@@ -0,0 +1,144 @@
+//! Protocols chaining several phases, so that the caller drives a single [`Protocol`].
+use crate::{
+    dkg::{assert_key_invariants, do_keygen},
+    ecdsa::{
+        robust_ecdsa::{
+            presign::{assert_presign_inputs, do_presign},
+            PresignArguments, PresignOutput,
+        },
+        KeygenOutput, Secp256K1Sha256,
+    },
+    errors::InitializationError,
+    participants::Participant,
+    protocol::{
+        internal::{make_protocol, Comms},
+        Protocol,
+    },
+    MaxMalicious,
+};
+use rand_core::CryptoRngCore;
+
+/// Runs the robust ECDSA key generation, then presigns with the fresh key
+/// without returning control to the caller in between.
+///
+/// The key is generated with threshold `max_malicious + 1`, as expected by robust ECDSA,
+/// hence the participants must be exactly `2 * max_malicious + 1`.
+pub fn keygen_then_presign(
+    participants: &[Participant],
+    me: Participant,
+    max_malicious: impl Into<MaxMalicious>,
+    rng: impl CryptoRngCore + Send + 'static,
+) -> Result<impl Protocol<Output = (KeygenOutput, PresignOutput)>, InitializationError> {
+    let max_malicious = max_malicious.into();
+    let participants = assert_presign_inputs(participants, me, max_malicious)?;
+    // cannot overflow as max_malicious is at most half the participant count
+    let threshold = max_malicious.value() + 1;
+    assert_key_invariants(participants.participants(), me, threshold)?;
+
+    let comms = Comms::new();
+    let chan = comms.shared_channel();
+    let fut = async move {
+        let mut rng = rng;
+        let keygen_out = do_keygen::<Secp256K1Sha256>(
+            chan.child(0),
+            participants.clone(),
+            me,
+            threshold,
+            None,
+            &mut rng,
+        )
+        .await?;
+        let args = PresignArguments {
+            keygen_out: keygen_out.clone(),
+            max_malicious,
+            active_security: true,
+        };
+        let presignature = do_presign(chan.child(1), participants, me, args, rng).await?;
+        Ok((keygen_out, presignature))
+    };
+    Ok(make_protocol(comms, fut))
+}
+
+#[cfg(test)]
+mod test {
+    use super::keygen_then_presign;
+    use crate::ecdsa::{
+        robust_ecdsa::{sign::sign, PresignOutput, RerandomizedPresignOutput},
+        KeygenOutput, MessageHash, Scalar, SignatureOption,
+    };
+    use crate::errors::InitializationError;
+    use crate::protocol::Protocol;
+    use crate::test_utils::{
+        assert_public_key_invariant, check_one_coordinator_output, generate_participants,
+        run_protocol, GenProtocol, MockCryptoRng,
+    };
+    use rand_core::{RngCore, SeedableRng};
+
+    #[test]
+    fn test_keygen_then_presign() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let max_malicious: usize = 2;
+        let participants = generate_participants(5);
+
+        let mut protocols: GenProtocol<(KeygenOutput, PresignOutput)> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let protocol = keygen_then_presign(
+                &participants,
+                *p,
+                max_malicious,
+                MockCryptoRng::seed_from_u64(rng.next_u64()),
+            )
+            .unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let result = run_protocol(protocols).unwrap();
+        let keys = result
+            .iter()
+            .map(|(p, (keygen_out, _))| (*p, keygen_out.clone()))
+            .collect::<Vec<_>>();
+        assert_public_key_invariant(&keys);
+
+        // the presignatures are usable with the generated key
+        let public_key = keys[0].1.public_key.to_element().to_affine();
+        let coordinator = participants[0];
+        let msg_hash = Scalar::from(42u64);
+        let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+        for (p, (_, presignature)) in result {
+            let protocol = sign(
+                &participants,
+                coordinator,
+                max_malicious,
+                p,
+                public_key,
+                RerandomizedPresignOutput::new_without_rerandomization(&presignature),
+                presignature.id,
+                0,
+                MessageHash::from_prehashed(msg_hash.to_bytes().into()),
+            )
+            .unwrap();
+            protocols.push((
+                p,
+                Box::new(protocol) as Box<dyn Protocol<Output = SignatureOption>>,
+            ));
+        }
+        let signature =
+            check_one_coordinator_output(run_protocol(protocols).unwrap(), coordinator).unwrap();
+        assert!(signature.verify(&public_key, &msg_hash));
+    }
+
+    #[test]
+    fn test_keygen_then_presign_rejects_bad_participants() {
+        let participants = generate_participants(4);
+        let result = keygen_then_presign(
+            &participants,
+            participants[0],
+            1usize,
+            MockCryptoRng::seed_from_u64(42),
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            InitializationError::BadParameters(_)
+        ));
+    }
+}
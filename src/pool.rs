@@ -0,0 +1,114 @@
+//! A pool of presignatures kept by a signing service between two refills.
+use std::collections::VecDeque;
+
+/// A queue of presignatures, handed out oldest first.
+///
+/// Presignatures are moved out of the pool by [`Self::take`] and the pool cannot be
+/// cloned, so the pool never hands out the same presignature twice.
+/// The presignature types are [`Clone`] however, and the pool cannot tell whether a
+/// copy of a pushed presignature is kept or pushed again elsewhere: callers must
+/// push the only copy.
+pub struct PresignPool<P> {
+    presignatures: VecDeque<P>,
+}
+
+impl<P> PresignPool<P> {
+    pub const fn new() -> Self {
+        Self {
+            presignatures: VecDeque::new(),
+        }
+    }
+
+    /// Adds a fresh presignature to the pool.
+    pub fn push(&mut self, presignature: P) {
+        self.presignatures.push_back(presignature);
+    }
+
+    /// Removes the oldest presignature from the pool, to be used for a single signature.
+    pub fn take(&mut self) -> Option<P> {
+        self.presignatures.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.presignatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presignatures.is_empty()
+    }
+
+    /// Whether the pool went below `low_watermark` presignatures and should be refilled.
+    pub fn needs_refill(&self, low_watermark: usize) -> bool {
+        self.len() < low_watermark
+    }
+}
+
+impl<P> Default for PresignPool<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> Extend<P> for PresignPool<P> {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, presignatures: I) {
+        self.presignatures.extend(presignatures);
+    }
+}
+
+impl<P> FromIterator<P> for PresignPool<P> {
+    fn from_iter<I: IntoIterator<Item = P>>(presignatures: I) -> Self {
+        Self {
+            presignatures: presignatures.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PresignPool;
+    use crate::ecdsa::robust_ecdsa::PresignOutput;
+    use crate::ecdsa::{PresignId, Secp256K1Sha256};
+    use crate::participants::ParticipantList;
+    use crate::test_utils::{generate_participants, MockCryptoRng};
+    use k256::ProjectivePoint;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_presign_pool() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = ParticipantList::new(&generate_participants(3)).unwrap();
+        let mut random_scalar = || frost_core::random_nonzero::<Secp256K1Sha256, _>(&mut rng);
+        let presignatures = (0..5)
+            .map(|_| {
+                let big_r = (ProjectivePoint::GENERATOR * random_scalar()).to_affine();
+                PresignOutput {
+                    big_r,
+                    c: random_scalar(),
+                    e: random_scalar(),
+                    alpha: random_scalar(),
+                    beta: random_scalar(),
+                    id: PresignId::derive(&big_r, &participants),
+                    epoch: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+        let ids = presignatures.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        let low_watermark = 3;
+        let mut pool = presignatures.into_iter().collect::<PresignPool<_>>();
+        assert_eq!(pool.len(), 5);
+        assert!(!pool.needs_refill(low_watermark));
+
+        // draining below the watermark signals the refill,
+        // and every presignature is handed out exactly once
+        let mut taken = Vec::new();
+        while let Some(presignature) = pool.take() {
+            taken.push(presignature.id);
+            assert_eq!(pool.needs_refill(low_watermark), pool.len() < low_watermark);
+        }
+        assert_eq!(taken, ids);
+        assert!(pool.is_empty());
+        assert!(pool.needs_refill(low_watermark));
+        assert!(pool.take().is_none());
+    }
+}
@@ -1,10 +1,12 @@
+use crate::errors::InitializationError;
 use crate::participants::{ParticipantCounter, ParticipantList, ParticipantMap};
 use crate::protocol::ProtocolError;
 use crate::protocol::{
-    internal::{SharedChannel, Waitpoint},
-    Participant,
+    internal::{make_protocol, Comms, Waitpoint},
+    Channel, Participant, Protocol,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// This structure is essential for the reliable broadcast protocol
 /// Send is used in the first phase, Echo in the second, and Ready
@@ -80,7 +82,7 @@ fn echo_ready_thresholds(n: usize) -> (usize, usize) {
 /// This reliable broadcast function is the echo-broadcast protocol from the sender side.
 /// It broadcasts some data in a vote
 pub fn reliable_broadcast_send<T>(
-    chan: &SharedChannel,
+    chan: &impl Channel,
     wait: Waitpoint,
     participants: &ParticipantList,
     me: Participant,
@@ -138,7 +140,7 @@ impl<'a, T: PartialEq> BroadcastProtocolState<'a, T> {
 /// `Reliable_broadcast_receive_all` is expected to be called right after `reliable_broadcast_send`.
 #[allow(clippy::too_many_lines)]
 pub async fn reliable_broadcast_receive_all<'a, T>(
-    chan: &SharedChannel,
+    chan: &impl Channel,
     wait: Waitpoint,
     participants: &'a ParticipantList,
     me: Participant,
@@ -332,7 +334,7 @@ where
 /// The reliable echo-broadcast protocol that party me is supposed
 /// to run with all the other parties
 pub async fn do_broadcast<'a, T>(
-    chan: &mut SharedChannel,
+    chan: &mut impl Channel,
     participants: &'a ParticipantList,
     me: Participant,
     data: T,
@@ -347,11 +349,56 @@ where
     Ok(vote_list)
 }
 
+/// Reliably broadcasts `value` to all the `participants`, e.g. to agree on the
+/// message to sign before starting a signing protocol.
+///
+/// The output maps every participant, including `me`, to the value it broadcast.
+/// As long as at most `(n-1)/3` participants are malicious, every honest participant
+/// outputs the same map, and the entry of an honest participant is the value it sent.
+pub fn echo_broadcast<T>(
+    participants: &[Participant],
+    me: Participant,
+    value: T,
+) -> Result<impl Protocol<Output = BTreeMap<Participant, T>>, InitializationError>
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + Send + 'static,
+{
+    if participants.len() < 2 {
+        return Err(InitializationError::NotEnoughParticipants {
+            participants: participants.len(),
+        });
+    }
+    let participants =
+        ParticipantList::new(participants).ok_or(InitializationError::DuplicateParticipants)?;
+    if !participants.contains(me) {
+        return Err(InitializationError::MissingParticipant {
+            role: "self",
+            participant: me,
+        });
+    }
+
+    let comms = Comms::new();
+    let mut chan = comms.shared_channel();
+    let fut = async move {
+        let values = do_broadcast(&mut chan, &participants, me, value).await?;
+        participants
+            .participants()
+            .iter()
+            .map(|p| {
+                values
+                    .get(*p)
+                    .map(|v| (*p, v.clone()))
+                    .ok_or(ProtocolError::MissingContribution(*p))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+    };
+    Ok(make_protocol(comms, fut))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::protocol::internal::{make_protocol, Comms};
-    use crate::protocol::{Protocol, ProtocolError};
+    use crate::protocol::{internal::SharedChannel, ProtocolError};
     use crate::test_utils::{generate_participants, run_protocol};
 
     /// This function is similar to `do_broadcast` except it is tailored to
@@ -633,4 +680,47 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_echo_broadcast() {
+        let participants = generate_participants(5);
+        let mut protocols: Vec<(_, Box<dyn Protocol<Output = BTreeMap<Participant, String>>>)> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let protocol =
+                echo_broadcast(&participants, *p, format!("message from {p:?}")).unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+
+        let result = run_protocol(protocols).unwrap();
+        let expected = participants
+            .iter()
+            .map(|p| (*p, format!("message from {p:?}")))
+            .collect::<BTreeMap<_, _>>();
+        for (_, values) in result {
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    fn test_echo_broadcast_inputs() {
+        let participants = generate_participants(3);
+        let outsider = Participant::from(42u32);
+        assert_eq!(
+            echo_broadcast(&participants[..1], participants[0], 0u8).err(),
+            Some(InitializationError::NotEnoughParticipants { participants: 1 })
+        );
+        assert_eq!(
+            echo_broadcast(&participants, outsider, 0u8).err(),
+            Some(InitializationError::MissingParticipant {
+                role: "self",
+                participant: outsider
+            })
+        );
+        let duplicated = [participants[0], participants[0]];
+        assert_eq!(
+            echo_broadcast(&duplicated, participants[0], 0u8).err(),
+            Some(InitializationError::DuplicateParticipants)
+        );
+    }
 }
@@ -1,25 +1,49 @@
 //! Helper functions for the protocol.
-use super::{internal::SharedChannel, Participant, ProtocolError};
-use crate::participants::{ParticipantCounter, ParticipantList};
+use super::{Channel, Participant, ProtocolError};
+use crate::participants::{ParticipantCounter, ParticipantList, ParticipantMap};
 
 /// Gather exactly one message from each participant in a group before proceeding.
+///
+/// The messages are returned ordered as the participants of the list.
 pub async fn recv_from_others<T>(
-    chan: &SharedChannel,
+    chan: &impl Channel,
     waitpoint: u64,
     participants: &ParticipantList,
     me: Participant,
 ) -> Result<Vec<(Participant, T)>, ProtocolError>
 where
     T: serde::de::DeserializeOwned,
+{
+    let messages = collect_from_all(chan, waitpoint, participants, me, |_, msg: T| Ok(msg)).await?;
+    Ok(messages.into_entries())
+}
+
+/// Gather exactly one message from each participant in a group, checking each
+/// message with `verify` as it arrives.
+///
+/// `verify` turns the message of a participant into the value stored for it,
+/// and should return an error attributed to that participant if the message is
+/// invalid, which aborts the collection. Repeated messages are dropped before
+/// being verified. The returned map holds no value for `me`.
+pub async fn collect_from_all<'a, T, U, F>(
+    chan: &impl Channel,
+    waitpoint: u64,
+    participants: &'a ParticipantList,
+    me: Participant,
+    mut verify: F,
+) -> Result<ParticipantMap<'a, U>, ProtocolError>
+where
+    T: serde::de::DeserializeOwned,
+    F: FnMut(Participant, T) -> Result<U, ProtocolError>,
 {
     let mut seen = ParticipantCounter::new(participants);
     seen.put(me);
-    let mut messages = Vec::with_capacity(participants.others(me).count());
+    let mut messages = ParticipantMap::new(participants);
 
     while !seen.full() {
         let (from, msg) = chan.recv(waitpoint).await?;
         if seen.put(from) {
-            messages.push((from, msg));
+            messages.put(from, verify(from, msg)?);
         }
     }
 
@@ -42,7 +42,7 @@
 //! This is why we have to take great care that the identifiers a protocol will produce
 //! are deterministic, even in the presence of concurrent tasks.
 
-use super::{Action, MessageData, Participant, Protocol, ProtocolError};
+use super::{Action, Channel, MessageData, Participant, Protocol, ProtocolError};
 use futures::future::BoxFuture;
 use futures::lock::Mutex;
 use futures::task::noop_waker;
@@ -51,7 +51,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::VecDeque;
 use std::task::Context;
-use std::{collections::HashMap, error, future::Future, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 
 use crate::crypto::constants::NEAR_CHANNEL_TAGS_DOMAIN;
 
@@ -187,6 +187,31 @@ impl MessageHeader {
     }
 }
 
+/// The number of bytes prepended to every message to route it.
+pub(crate) const MESSAGE_HEADER_LEN: usize = MessageHeader::LEN;
+
+/// Returns the encoded header of a message, identifying its channel and waitpoint.
+#[cfg(feature = "test-utils")]
+pub(crate) fn message_header(message: &[u8]) -> Option<&[u8]> {
+    message.get(..MessageHeader::LEN)
+}
+
+/// Moves a message to the next waitpoint of its channel, simulating a misrouted message.
+///
+/// Returns false, leaving the message untouched, if it has no valid header.
+#[cfg(feature = "test-utils")]
+pub(crate) fn bump_message_waitpoint(message: &mut [u8]) -> bool {
+    let Some(mut header) = MessageHeader::from_bytes(message) else {
+        return false;
+    };
+    header.next_waitpoint();
+    let Some(encoded) = message.get_mut(..MessageHeader::LEN) else {
+        return false;
+    };
+    encoded.copy_from_slice(&header.to_bytes());
+    true
+}
+
 struct SubMessageQueue {
     sender: futures::channel::mpsc::UnboundedSender<(Participant, MessageData)>,
     receiver: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<(Participant, MessageData)>>>,
@@ -335,9 +360,13 @@ impl Comms {
         let message_data = data.get(MessageHeader::LEN..).ok_or_else(|| {
             ProtocolError::DeserializationError("Failed to deserialize message data".to_string())
         })?;
-        let decoded: Result<T, Box<dyn error::Error + Send + Sync>> =
-            rmp_serde::decode::from_slice(message_data).map_err(std::convert::Into::into);
-        Ok((from, decoded?))
+        let decoded = rmp_serde::decode::from_slice(message_data).map_err(|_| {
+            ProtocolError::MalformedMessage {
+                from,
+                waitpoint: header.waitpoint,
+            }
+        })?;
+        Ok((from, decoded))
     }
 
     pub fn private_channel(&self, from: Participant, to: Participant) -> PrivateChannel {
@@ -368,6 +397,14 @@ impl SharedChannel {
         self.header.next_waitpoint()
     }
 
+    /// Get the ith child of this channel, e.g. to run sub-protocols one after the other.
+    pub fn child(&self, i: u64) -> Self {
+        Self {
+            comms: self.comms.clone(),
+            header: self.header.child(i),
+        }
+    }
+
     pub fn send_many<T: Serialize>(
         &self,
         waitpoint: Waitpoint,
@@ -397,6 +434,32 @@ impl SharedChannel {
     }
 }
 
+impl Channel for SharedChannel {
+    fn next_waitpoint(&mut self) -> Waitpoint {
+        Self::next_waitpoint(self)
+    }
+
+    fn send_many<T: Serialize>(&self, waitpoint: Waitpoint, data: &T) -> Result<(), ProtocolError> {
+        Self::send_many(self, waitpoint, data)
+    }
+
+    fn send_private<T: Serialize>(
+        &self,
+        waitpoint: Waitpoint,
+        to: Participant,
+        data: &T,
+    ) -> Result<(), ProtocolError> {
+        Self::send_private(self, waitpoint, to, data)
+    }
+
+    fn recv<T: DeserializeOwned>(
+        &self,
+        waitpoint: Waitpoint,
+    ) -> impl Future<Output = Result<(Participant, T), ProtocolError>> + Send {
+        Self::recv(self, waitpoint)
+    }
+}
+
 /// Represents a private channel.
 ///
 /// This can be seen as a separate "namespace" for `SharedChannel`.
@@ -552,4 +615,24 @@ mod tests {
 
         assert!(messages.len() == usize::try_from(attack_count).unwrap());
     }
+
+    #[test]
+    fn recv_reports_malformed_messages() {
+        let comms = Comms::new();
+        let sender = Participant::from(7_u32);
+        let header = MessageHeader::new(ChannelTag::root_shared()).with_waitpoint(3);
+        let mut message = header.to_bytes().to_vec();
+        // a msgpack string where an integer is expected
+        message.extend_from_slice(&[0xa1, b'x']);
+        comms.push_message(sender, message);
+
+        let result = futures::executor::block_on(comms.recv::<u64>(header));
+        assert_eq!(
+            result.err(),
+            Some(ProtocolError::MalformedMessage {
+                from: sender,
+                waitpoint: 3
+            })
+        );
+    }
 }
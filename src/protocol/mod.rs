@@ -9,9 +9,16 @@ pub(crate) mod echo_broadcast;
 pub(crate) mod helpers;
 pub(crate) mod internal;
 
+use std::future::Future;
+
+use serde::{de::DeserializeOwned, Serialize};
+
 use crate::errors::ProtocolError;
 use crate::participants::Participant;
 
+pub use echo_broadcast::echo_broadcast;
+pub use internal::Waitpoint;
+
 /// Represents the data making up a message.
 ///
 /// We choose to just represent messages as opaque vectors of bytes, with all
@@ -43,6 +50,44 @@ pub enum Action<T> {
     Return(T),
 }
 
+/// The output of a protocol in which only the coordinator learns the result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoleOutput<T> {
+    /// The result, returned to the coordinator.
+    Coordinator(T),
+    /// Returned to the other participants.
+    Participant,
+}
+
+impl<T> RoleOutput<T> {
+    /// Returns the result if we are the coordinator.
+    pub fn into_coordinator_output(self) -> Option<T> {
+        match self {
+            Self::Coordinator(output) => Some(output),
+            Self::Participant => None,
+        }
+    }
+
+    /// Converts the result of the coordinator, if any.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> RoleOutput<U> {
+        match self {
+            Self::Coordinator(output) => RoleOutput::Coordinator(f(output)),
+            Self::Participant => RoleOutput::Participant,
+        }
+    }
+
+    /// Whether the protocol ran as the coordinator, i.e. holds the result.
+    pub const fn is_coordinator(&self) -> bool {
+        matches!(self, Self::Coordinator(_))
+    }
+}
+
+impl<T> From<RoleOutput<T>> for Option<T> {
+    fn from(output: RoleOutput<T>) -> Self {
+        output.into_coordinator_output()
+    }
+}
+
 /// A trait for protocols.
 ///
 /// Basically, this represents a struct for the behavior of a single participant
@@ -63,3 +108,35 @@ pub trait Protocol {
     /// Inform the protocol of a new message.
     fn message(&mut self, from: Participant, data: MessageData);
 }
+
+/// The messaging operations the protocol cores run on.
+///
+/// The [`Protocol`] returned by each entry point drives its core over an internal
+/// channel implementing this trait. With the `custom-executor` feature, the key
+/// generation and ECDSA cores are also exposed as plain futures over any
+/// implementation, e.g. one backed by an async transport, to be awaited on
+/// another executor.
+///
+/// Implementations must deliver every message at the waitpoint it was sent on,
+/// authenticate its sender, and keep private messages confidential.
+pub trait Channel: Send + Sync {
+    /// Get the next available waitpoint on this channel.
+    fn next_waitpoint(&mut self) -> Waitpoint;
+
+    /// Send a message to all other participants.
+    fn send_many<T: Serialize>(&self, waitpoint: Waitpoint, data: &T) -> Result<(), ProtocolError>;
+
+    /// Send a message to a single participant.
+    fn send_private<T: Serialize>(
+        &self,
+        waitpoint: Waitpoint,
+        to: Participant,
+        data: &T,
+    ) -> Result<(), ProtocolError>;
+
+    /// Wait for the next message sent to us at this waitpoint.
+    fn recv<T: DeserializeOwned>(
+        &self,
+        waitpoint: Waitpoint,
+    ) -> impl Future<Output = Result<(Participant, T), ProtocolError>> + Send;
+}
@@ -0,0 +1,305 @@
+//! A scheme-tagged signature, for callers handling the output of several schemes.
+//!
+//! The envelope serializes to a 1 byte scheme tag followed by the
+//! serialization of the wrapped signature.
+use frost_core::{Field, Group};
+use frost_secp256k1::{Secp256K1Group, Secp256K1ScalarField};
+use k256::{AffinePoint, ProjectivePoint};
+use reddsa::frost::redjubjub;
+use serde::{Deserialize, Serialize};
+
+use crate::ecdsa::{self, msg_hash_scalar};
+use crate::errors::{InitializationError, ProtocolError};
+use crate::frost::eddsa;
+use crate::SignInput;
+
+/// A signature produced by one of the supported schemes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureEnvelope {
+    Ecdsa(ecdsa::Signature),
+    EdDsa(eddsa::Signature),
+    RedJubjub(redjubjub::Signature),
+}
+
+/// The public key checking a [`SignatureEnvelope`] of the same scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemePublicKey {
+    Ecdsa(AffinePoint),
+    EdDsa(eddsa::VerifyingKey),
+    RedJubjub(redjubjub::VerifyingKey),
+}
+
+impl SignatureEnvelope {
+    pub const ECDSA_TAG: u8 = 0;
+    pub const EDDSA_TAG: u8 = 1;
+    pub const REDJUBJUB_TAG: u8 = 2;
+
+    /// Returns the tag identifying the scheme in the serialization
+    pub const fn scheme_tag(&self) -> u8 {
+        match self {
+            Self::Ecdsa(_) => Self::ECDSA_TAG,
+            Self::EdDsa(_) => Self::EDDSA_TAG,
+            Self::RedJubjub(_) => Self::REDJUBJUB_TAG,
+        }
+    }
+
+    /// Serializes the signature as the scheme tag followed by the signature bytes.
+    ///
+    /// An ECDSA signature is written as the compressed `big_r` followed by `s`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProtocolError> {
+        let mut out = vec![self.scheme_tag()];
+        match self {
+            Self::Ecdsa(sig) => {
+                let big_r = Secp256K1Group::serialize(&ProjectivePoint::from(sig.big_r))
+                    .map_err(|_| ProtocolError::PointSerialization)?;
+                out.extend_from_slice(&big_r);
+                out.extend_from_slice(&Secp256K1ScalarField::serialize(&sig.s));
+            }
+            Self::EdDsa(sig) => {
+                out.extend(sig.serialize().map_err(|_| ProtocolError::ErrorEncoding)?);
+            }
+            Self::RedJubjub(sig) => {
+                out.extend(sig.serialize().map_err(|_| ProtocolError::ErrorEncoding)?);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Deserializes a signature written by [`Self::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let (tag, payload) = bytes.split_first().ok_or(ProtocolError::MalformedElement)?;
+        match *tag {
+            Self::ECDSA_TAG => {
+                let (big_r, s) = payload
+                    .split_at_checked(33)
+                    .ok_or(ProtocolError::MalformedElement)?;
+                let big_r: [u8; 33] = big_r
+                    .try_into()
+                    .map_err(|_| ProtocolError::MalformedElement)?;
+                let s: [u8; 32] = s.try_into().map_err(|_| ProtocolError::MalformedElement)?;
+                let big_r = Secp256K1Group::deserialize(&big_r)
+                    .map_err(|_| ProtocolError::MalformedElement)?;
+                let s = Secp256K1ScalarField::deserialize(&s)
+                    .map_err(|_| ProtocolError::MalformedElement)?;
+                ecdsa::Signature::new(big_r.to_affine(), s).map(Self::Ecdsa)
+            }
+            Self::EDDSA_TAG => eddsa::Signature::deserialize(payload)
+                .map(Self::EdDsa)
+                .map_err(|_| ProtocolError::MalformedElement),
+            Self::REDJUBJUB_TAG => redjubjub::Signature::deserialize(payload)
+                .map(Self::RedJubjub)
+                .map_err(|_| ProtocolError::MalformedElement),
+            tag => Err(ProtocolError::InvalidInput(format!(
+                "unknown signature scheme tag {tag}"
+            ))),
+        }
+    }
+
+    /// Verifies the signature of `msg` with the verifier of its scheme.
+    ///
+    /// ECDSA expects a prehashed message while the other schemes expect the raw one.
+    pub fn verify(&self, pk: &SchemePublicKey, msg: &SignInput) -> Result<(), ProtocolError> {
+        let to_protocol_error =
+            |err: InitializationError| ProtocolError::InvalidInput(err.to_string());
+        let verified = match (self, pk) {
+            (Self::Ecdsa(sig), SchemePublicKey::Ecdsa(pk)) => {
+                let msg_hash = msg_hash_scalar(msg.clone()).map_err(to_protocol_error)?;
+                sig.verify(pk, &msg_hash)
+            }
+            (Self::EdDsa(sig), SchemePublicKey::EdDsa(pk)) => {
+                let message = msg.clone().into_raw("EdDSA").map_err(to_protocol_error)?;
+                pk.verify(&message, sig).is_ok()
+            }
+            (Self::RedJubjub(sig), SchemePublicKey::RedJubjub(pk)) => {
+                let message = msg.clone().into_raw("RedDSA").map_err(to_protocol_error)?;
+                pk.verify(&message, sig).is_ok()
+            }
+            _ => {
+                return Err(ProtocolError::InvalidInput(
+                    "the public key does not belong to the signature scheme".to_string(),
+                ))
+            }
+        };
+        if verified {
+            Ok(())
+        } else {
+            Err(ProtocolError::SignatureVerificationFailed)
+        }
+    }
+}
+
+impl From<ecdsa::Signature> for SignatureEnvelope {
+    fn from(sig: ecdsa::Signature) -> Self {
+        Self::Ecdsa(sig)
+    }
+}
+
+impl From<eddsa::Signature> for SignatureEnvelope {
+    fn from(sig: eddsa::Signature) -> Self {
+        Self::EdDsa(sig)
+    }
+}
+
+impl From<redjubjub::Signature> for SignatureEnvelope {
+    fn from(sig: redjubjub::Signature) -> Self {
+        Self::RedJubjub(sig)
+    }
+}
+
+impl Serialize for SignatureEnvelope {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let bytes = self.to_bytes().map_err(serde::ser::Error::custom)?;
+        serde_bytes::Serialize::serialize(&bytes[..], serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignatureEnvelope {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let v: Vec<u8> = serde_bytes::Deserialize::deserialize(deserializer)?;
+        Self::from_bytes(&v).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SchemePublicKey, SignatureEnvelope};
+    use crate::ecdsa::{self, hash_message, MessageHash, Scalar};
+    use crate::errors::ProtocolError;
+    use crate::frost::eddsa;
+    use crate::test_utils::{assert_serde_roundtrip, MockCryptoRng};
+    use crate::SignInput;
+
+    use k256::{
+        elliptic_curve::{scalar::IsHigh, Field},
+        ProjectivePoint,
+    };
+    use rand_core::SeedableRng;
+    use reddsa::frost::redjubjub;
+
+    fn ecdsa_signature(
+        rng: &mut MockCryptoRng,
+        msg_hash: Scalar,
+    ) -> (ecdsa::Signature, SchemePublicKey) {
+        let sk = Scalar::random(&mut *rng);
+        let k = Scalar::random(&mut *rng);
+        let big_r = (ProjectivePoint::GENERATOR * k).to_affine();
+        let r = ecdsa::x_coordinate(&big_r);
+        let s = k.invert().unwrap() * (msg_hash + r * sk);
+        let s = if bool::from(s.is_high()) { -s } else { s };
+        let pk = (ProjectivePoint::GENERATOR * sk).to_affine();
+        (
+            ecdsa::Signature::new(big_r, s).unwrap(),
+            SchemePublicKey::Ecdsa(pk),
+        )
+    }
+
+    #[test]
+    fn test_ecdsa_envelope() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let msg_hash = hash_message(b"Hello from Near");
+        let (sig, pk) = ecdsa_signature(&mut rng, msg_hash);
+        let envelope = SignatureEnvelope::from(sig);
+
+        let bytes = envelope.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 1 + 33 + 32);
+        assert_eq!(bytes[0], SignatureEnvelope::ECDSA_TAG);
+        assert_eq!(SignatureEnvelope::from_bytes(&bytes).unwrap(), envelope);
+
+        let msg = SignInput::from(MessageHash::from_message(b"Hello from Near"));
+        assert!(envelope.verify(&pk, &msg).is_ok());
+        assert_eq!(
+            envelope.verify(
+                &pk,
+                &SignInput::from(MessageHash::from_message(b"another message"))
+            ),
+            Err(ProtocolError::SignatureVerificationFailed)
+        );
+        assert!(matches!(
+            envelope.verify(&pk, &SignInput::Raw(b"Hello from Near".to_vec())),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_frost_envelopes() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let msg = b"Hello from Near".to_vec();
+
+        let eddsa_sk = frost_ed25519::SigningKey::new(&mut rng);
+        let eddsa_env = SignatureEnvelope::from(eddsa_sk.sign(&mut rng, &msg));
+        let eddsa_pk = SchemePublicKey::EdDsa(eddsa::VerifyingKey::from(&eddsa_sk));
+
+        let redjubjub_sk = redjubjub::SigningKey::new(&mut rng);
+        let redjubjub_env = SignatureEnvelope::from(redjubjub_sk.sign(&mut rng, &msg));
+        let redjubjub_pk = SchemePublicKey::RedJubjub(redjubjub::VerifyingKey::from(&redjubjub_sk));
+
+        for (envelope, pk, tag) in [
+            (&eddsa_env, &eddsa_pk, SignatureEnvelope::EDDSA_TAG),
+            (
+                &redjubjub_env,
+                &redjubjub_pk,
+                SignatureEnvelope::REDJUBJUB_TAG,
+            ),
+        ] {
+            let bytes = envelope.to_bytes().unwrap();
+            assert_eq!(bytes[0], tag);
+            assert_eq!(&SignatureEnvelope::from_bytes(&bytes).unwrap(), envelope);
+            assert!(envelope.verify(pk, &SignInput::Raw(msg.clone())).is_ok());
+            assert_eq!(
+                envelope.verify(pk, &SignInput::Raw(b"another message".to_vec())),
+                Err(ProtocolError::SignatureVerificationFailed)
+            );
+            assert!(matches!(
+                envelope.verify(pk, &SignInput::Prehashed([0u8; 32])),
+                Err(ProtocolError::InvalidInput(_))
+            ));
+        }
+
+        // the key of another scheme is rejected
+        assert!(matches!(
+            eddsa_env.verify(&redjubjub_pk, &SignInput::Raw(msg)),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_envelope_serde() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let (sig, _) = ecdsa_signature(&mut rng, hash_message(b"Hello from Near"));
+        assert_serde_roundtrip(&SignatureEnvelope::from(sig));
+
+        let msg = b"Hello from Near";
+        let eddsa_sk = frost_ed25519::SigningKey::new(&mut rng);
+        let eddsa_sig = eddsa_sk.sign(&mut rng, msg);
+        assert_serde_roundtrip(&eddsa_sig);
+        assert_serde_roundtrip(&SignatureEnvelope::from(eddsa_sig));
+        let redjubjub_sk = redjubjub::SigningKey::new(&mut rng);
+        assert_serde_roundtrip(&SignatureEnvelope::from(redjubjub_sk.sign(&mut rng, msg)));
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_malformed_input() {
+        assert_eq!(
+            SignatureEnvelope::from_bytes(&[]),
+            Err(ProtocolError::MalformedElement)
+        );
+        assert!(matches!(
+            SignatureEnvelope::from_bytes(&[3, 0, 0]),
+            Err(ProtocolError::InvalidInput(_))
+        ));
+        assert_eq!(
+            SignatureEnvelope::from_bytes(&[SignatureEnvelope::ECDSA_TAG; 10]),
+            Err(ProtocolError::MalformedElement)
+        );
+        assert_eq!(
+            SignatureEnvelope::from_bytes(&[SignatureEnvelope::EDDSA_TAG; 10]),
+            Err(ProtocolError::MalformedElement)
+        );
+    }
+}
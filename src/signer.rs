@@ -0,0 +1,582 @@
+//! A signing facade over the supported schemes, for callers choosing the scheme at runtime.
+//!
+//! [`new_signer`] returns a [`ThresholdSigner`] for a scheme and curve pair, whose
+//! [`ThresholdSigner::sign`] takes the same arguments whatever the scheme, and whose
+//! protocol returns a [`SignatureEnvelope`] to the coordinator.
+use reddsa::frost::redjubjub::Randomizer;
+
+use crate::ecdsa::{self, ot_based_ecdsa, robust_ecdsa, MessageHash};
+use crate::errors::{InitializationError, ProtocolError};
+use crate::frost::{eddsa, redjubjub};
+use crate::participants::Participant;
+use crate::protocol::{Action, MessageData, Protocol, RoleOutput};
+use crate::signature::SignatureEnvelope;
+use crate::thresholds::Scheme;
+use crate::SignInput;
+
+/// The curves the signing schemes are instantiated over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveId {
+    Secp256k1,
+    Ed25519,
+    JubJub,
+}
+
+/// The key shares of a participant, tagged with their curve
+#[derive(Debug, Clone)]
+pub enum SignerKey {
+    Secp256k1(ecdsa::KeygenOutput),
+    Ed25519(eddsa::KeygenOutput),
+    JubJub(redjubjub::KeygenOutput),
+}
+
+impl SignerKey {
+    pub const fn curve(&self) -> CurveId {
+        match self {
+            Self::Secp256k1(_) => CurveId::Secp256k1,
+            Self::Ed25519(_) => CurveId::Ed25519,
+            Self::JubJub(_) => CurveId::JubJub,
+        }
+    }
+}
+
+/// The presignature consumed by a signature, tagged with its scheme
+#[derive(Clone)]
+pub enum SignerPresignature {
+    /// A rerandomized robust ECDSA presignature, along with the tweak
+    /// deriving the public key the signature is checked against.
+    RobustEcdsa {
+        presignature: robust_ecdsa::RerandomizedPresignOutput,
+        tweak: ecdsa::Tweak,
+    },
+    /// A rerandomized OT based ECDSA presignature, along with the tweak
+    /// deriving the public key the signature is checked against.
+    OtBasedEcdsa {
+        presignature: ot_based_ecdsa::RerandomizedPresignOutput,
+        tweak: ecdsa::Tweak,
+    },
+    EdDsa(eddsa::PresignOutput),
+    /// A RedJubJub presignature, along with the randomizer which only the
+    /// coordinator provides.
+    RedJubjub {
+        presignature: redjubjub::PresignOutput,
+        randomizer: Option<Randomizer>,
+    },
+}
+
+/// The signing protocol returned by [`ThresholdSigner::sign`]
+pub type SignerProtocol = Box<dyn Protocol<Output = RoleOutput<SignatureEnvelope>>>;
+
+/// Signs with one of the supported schemes, see [`new_signer`].
+pub trait ThresholdSigner: Send + Sync {
+    fn scheme(&self) -> Scheme;
+
+    fn curve(&self) -> CurveId;
+
+    /// Starts the signing protocol of the scheme.
+    ///
+    /// The key and presignature must be of the scheme and curve of the signer.
+    /// ECDSA signatures use the identifier of the presignature and the epoch of the key.
+    fn sign(
+        &self,
+        participants: &[Participant],
+        coordinator: Participant,
+        me: Participant,
+        key: SignerKey,
+        presignature: SignerPresignature,
+        input: SignInput,
+    ) -> Result<SignerProtocol, InitializationError>;
+}
+
+/// Returns the signer of `scheme` over `curve`.
+///
+/// The `threshold` is the one expected by the protocols of the scheme, i.e. the maximum
+/// number of malicious parties for robust ECDSA, and the reconstruction lower bound otherwise.
+pub fn new_signer(
+    scheme: Scheme,
+    curve: CurveId,
+    threshold: usize,
+) -> Result<Box<dyn ThresholdSigner>, InitializationError> {
+    match (scheme, curve) {
+        (Scheme::RobustEcdsa, CurveId::Secp256k1) => Ok(Box::new(RobustEcdsaSigner(threshold))),
+        (Scheme::OtBasedEcdsa, CurveId::Secp256k1) => Ok(Box::new(OtBasedEcdsaSigner(threshold))),
+        (Scheme::Frost, CurveId::Ed25519) => Ok(Box::new(EdDsaSigner(threshold))),
+        (Scheme::Frost, CurveId::JubJub) => Ok(Box::new(RedJubjubSigner(threshold))),
+        _ => Err(InitializationError::BadParameters(format!(
+            "{scheme:?} signing is not supported over {curve:?}"
+        ))),
+    }
+}
+
+struct RobustEcdsaSigner(usize);
+
+struct OtBasedEcdsaSigner(usize);
+
+struct EdDsaSigner(usize);
+
+struct RedJubjubSigner(usize);
+
+impl ThresholdSigner for RobustEcdsaSigner {
+    fn scheme(&self) -> Scheme {
+        Scheme::RobustEcdsa
+    }
+
+    fn curve(&self) -> CurveId {
+        CurveId::Secp256k1
+    }
+
+    fn sign(
+        &self,
+        participants: &[Participant],
+        coordinator: Participant,
+        me: Participant,
+        key: SignerKey,
+        presignature: SignerPresignature,
+        input: SignInput,
+    ) -> Result<SignerProtocol, InitializationError> {
+        let SignerKey::Secp256k1(key) = key else {
+            return Err(key_mismatch(self.curve(), &key));
+        };
+        let SignerPresignature::RobustEcdsa {
+            presignature,
+            tweak,
+        } = presignature
+        else {
+            return Err(presignature_mismatch(self.scheme()));
+        };
+        let public_key = tweak
+            .derive_verifying_key(&key.public_key)
+            .to_element()
+            .to_affine();
+        let presign_id = presignature.id();
+        let protocol = robust_ecdsa::sign::sign(
+            participants,
+            coordinator,
+            self.0,
+            me,
+            public_key,
+            presignature,
+            presign_id,
+            key.epoch,
+            MessageHash::try_from(input)?,
+        )?;
+        Ok(MapOutput::boxed(protocol, from_option))
+    }
+}
+
+impl ThresholdSigner for OtBasedEcdsaSigner {
+    fn scheme(&self) -> Scheme {
+        Scheme::OtBasedEcdsa
+    }
+
+    fn curve(&self) -> CurveId {
+        CurveId::Secp256k1
+    }
+
+    fn sign(
+        &self,
+        participants: &[Participant],
+        coordinator: Participant,
+        me: Participant,
+        key: SignerKey,
+        presignature: SignerPresignature,
+        input: SignInput,
+    ) -> Result<SignerProtocol, InitializationError> {
+        let SignerKey::Secp256k1(key) = key else {
+            return Err(key_mismatch(self.curve(), &key));
+        };
+        let SignerPresignature::OtBasedEcdsa {
+            presignature,
+            tweak,
+        } = presignature
+        else {
+            return Err(presignature_mismatch(self.scheme()));
+        };
+        let public_key = tweak
+            .derive_verifying_key(&key.public_key)
+            .to_element()
+            .to_affine();
+        let presign_id = presignature.id;
+        let protocol = ot_based_ecdsa::sign::sign(
+            participants,
+            coordinator,
+            self.0,
+            me,
+            public_key,
+            presignature,
+            presign_id,
+            key.epoch,
+            MessageHash::try_from(input)?,
+        )?;
+        Ok(MapOutput::boxed(protocol, from_option))
+    }
+}
+
+impl ThresholdSigner for EdDsaSigner {
+    fn scheme(&self) -> Scheme {
+        Scheme::Frost
+    }
+
+    fn curve(&self) -> CurveId {
+        CurveId::Ed25519
+    }
+
+    fn sign(
+        &self,
+        participants: &[Participant],
+        coordinator: Participant,
+        me: Participant,
+        key: SignerKey,
+        presignature: SignerPresignature,
+        input: SignInput,
+    ) -> Result<SignerProtocol, InitializationError> {
+        let SignerKey::Ed25519(key) = key else {
+            return Err(key_mismatch(self.curve(), &key));
+        };
+        let SignerPresignature::EdDsa(presignature) = presignature else {
+            return Err(presignature_mismatch(self.scheme()));
+        };
+        let protocol = eddsa::sign::sign_v2(
+            participants,
+            self.0,
+            me,
+            coordinator,
+            key,
+            None,
+            presignature,
+            input,
+        )?;
+        Ok(MapOutput::boxed(protocol, |output| output.map(Into::into)))
+    }
+}
+
+impl ThresholdSigner for RedJubjubSigner {
+    fn scheme(&self) -> Scheme {
+        Scheme::Frost
+    }
+
+    fn curve(&self) -> CurveId {
+        CurveId::JubJub
+    }
+
+    fn sign(
+        &self,
+        participants: &[Participant],
+        coordinator: Participant,
+        me: Participant,
+        key: SignerKey,
+        presignature: SignerPresignature,
+        input: SignInput,
+    ) -> Result<SignerProtocol, InitializationError> {
+        let SignerKey::JubJub(key) = key else {
+            return Err(key_mismatch(self.curve(), &key));
+        };
+        let SignerPresignature::RedJubjub {
+            presignature,
+            randomizer,
+        } = presignature
+        else {
+            return Err(presignature_mismatch(self.scheme()));
+        };
+        let protocol = redjubjub::sign::sign(
+            participants,
+            self.0,
+            me,
+            coordinator,
+            key,
+            None,
+            presignature,
+            input,
+            randomizer,
+        )?;
+        Ok(MapOutput::boxed(protocol, |output| output.map(Into::into)))
+    }
+}
+
+fn key_mismatch(expected: CurveId, key: &SignerKey) -> InitializationError {
+    InitializationError::BadParameters(format!(
+        "expected a {expected:?} key, got a {:?} key",
+        key.curve()
+    ))
+}
+
+fn presignature_mismatch(scheme: Scheme) -> InitializationError {
+    InitializationError::BadParameters(format!("expected a {scheme:?} presignature"))
+}
+
+fn from_option<S: Into<SignatureEnvelope>>(signature: Option<S>) -> RoleOutput<SignatureEnvelope> {
+    signature.map_or(RoleOutput::Participant, |signature| {
+        RoleOutput::Coordinator(signature.into())
+    })
+}
+
+/// Wraps a signing protocol, converting its output once it returns
+struct MapOutput<P: Protocol> {
+    protocol: P,
+    map: fn(P::Output) -> RoleOutput<SignatureEnvelope>,
+}
+
+impl<P: Protocol + 'static> MapOutput<P> {
+    fn boxed(protocol: P, map: fn(P::Output) -> RoleOutput<SignatureEnvelope>) -> SignerProtocol {
+        Box::new(Self { protocol, map })
+    }
+}
+
+impl<P: Protocol> Protocol for MapOutput<P> {
+    type Output = RoleOutput<SignatureEnvelope>;
+
+    fn poke(&mut self) -> Result<Action<Self::Output>, ProtocolError> {
+        Ok(match self.protocol.poke()? {
+            Action::Wait => Action::Wait,
+            Action::SendMany(data) => Action::SendMany(data),
+            Action::SendPrivate(to, data) => Action::SendPrivate(to, data),
+            Action::Return(output) => Action::Return((self.map)(output)),
+        })
+    }
+
+    fn message(&mut self, from: Participant, data: MessageData) {
+        self.protocol.message(from, data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecdsa::ot_based_ecdsa::triples::test::deal;
+    use crate::frost::redjubjub::JubjubBlake2b512;
+    use crate::signature::SchemePublicKey;
+    use crate::test_utils::{
+        ecdsa_generate_rerandpresig_args, frost_run_presignature, generate_participants,
+        one_coordinator_output, run_keygen, run_protocol, GenProtocol, MockCryptoRng,
+    };
+    use frost_core::Field;
+    use frost_ed25519::Ed25519Sha512;
+    use rand::SeedableRng;
+    use rand_core::RngCore;
+    use reddsa::frost::redjubjub::{JubjubScalarField, RandomizedParams};
+
+    /// Runs `signer` with the key and presignature of each participant,
+    /// returning the signature of the coordinator
+    fn run_signer(
+        signer: &dyn ThresholdSigner,
+        participants: &[Participant],
+        coordinator: Participant,
+        inputs: Vec<(Participant, SignerKey, SignerPresignature)>,
+        input: &SignInput,
+    ) -> SignatureEnvelope {
+        let mut protocols: GenProtocol<RoleOutput<SignatureEnvelope>> = Vec::new();
+        for (p, key, presignature) in inputs {
+            let protocol = signer
+                .sign(
+                    participants,
+                    coordinator,
+                    p,
+                    key,
+                    presignature,
+                    input.clone(),
+                )
+                .unwrap();
+            protocols.push((p, protocol));
+        }
+        one_coordinator_output(run_protocol(protocols).unwrap(), coordinator).unwrap()
+    }
+
+    #[test]
+    fn test_new_signer_rejects_unsupported_pairs() {
+        for (scheme, curve) in [
+            (Scheme::Dkg, CurveId::Secp256k1),
+            (Scheme::RobustEcdsa, CurveId::Ed25519),
+            (Scheme::OtBasedEcdsa, CurveId::JubJub),
+            (Scheme::Frost, CurveId::Secp256k1),
+        ] {
+            assert!(new_signer(scheme, curve, 2).is_err());
+        }
+        let signer = new_signer(Scheme::Frost, CurveId::JubJub, 2).unwrap();
+        assert_eq!(signer.scheme(), Scheme::Frost);
+        assert_eq!(signer.curve(), CurveId::JubJub);
+    }
+
+    #[test]
+    fn test_eddsa_signer() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold = 2;
+        let keys = run_keygen::<Ed25519Sha512, _>(&participants, threshold, &mut rng);
+        let presignatures = frost_run_presignature(
+            &keys,
+            threshold,
+            participants.len(),
+            MockCryptoRng::seed_from_u64(rng.next_u64()),
+        )
+        .unwrap();
+        let signer = new_signer(Scheme::Frost, CurveId::Ed25519, threshold).unwrap();
+        let coordinator = participants[0];
+        let input = SignInput::Raw(b"hello world".to_vec());
+
+        // a key over another curve is rejected
+        let ecdsa_key = run_keygen::<ecdsa::Secp256K1Sha256, _>(&participants, threshold, &mut rng)
+            .swap_remove(0)
+            .1;
+        assert!(signer
+            .sign(
+                &participants,
+                coordinator,
+                participants[0],
+                SignerKey::Secp256k1(ecdsa_key),
+                SignerPresignature::EdDsa(presignatures[0].1.clone()),
+                input.clone(),
+            )
+            .is_err());
+
+        let inputs = keys
+            .iter()
+            .zip(presignatures)
+            .map(|((p, key), (_, presignature))| {
+                (
+                    *p,
+                    SignerKey::Ed25519(key.clone()),
+                    SignerPresignature::EdDsa(presignature),
+                )
+            })
+            .collect();
+        let signature = run_signer(signer.as_ref(), &participants, coordinator, inputs, &input);
+        let pk = SchemePublicKey::EdDsa(keys[0].1.public_key);
+        signature.verify(&pk, &input).unwrap();
+    }
+
+    #[test]
+    fn test_robust_ecdsa_signer() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let max_malicious = 1;
+        let keys =
+            run_keygen::<ecdsa::Secp256K1Sha256, _>(&participants, max_malicious + 1, &mut rng);
+        let public_key = keys[0].1.public_key;
+        let presignatures = robust_ecdsa::test::run_presign(keys.clone(), max_malicious, &mut rng);
+        let (args, msg_hash) = ecdsa_generate_rerandpresig_args(
+            &mut rng,
+            &participants,
+            public_key,
+            presignatures[0].1.big_r,
+        );
+        let signer = new_signer(Scheme::RobustEcdsa, CurveId::Secp256k1, max_malicious).unwrap();
+        let coordinator = participants[1];
+        let input = SignInput::Prehashed(msg_hash.to_bytes().into());
+
+        let inputs = keys
+            .iter()
+            .zip(&presignatures)
+            .map(|((p, key), (_, presignature))| {
+                let presignature = robust_ecdsa::RerandomizedPresignOutput::rerandomize_presign(
+                    presignature,
+                    &args,
+                )
+                .unwrap();
+                (
+                    *p,
+                    SignerKey::Secp256k1(key.clone()),
+                    SignerPresignature::RobustEcdsa {
+                        presignature,
+                        tweak: args.tweak,
+                    },
+                )
+            })
+            .collect();
+        let signature = run_signer(signer.as_ref(), &participants, coordinator, inputs, &input);
+        let derived_pk = args.tweak.derive_verifying_key(&public_key);
+        let pk = SchemePublicKey::Ecdsa(derived_pk.to_element().to_affine());
+        signature.verify(&pk, &input).unwrap();
+    }
+
+    #[test]
+    fn test_ot_based_ecdsa_signer() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold = 2;
+        let keys = run_keygen::<ecdsa::Secp256K1Sha256, _>(&participants, threshold, &mut rng);
+        let public_key = keys[0].1.public_key;
+        let (pub0, shares0) = deal(&mut rng, &participants, threshold.into()).unwrap();
+        let (pub1, shares1) = deal(&mut rng, &participants, threshold.into()).unwrap();
+        let presignatures = ot_based_ecdsa::test::run_presign(
+            keys.clone(),
+            shares0,
+            shares1,
+            &pub0,
+            &pub1,
+            threshold.into(),
+        );
+        let (args, msg_hash) = ecdsa_generate_rerandpresig_args(
+            &mut rng,
+            &participants,
+            public_key,
+            presignatures[0].1.big_r,
+        );
+        let signer = new_signer(Scheme::OtBasedEcdsa, CurveId::Secp256k1, threshold).unwrap();
+        let coordinator = participants[2];
+        let input = SignInput::Prehashed(msg_hash.to_bytes().into());
+
+        let inputs = keys
+            .iter()
+            .zip(&presignatures)
+            .map(|((p, key), (_, presignature))| {
+                let presignature = ot_based_ecdsa::RerandomizedPresignOutput::rerandomize_presign(
+                    presignature,
+                    &args,
+                )
+                .unwrap();
+                (
+                    *p,
+                    SignerKey::Secp256k1(key.clone()),
+                    SignerPresignature::OtBasedEcdsa {
+                        presignature,
+                        tweak: args.tweak,
+                    },
+                )
+            })
+            .collect();
+        let signature = run_signer(signer.as_ref(), &participants, coordinator, inputs, &input);
+        let derived_pk = args.tweak.derive_verifying_key(&public_key);
+        let pk = SchemePublicKey::Ecdsa(derived_pk.to_element().to_affine());
+        signature.verify(&pk, &input).unwrap();
+    }
+
+    #[test]
+    fn test_redjubjub_signer() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let threshold = 2;
+        let keys = run_keygen::<JubjubBlake2b512, _>(&participants, threshold, &mut rng);
+        let presignatures = frost_run_presignature(
+            &keys,
+            threshold,
+            participants.len(),
+            MockCryptoRng::seed_from_u64(rng.next_u64()),
+        )
+        .unwrap();
+        let randomizer = Randomizer::from_scalar(JubjubScalarField::random(&mut rng));
+        let signer = new_signer(Scheme::Frost, CurveId::JubJub, threshold).unwrap();
+        let coordinator = participants[0];
+        let input = SignInput::Raw(b"hello world".to_vec());
+
+        // only the coordinator provides the randomizer
+        let inputs = keys
+            .iter()
+            .zip(presignatures)
+            .map(|((p, key), (_, presignature))| {
+                (
+                    *p,
+                    SignerKey::JubJub(key.clone()),
+                    SignerPresignature::RedJubjub {
+                        presignature,
+                        randomizer: (*p == coordinator).then_some(randomizer),
+                    },
+                )
+            })
+            .collect();
+        let signature = run_signer(signer.as_ref(), &participants, coordinator, inputs, &input);
+        let randomized_params =
+            RandomizedParams::from_randomizer(&keys[0].1.public_key, randomizer);
+        let pk = SchemePublicKey::RedJubjub(*randomized_params.randomized_verifying_key());
+        signature.verify(&pk, &input).unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::{poll_fn, Future};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+use futures::executor::block_on;
+use futures::future::try_join_all;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::ProtocolError;
+use crate::participants::Participant;
+use crate::protocol::{Channel, MessageData, Waitpoint};
+
+/// The messages in flight, indexed by recipient and waitpoint.
+#[derive(Default)]
+struct Network {
+    inboxes: HashMap<(Participant, Waitpoint), VecDeque<(Participant, MessageData)>>,
+    wakers: Vec<Waker>,
+}
+
+/// A [`Channel`] passing the messages of a participant through shared memory,
+/// independently of the [`Protocol`](crate::protocol::Protocol) driver.
+///
+/// It stands for the transport of an integrator running the cores exposed with
+/// the `custom-executor` feature on their own executor.
+pub struct MemoryChannel {
+    me: Participant,
+    participants: Vec<Participant>,
+    waitpoint: Waitpoint,
+    network: Arc<Mutex<Network>>,
+}
+
+impl MemoryChannel {
+    /// Connects the participants together, returning the channel of each of them.
+    pub fn connect(participants: &[Participant]) -> Vec<(Participant, Self)> {
+        let network = Arc::new(Mutex::new(Network::default()));
+        participants
+            .iter()
+            .map(|me| {
+                let chan = Self {
+                    me: *me,
+                    participants: participants.to_vec(),
+                    waitpoint: 0,
+                    network: network.clone(),
+                };
+                (*me, chan)
+            })
+            .collect()
+    }
+
+    fn deliver(&self, to: Participant, waitpoint: Waitpoint, data: MessageData) {
+        let mut network = self.network.lock().unwrap();
+        network
+            .inboxes
+            .entry((to, waitpoint))
+            .or_default()
+            .push_back((self.me, data));
+        for waker in network.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+fn encode<T: Serialize>(data: &T) -> Result<MessageData, ProtocolError> {
+    rmp_serde::encode::to_vec(data).map_err(|_| ProtocolError::ErrorEncoding)
+}
+
+impl Channel for MemoryChannel {
+    fn next_waitpoint(&mut self) -> Waitpoint {
+        let waitpoint = self.waitpoint;
+        self.waitpoint += 1;
+        waitpoint
+    }
+
+    fn send_many<T: Serialize>(&self, waitpoint: Waitpoint, data: &T) -> Result<(), ProtocolError> {
+        let data = encode(data)?;
+        for p in self.participants.iter().filter(|p| **p != self.me) {
+            self.deliver(*p, waitpoint, data.clone());
+        }
+        Ok(())
+    }
+
+    fn send_private<T: Serialize>(
+        &self,
+        waitpoint: Waitpoint,
+        to: Participant,
+        data: &T,
+    ) -> Result<(), ProtocolError> {
+        self.deliver(to, waitpoint, encode(data)?);
+        Ok(())
+    }
+
+    fn recv<T: DeserializeOwned>(
+        &self,
+        waitpoint: Waitpoint,
+    ) -> impl Future<Output = Result<(Participant, T), ProtocolError>> + Send {
+        let network = self.network.clone();
+        let me = self.me;
+        async move {
+            let (from, data) = poll_fn(|cx| {
+                let mut network = network.lock().unwrap();
+                if let Some(message) = network
+                    .inboxes
+                    .get_mut(&(me, waitpoint))
+                    .and_then(VecDeque::pop_front)
+                {
+                    return Poll::Ready(message);
+                }
+                network.wakers.push(cx.waker().clone());
+                Poll::Pending
+            })
+            .await;
+            let decoded = rmp_serde::decode::from_slice(&data)
+                .map_err(|_| ProtocolError::MalformedMessage { from, waitpoint })?;
+            Ok((from, decoded))
+        }
+    }
+}
+
+/// Awaits the protocol futures of every participant together on a single thread,
+/// e.g. the ones built over [`MemoryChannel`]s, stopping at the first error.
+pub fn run_futures<T, F>(
+    futures: Vec<(Participant, F)>,
+) -> Result<Vec<(Participant, T)>, ProtocolError>
+where
+    F: Future<Output = Result<T, ProtocolError>>,
+{
+    let (participants, futures): (Vec<_>, Vec<_>) = futures.into_iter().unzip();
+    let outputs = block_on(try_join_all(futures))?;
+    Ok(participants.into_iter().zip(outputs).collect())
+}
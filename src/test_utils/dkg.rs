@@ -1,10 +1,16 @@
+use frost_core::Group;
 use rand::SeedableRng;
 use rand_core::CryptoRngCore;
 
+use crate::crypto::polynomials::Polynomial;
+use crate::dkg::assert_key_invariants;
 use crate::participants::Participant;
-use crate::test_utils::{run_protocol, GenOutput, GenProtocol};
+use crate::test_utils::{run_protocol, GenOutput, GenProtocol, MockCryptoRng};
 use crate::thresholds::ReconstructionLowerBound;
-use crate::{keygen, refresh, reshare, Ciphersuite, Element, KeygenOutput, Scalar, VerifyingKey};
+use crate::{
+    keygen, refresh, reshare, Ciphersuite, Element, KeygenOutput, Scalar, SigningShare,
+    VerifyingKey,
+};
 
 // +++++++++++++++++ DKG Functions +++++++++++++++++ //
 type DKGGenProtocol<C> = GenProtocol<KeygenOutput<C>>;
@@ -33,6 +39,39 @@ where
     run_protocol(protocols).unwrap()
 }
 
+impl<C: Ciphersuite> KeygenOutput<C> {
+    /// Deterministically deals the key pair of `me` without running DKG,
+    /// and returns it together with the group key.
+    ///
+    /// A polynomial of degree `threshold - 1` is sampled from `seed`, so calling
+    /// this for every participant with the same arguments yields a consistent
+    /// sharing of a single key. The dealer knows the whole key: only use it in tests.
+    ///
+    /// # Panics
+    ///
+    /// If the arguments break the invariants checked by [`keygen`], e.g. `me` is
+    /// not a participant or the threshold exceeds the number of participants.
+    pub fn from_seed(
+        seed: &[u8; 32],
+        me: Participant,
+        participants: &[Participant],
+        threshold: impl Into<ReconstructionLowerBound>,
+    ) -> (Self, VerifyingKey<C>) {
+        let threshold = usize::from(threshold.into());
+        assert_key_invariants(participants, me, threshold).unwrap();
+        let mut rng = MockCryptoRng::from_seed(*seed);
+        let f = Polynomial::<C>::generate_polynomial(None, threshold - 1, &mut rng).unwrap();
+        let private_share = f.eval_at_participant(me).unwrap();
+        let public_key = VerifyingKey::new(C::Group::generator() * f.eval_at_zero().unwrap().0);
+        let keygen_output = Self {
+            private_share: SigningShare::new(private_share.0),
+            public_key,
+            epoch: 0,
+        };
+        (keygen_output, public_key)
+    }
+}
+
 /// Runs distributed refresh
 /// If the protocol succeeds, returns a sorted vector based on participants id
 pub fn run_refresh<C: Ciphersuite, R: CryptoRngCore + SeedableRng + Send + 'static>(
@@ -52,6 +91,7 @@ where
         let protocol = refresh::<C>(
             Some(out.private_share),
             out.public_key,
+            out.epoch,
             participants,
             threshold,
             *p,
@@ -96,6 +136,8 @@ where
         }
     }
 
+    // every old share is in the same epoch
+    let old_epoch = keys.first().map_or(0, |(_, k)| k.epoch);
     let mut protocols: DKGGenProtocol<C> = Vec::with_capacity(participants.len());
 
     for (p, out) in &setup {
@@ -105,6 +147,7 @@ where
             old_threshold,
             out.0,
             out.1,
+            old_epoch,
             new_participants,
             new_threshold,
             *p,
@@ -130,3 +173,64 @@ pub fn assert_public_key_invariant<C: Ciphersuite>(
         panic!("public key package is not the same for all participants");
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::participants::ParticipantList;
+    use crate::test_utils::generate_participants;
+    use frost_secp256k1::Secp256K1Sha256;
+    use subtle::ConstantTimeEq;
+
+    type C = Secp256K1Sha256;
+
+    #[test]
+    fn test_keygen_output_from_seed() {
+        let participants = generate_participants(5);
+        let threshold = 3usize;
+        let seed = [7u8; 32];
+        let keys = participants
+            .iter()
+            .map(|p| {
+                let (key, group_key) =
+                    KeygenOutput::<C>::from_seed(&seed, *p, &participants, threshold);
+                assert_eq!(group_key, key.public_key);
+                (*p, key)
+            })
+            .collect::<Vec<_>>();
+        assert_public_key_invariant(&keys);
+
+        // deterministic in the seed
+        let (again, _) =
+            KeygenOutput::<C>::from_seed(&seed, participants[0], &participants, threshold);
+        assert!(bool::from(again.ct_eq(&keys[0].1)));
+        let (_, other) =
+            KeygenOutput::<C>::from_seed(&[8u8; 32], participants[0], &participants, threshold);
+        assert_ne!(other, keys[0].1.public_key);
+
+        // any threshold of the shares reconstructs the group key
+        let signers = ParticipantList::new(&participants[1..=threshold]).unwrap();
+        let secret = keys[1..=threshold]
+            .iter()
+            .map(|(p, key)| signers.lagrange::<C>(*p).unwrap() * key.private_share.to_scalar())
+            .sum::<Scalar<C>>();
+        assert_eq!(
+            <C as frost_core::Ciphersuite>::Group::generator() * secret,
+            keys[0].1.public_key.to_element()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ThresholdTooLarge")]
+    fn test_keygen_output_from_seed_rejects_large_threshold() {
+        let participants = generate_participants(5);
+        KeygenOutput::<C>::from_seed(&[7u8; 32], participants[0], &participants, 6usize);
+    }
+
+    #[test]
+    #[should_panic(expected = "MissingParticipant")]
+    fn test_keygen_output_from_seed_rejects_outsider() {
+        let participants = generate_participants(5);
+        KeygenOutput::<C>::from_seed(&[7u8; 32], Participant::from(99u32), &participants, 3usize);
+    }
+}
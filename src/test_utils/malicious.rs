@@ -0,0 +1,206 @@
+use crate::errors::ProtocolError;
+use crate::participants::Participant;
+use crate::protocol::internal::bump_message_waitpoint;
+use crate::protocol::{Action, MessageData, Protocol};
+use crate::test_utils::GenProtocol;
+
+/// How a [`MaliciousDriver`] tampers with the outgoing messages of its protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TamperPolicy {
+    /// Flips the lowest bit of the last byte, corrupting the payload but not its routing
+    FlipBytes,
+    /// Drops the message
+    DropMessage,
+    /// Delivers the message on the next waitpoint of its channel
+    WrongWaitpoint,
+}
+
+/// Wraps the protocol of a participant and tampers with its outgoing messages,
+/// to check how the honest participants react to a misbehaving one.
+pub struct MaliciousDriver<T> {
+    protocol: Box<dyn Protocol<Output = T>>,
+    policy: TamperPolicy,
+    private_only: bool,
+}
+
+impl<T> MaliciousDriver<T> {
+    /// Tampers with every `SendMany` and `SendPrivate` of `protocol` following `policy`
+    pub fn new(protocol: Box<dyn Protocol<Output = T>>, policy: TamperPolicy) -> Self {
+        Self {
+            protocol,
+            policy,
+            private_only: false,
+        }
+    }
+
+    /// Restricts the tampering to the `SendPrivate` messages
+    #[must_use]
+    pub const fn private_only(mut self) -> Self {
+        self.private_only = true;
+        self
+    }
+
+    /// Returns the tampered message, or `None` if it is dropped
+    fn tamper(&self, mut data: MessageData) -> Option<MessageData> {
+        match self.policy {
+            TamperPolicy::FlipBytes => {
+                if let Some(last) = data.last_mut() {
+                    *last ^= 1;
+                }
+                Some(data)
+            }
+            TamperPolicy::DropMessage => None,
+            TamperPolicy::WrongWaitpoint => {
+                bump_message_waitpoint(&mut data);
+                Some(data)
+            }
+        }
+    }
+}
+
+impl<T> Protocol for MaliciousDriver<T> {
+    type Output = T;
+
+    fn poke(&mut self) -> Result<Action<T>, ProtocolError> {
+        loop {
+            let action = match self.protocol.poke()? {
+                Action::SendMany(data) if !self.private_only => {
+                    self.tamper(data).map(Action::SendMany)
+                }
+                Action::SendPrivate(to, data) => {
+                    self.tamper(data).map(|data| Action::SendPrivate(to, data))
+                }
+                action => Some(action),
+            };
+            // a dropped message is skipped in favour of the next action
+            if let Some(action) = action {
+                return Ok(action);
+            }
+        }
+    }
+
+    fn message(&mut self, from: Participant, data: MessageData) {
+        self.protocol.message(from, data);
+    }
+}
+
+/// Replaces the protocol of `malicious` by the driver built with `corrupt`
+pub fn corrupt_participant<T: 'static>(
+    protocols: GenProtocol<T>,
+    malicious: Participant,
+    corrupt: impl FnOnce(Box<dyn Protocol<Output = T>>) -> MaliciousDriver<T>,
+) -> GenProtocol<T> {
+    let mut corrupt = Some(corrupt);
+    protocols
+        .into_iter()
+        .map(|(p, protocol)| match corrupt.take_if(|_| p == malicious) {
+            Some(corrupt) => (
+                p,
+                Box::new(corrupt(protocol)) as Box<dyn Protocol<Output = T>>,
+            ),
+            None => (p, protocol),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecdsa::robust_ecdsa::{presign::presign, PresignArguments, PresignOutput};
+    use crate::errors::ErrorCategory;
+    use crate::test_utils::{generate_participants, run_keygen, run_protocol, MockCryptoRng};
+    use crate::{keygen, KeygenOutput};
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::{RngCore, SeedableRng};
+
+    type C = Secp256K1Sha256;
+
+    fn keygen_protocols(
+        participants: &[Participant],
+        rng: &mut MockCryptoRng,
+    ) -> GenProtocol<KeygenOutput<C>> {
+        participants
+            .iter()
+            .map(|p| {
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let protocol = keygen::<C>(participants, *p, 2, rng_p).unwrap();
+                (
+                    *p,
+                    Box::new(protocol) as Box<dyn Protocol<Output = KeygenOutput<C>>>,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_keygen_flipped_share_is_attributed() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(4);
+        let malicious = participants[2];
+        let protocols = corrupt_participant(
+            keygen_protocols(&participants, &mut rng),
+            malicious,
+            |protocol| MaliciousDriver::new(protocol, TamperPolicy::FlipBytes).private_only(),
+        );
+        let err = run_protocol(protocols).unwrap_err();
+        assert_eq!(err, ProtocolError::InvalidSecretShare(malicious));
+        assert_eq!(err.category(), ErrorCategory::Attributable(malicious));
+    }
+
+    #[test]
+    fn test_keygen_dropped_messages_stall() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let protocols = corrupt_participant(
+            keygen_protocols(&participants, &mut rng),
+            participants[0],
+            |protocol| MaliciousDriver::new(protocol, TamperPolicy::DropMessage),
+        );
+        let err = run_protocol(protocols).unwrap_err();
+        assert!(err.to_string().contains("protocol is stuck"));
+    }
+
+    #[test]
+    fn test_keygen_wrong_waitpoint_fails() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(3);
+        let protocols = corrupt_participant(
+            keygen_protocols(&participants, &mut rng),
+            participants[1],
+            |protocol| MaliciousDriver::new(protocol, TamperPolicy::WrongWaitpoint),
+        );
+        assert!(run_protocol(protocols).is_err());
+    }
+
+    #[test]
+    fn test_robust_presign_flipped_share_fails() {
+        let mut rng = MockCryptoRng::seed_from_u64(42);
+        let participants = generate_participants(5);
+        let keys = run_keygen::<C, _>(&participants, 3usize, &mut rng);
+        let protocols: GenProtocol<PresignOutput> = keys
+            .into_iter()
+            .map(|(p, keygen_out)| {
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let protocol = presign(
+                    &participants,
+                    p,
+                    PresignArguments {
+                        keygen_out,
+                        max_malicious: 2usize.into(),
+                        active_security: true,
+                    },
+                    rng_p,
+                )
+                .unwrap();
+                (
+                    p,
+                    Box::new(protocol) as Box<dyn Protocol<Output = PresignOutput>>,
+                )
+            })
+            .collect();
+        let protocols = corrupt_participant(protocols, participants[3], |protocol| {
+            MaliciousDriver::new(protocol, TamperPolicy::FlipBytes)
+        });
+        assert!(run_protocol(protocols).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::participants::Participant;
+
+/// Communication metrics collected while running a protocol
+/// with [`run_protocol_instrumented`](crate::test_utils::run_protocol_instrumented)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProtocolMetrics {
+    /// Total number of times the participants were poked
+    pub pokes: usize,
+    /// Number of `SendMany` actions
+    pub send_many: usize,
+    /// Number of `SendPrivate` actions
+    pub send_private: usize,
+    /// Bytes sent by each participant, a `SendMany` counting once per recipient
+    pub bytes_sent: BTreeMap<Participant, usize>,
+    /// Bytes received by each participant
+    pub bytes_received: BTreeMap<Participant, usize>,
+}
+
+impl ProtocolMetrics {
+    pub(crate) fn new(participants: &[Participant]) -> Self {
+        let zeros = participants
+            .iter()
+            .map(|p| (*p, 0))
+            .collect::<BTreeMap<_, _>>();
+        Self {
+            bytes_sent: zeros.clone(),
+            bytes_received: zeros,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn record_poke(&mut self) {
+        self.pokes += 1;
+    }
+
+    pub(crate) fn record_send_many(&mut self) {
+        self.send_many += 1;
+    }
+
+    pub(crate) fn record_send_private(&mut self) {
+        self.send_private += 1;
+    }
+
+    /// Records the delivery of a message of `size` bytes
+    pub(crate) fn record_delivery(&mut self, from: Participant, to: Participant, size: usize) {
+        *self.bytes_sent.entry(from).or_default() += size;
+        *self.bytes_received.entry(to).or_default() += size;
+    }
+
+    /// Total number of bytes exchanged during the protocol
+    pub fn total_bytes(&self) -> usize {
+        self.bytes_sent.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Protocol;
+    use crate::test_utils::{
+        generate_participants, run_protocol, run_protocol_instrumented, GenProtocol, MockCryptoRng,
+    };
+    use crate::{keygen, KeygenOutput};
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::{RngCore, SeedableRng};
+
+    fn keygen_protocols(
+        participants: &[Participant],
+        rng: &mut MockCryptoRng,
+    ) -> GenProtocol<KeygenOutput<Secp256K1Sha256>> {
+        participants
+            .iter()
+            .map(|p| {
+                let rng_p = MockCryptoRng::seed_from_u64(rng.next_u64());
+                let protocol = keygen::<Secp256K1Sha256>(participants, *p, 2, rng_p).unwrap();
+                (
+                    *p,
+                    Box::new(protocol) as Box<dyn Protocol<Output = KeygenOutput<Secp256K1Sha256>>>,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_protocol_instrumented() {
+        let participants = generate_participants(3);
+        let (outputs, metrics) = run_protocol_instrumented(keygen_protocols(
+            &participants,
+            &mut MockCryptoRng::seed_from_u64(42),
+        ))
+        .unwrap();
+
+        // instrumentation does not change the outputs
+        let expected = run_protocol(keygen_protocols(
+            &participants,
+            &mut MockCryptoRng::seed_from_u64(42),
+        ))
+        .unwrap();
+        assert_eq!(outputs, expected);
+
+        assert!(metrics.send_many > 0);
+        assert!(metrics.send_private > 0);
+        assert!(metrics.pokes >= metrics.send_many + metrics.send_private);
+        assert_eq!(metrics.bytes_sent.len(), participants.len());
+        assert_eq!(
+            metrics.total_bytes(),
+            metrics.bytes_received.values().sum::<usize>()
+        );
+        assert!(metrics.bytes_sent.values().all(|bytes| *bytes > 0));
+    }
+}
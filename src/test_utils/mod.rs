@@ -6,7 +6,10 @@
     clippy::indexing_slicing
 )]
 
+mod channel;
 mod dkg;
+mod malicious;
+mod metrics;
 mod mockrng;
 mod participant_simulation;
 mod participants;
@@ -21,6 +24,7 @@ use crate::participants::Participant;
 use crate::protocol::Protocol;
 use crate::KeygenOutput;
 use rand_core::CryptoRngCore;
+use serde::{de::DeserializeOwned, Serialize};
 
 /// Type representing DKG output keys
 pub type GenOutput<C> = Vec<(Participant, KeygenOutput<C>)>;
@@ -29,23 +33,30 @@ pub type GenProtocol<C> = Vec<(Participant, Box<dyn Protocol<Output = C>>)>;
 /// Type for a deterministic RNG
 pub use mockrng::MockCryptoRng;
 
+pub use channel::{run_futures, MemoryChannel};
 pub use dkg::{assert_public_key_invariant, run_keygen, run_refresh, run_reshare};
+pub use malicious::{corrupt_participant, MaliciousDriver, TamperPolicy};
+pub use metrics::ProtocolMetrics;
 pub use participant_simulation::Simulator;
 pub use participants::{generate_participants, generate_participants_with_random_ids};
-pub use presign::{ecdsa_generate_rerandpresig_args, frost_run_presignature};
+pub use presign::{
+    check_rerandomize_presign_many, ecdsa_generate_rerandpresig_args, frost_run_presignature,
+};
 pub use protocol::{
-    run_protocol, run_protocol_and_take_snapshots, run_simulated_protocol, run_two_party_protocol,
+    run_protocol, run_protocol_and_take_snapshots, run_protocol_instrumented,
+    run_simulated_protocol, run_simulated_protocol_timed, run_single_participant_replay,
+    run_two_party_protocol,
 };
-pub use sign::{check_one_coordinator_output, run_sign};
+pub use sign::{check_one_coordinator_output, run_sign, CoordinatorOutput};
 pub use snapshot::ProtocolSnapshot;
 pub use test_generators::*;
 
 /// Checks that the list contains all None but one element
 /// and verifies such element belongs to the coordinator
-pub fn one_coordinator_output<ProtocolOutput: Clone>(
-    all_sigs: Vec<(Participant, Option<ProtocolOutput>)>,
+pub fn one_coordinator_output<O: CoordinatorOutput>(
+    all_sigs: Vec<(Participant, O)>,
     coordinator: Participant,
-) -> Result<ProtocolOutput, ProtocolError> {
+) -> Result<O::Output, ProtocolError> {
     check_one_coordinator_output(all_sigs, coordinator)
 }
 
@@ -55,6 +66,18 @@ pub fn random_32_bytes(rng: &mut impl CryptoRngCore) -> [u8; 32] {
     bytes
 }
 
+/// Checks that a value is unchanged by a serialization round trip in JSON,
+/// and in MessagePack as used on the wire.
+pub fn assert_serde_roundtrip<T: Serialize + DeserializeOwned + PartialEq>(v: &T) {
+    let json = serde_json::to_string(v).unwrap();
+    let decoded: T = serde_json::from_str(&json).unwrap();
+    assert!(decoded == *v, "JSON round trip changed the value {json}");
+
+    let msgpack = rmp_serde::encode::to_vec(v).unwrap();
+    let decoded: T = rmp_serde::decode::from_slice(&msgpack).unwrap();
+    assert!(decoded == *v, "MessagePack round trip changed the value");
+}
+
 // Taken from https://github.com/ZcashFoundation/frost/blob/3ffc19d8f473d5bc4e07ed41bc884bdb42d6c29f/frost-secp256k1/tests/common_traits_tests.rs#L9
 #[allow(clippy::unnecessary_literal_unwrap)]
 pub fn check_common_traits_for_type<T: Clone + Eq + PartialEq + std::fmt::Debug>(v: &T) {
@@ -1,4 +1,7 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::participants::Participant;
+use crate::protocol::internal::message_header;
 use crate::protocol::MessageData;
 use crate::test_utils::snapshot::ProtocolSnapshot;
 
@@ -33,4 +36,82 @@ impl Simulator {
     pub fn get_view_size(&self) -> usize {
         self.view.iter().map(|(_, s)| s.len()).sum()
     }
+
+    /// Returns the number of communication rounds seen by the real participant,
+    /// i.e. the largest number of distinct waitpoints any single sender reached it on.
+    ///
+    /// Waitpoints of sub-channels running in parallel are counted separately,
+    /// so this is an upper bound for protocols spawning such sub-channels.
+    pub fn round_count(&self) -> usize {
+        let mut waitpoints: HashMap<Participant, HashSet<&[u8]>> = HashMap::new();
+        for (from, message) in &self.view {
+            if let Some(header) = message_header(message) {
+                waitpoints.entry(*from).or_default().insert(header);
+            }
+        }
+        waitpoints.values().map(HashSet::len).max().unwrap_or(0)
+    }
+
+    /// Returns the number of messages `from` sent to the real participant
+    /// on each of its waitpoints, in the order they were first reached.
+    pub fn messages_per_waitpoint(&self, from: Participant) -> Vec<usize> {
+        let mut counts: Vec<(&[u8], usize)> = Vec::new();
+        for (sender, message) in &self.view {
+            let Some(header) = message_header(message).filter(|_| *sender == from) else {
+                continue;
+            };
+            match counts.iter_mut().find(|(seen, _)| *seen == header) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((header, 1)),
+            }
+        }
+        counts.into_iter().map(|(_, count)| count).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Simulator;
+    use crate::errors::ProtocolError;
+    use crate::participants::{Participant, ParticipantList};
+    use crate::protocol::helpers::recv_from_others;
+    use crate::protocol::internal::{make_protocol, Comms};
+    use crate::protocol::Protocol;
+    use crate::test_utils::{generate_participants, run_protocol_and_take_snapshots};
+
+    const ROUNDS: usize = 3;
+
+    async fn do_rounds(
+        comms: Comms,
+        participants: ParticipantList,
+        me: Participant,
+    ) -> Result<(), ProtocolError> {
+        let mut chan = comms.shared_channel();
+        for round in 0..ROUNDS {
+            let wait = chan.next_waitpoint();
+            chan.send_many(wait, &round)?;
+            recv_from_others::<usize>(&chan, wait, &participants, me).await?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_count() {
+        let participants = generate_participants(4);
+        let mut protocols: Vec<(Participant, Box<dyn Protocol<Output = ()>>)> =
+            Vec::with_capacity(participants.len());
+        for p in &participants {
+            let comms = Comms::new();
+            let list = ParticipantList::new(&participants).unwrap();
+            let fut = do_rounds(comms.clone(), list, *p);
+            protocols.push((*p, Box::new(make_protocol(comms, fut))));
+        }
+        let (_, snapshot) = run_protocol_and_take_snapshots(protocols).unwrap();
+        let simulator = Simulator::new(participants[0], snapshot).unwrap();
+        assert_eq!(simulator.round_count(), ROUNDS);
+        assert_eq!(
+            simulator.messages_per_waitpoint(participants[1]),
+            vec![1; ROUNDS]
+        );
+    }
 }
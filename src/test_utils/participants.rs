@@ -1,5 +1,6 @@
 use crate::participants::Participant;
 use rand_core::CryptoRngCore;
+use std::collections::BTreeSet;
 
 // +++++++++++++++++ Participants Utilities +++++++++++++++++ //
 /// Generates a vector of `number` participants, sorted by the participant id.
@@ -10,15 +11,54 @@ pub fn generate_participants(number: usize) -> Vec<Participant> {
         .collect::<Vec<_>>()
 }
 
-/// Generates a vector of `number` participants, sorted by the participant id.
-/// The participants ids are drawn from rng.
+/// Generates a vector of `number` distinct participants, sorted by the participant id.
+/// The participants ids are drawn from rng, redrawing the ones already taken.
 pub fn generate_participants_with_random_ids(
     number: usize,
     rng: &mut impl CryptoRngCore,
 ) -> Vec<Participant> {
-    let mut participants = (0..number)
-        .map(|_| Participant::from(rng.next_u32()))
-        .collect::<Vec<_>>();
-    participants.sort();
-    participants
+    let mut participants = BTreeSet::new();
+    while participants.len() < number {
+        participants.insert(Participant::from(rng.next_u32()));
+    }
+    participants.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::generate_participants_with_random_ids;
+    use crate::participants::Participant;
+    use rand::{CryptoRng, RngCore};
+
+    /// Outputs every value twice in a row: 0, 0, 1, 1, 2, ...
+    struct CollidingRng(u32);
+
+    impl RngCore for CollidingRng {
+        fn next_u32(&mut self) -> u32 {
+            let value = self.0 / 2;
+            self.0 += 1;
+            value
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.next_u32().into()
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest);
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for CollidingRng {}
+
+    #[test]
+    fn test_random_ids_are_distinct() {
+        let participants = generate_participants_with_random_ids(5, &mut CollidingRng(0));
+        assert_eq!(
+            participants,
+            (0..5_u32).map(Participant::from).collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,12 +1,13 @@
 use frost_core::{Field, Group};
 use frost_secp256k1::Secp256K1Sha256;
-use k256::AffinePoint;
-use rand_core::CryptoRngCore;
+use k256::{AffinePoint, ProjectivePoint};
+use rand_core::{CryptoRngCore, SeedableRng};
 use std::error::Error;
 
 use crate::ecdsa::{RerandomizationArguments, Tweak};
+use crate::errors::ProtocolError;
 use crate::frost;
-use crate::test_utils::{run_protocol, GenProtocol};
+use crate::test_utils::{generate_participants, run_protocol, GenProtocol, MockCryptoRng};
 use crate::{
     Ciphersuite, Participant, ParticipantList, ReconstructionLowerBound, Scalar, VerifyingKey,
 };
@@ -48,6 +49,52 @@ pub fn ecdsa_generate_rerandpresig_args(
     (args, msg_hash)
 }
 
+/// Checks that rerandomizing ECDSA presignatures in a batch matches
+/// rerandomizing them one by one, for either ECDSA scheme.
+///
+/// `presignature` builds a presignature of random shares committing to the given point,
+/// and `shares` returns the commitment and the shares of a rerandomized presignature.
+pub fn check_rerandomize_presign_many<P, R>(
+    presignature: impl Fn(AffinePoint, &ParticipantList, &mut MockCryptoRng) -> P,
+    rerandomize: impl Fn(&P, &RerandomizationArguments) -> Result<R, ProtocolError>,
+    rerandomize_many: impl Fn(&[P], &[RerandomizationArguments]) -> Result<Vec<R>, ProtocolError>,
+    shares: impl Fn(&R) -> (AffinePoint, Vec<Scalar<Secp256K1Sha256>>),
+) {
+    let mut rng = MockCryptoRng::seed_from_u64(42);
+    let participants = ParticipantList::new(&generate_participants(3)).unwrap();
+    let random_point = |rng: &mut MockCryptoRng| {
+        ProjectivePoint::GENERATOR * frost_core::random_nonzero::<Secp256K1Sha256, _>(rng)
+    };
+    let pk = VerifyingKey::new(random_point(&mut rng));
+
+    let mut presignatures = Vec::new();
+    let mut args = Vec::new();
+    for _ in 0..5 {
+        let big_r = random_point(&mut rng).to_affine();
+        presignatures.push(presignature(big_r, &participants, &mut rng));
+        let (presign_args, _) =
+            ecdsa_generate_rerandpresig_args(&mut rng, participants.participants(), pk, big_r);
+        args.push(presign_args);
+    }
+
+    let many = rerandomize_many(&presignatures, &args).unwrap();
+    assert_eq!(many.len(), presignatures.len());
+    for ((presignature, args), rerandomized) in presignatures.iter().zip(&args).zip(&many) {
+        let single = rerandomize(presignature, args).unwrap();
+        assert_eq!(shares(rerandomized), shares(&single));
+    }
+
+    assert!(rerandomize_many(&[], &[]).unwrap().is_empty());
+    // one set of arguments per presignature
+    assert!(rerandomize_many(&presignatures[1..], &args).is_err());
+    // the arguments must match their presignature
+    args.swap(0, 1);
+    assert_eq!(
+        rerandomize_many(&presignatures, &args).err(),
+        Some(ProtocolError::IncompatibleRerandomizationInputs)
+    );
+}
+
 // +++++++++++++++++ EdDSA Presignature Rerandomization +++++++++++++++++ //
 type BoxErr = Box<dyn Error>;
 pub fn frost_run_presignature<C>(
@@ -1,8 +1,9 @@
 use crate::errors::ProtocolError;
-use crate::participants::Participant;
+use crate::participants::{Participant, ParticipantCounter, ParticipantList};
 use crate::protocol::{Action, Protocol};
-use crate::test_utils::{ProtocolSnapshot, Simulator};
+use crate::test_utils::{ProtocolMetrics, ProtocolSnapshot, Simulator};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 // +++++++++++++++++ Any Protocol +++++++++++++++++ //
 /// Run a protocol to completion, synchronously.
@@ -15,23 +16,44 @@ use std::collections::HashMap;
 pub fn run_protocol<T>(
     ps: Vec<(Participant, Box<dyn Protocol<Output = T>>)>,
 ) -> Result<Vec<(Participant, T)>, ProtocolError> {
-    run_protocol_common(ps, false).map(|(v, _)| v)
+    run_protocol_common(ps, false).map(|(v, _, _)| v)
 }
 
 /// Like [`run_protocol()`], except that it snapshots all the communication.
 pub fn run_protocol_and_take_snapshots<T>(
     ps: Vec<(Participant, Box<dyn Protocol<Output = T>>)>,
 ) -> Result<(Vec<(Participant, T)>, ProtocolSnapshot), ProtocolError> {
-    run_protocol_common(ps, true).map(|(v, snapshot)| (v, snapshot.unwrap()))
+    run_protocol_common(ps, true).map(|(v, snapshot, _)| (v, snapshot.unwrap()))
+}
+
+/// Like [`run_protocol()`], except that it also tallies the pokes,
+/// the messages and the bytes sent and received by each participant.
+pub fn run_protocol_instrumented<T>(
+    ps: Vec<(Participant, Box<dyn Protocol<Output = T>>)>,
+) -> Result<(Vec<(Participant, T)>, ProtocolMetrics), ProtocolError> {
+    run_protocol_common(ps, false).map(|(v, _, metrics)| (v, metrics))
 }
 
 /// Runs one real participant and one simulation representing the rest of participants
 /// The simulation has an internal storage of what to send to the real participant
 pub fn run_simulated_protocol<T>(
     real_participant: Participant,
-    mut real_prot: Box<dyn Protocol<Output = T>>,
+    real_prot: Box<dyn Protocol<Output = T>>,
     simulator: Simulator,
 ) -> Result<T, ProtocolError> {
+    run_simulated_protocol_timed(real_participant, real_prot, simulator).map(|(out, _)| out)
+}
+
+/// Like [`run_simulated_protocol()`], except that it also returns the time
+/// spent poking the real participant.
+///
+/// The recorded messages are all delivered before the clock starts and whatever
+/// the real participant sends is dropped, so only its computation is measured.
+pub fn run_simulated_protocol_timed<T>(
+    real_participant: Participant,
+    mut real_prot: Box<dyn Protocol<Output = T>>,
+    simulator: Simulator,
+) -> Result<(T, Duration), ProtocolError> {
     if simulator.real_participant() != real_participant {
         return Err(ProtocolError::AssertionFailed(
             "The given real participant does not match the simulator's internal real participant"
@@ -44,14 +66,26 @@ pub fn run_simulated_protocol<T>(
         real_prot.message(from, data);
     }
 
-    let mut out = None;
-    while out.is_none() {
-        let action = real_prot.poke()?;
-        if let Action::Return(output) = action {
-            out = Some(output);
+    let start = Instant::now();
+    loop {
+        if let Action::Return(output) = real_prot.poke()? {
+            return Ok((output, start.elapsed()));
         }
     }
-    out.ok_or_else(|| ProtocolError::Other("out is None".to_string()))
+}
+
+/// Replays the messages `me` received in `snapshot` to a fresh `protocol`,
+/// returning its output together with the time spent poking it,
+/// see [`run_simulated_protocol_timed()`].
+pub fn run_single_participant_replay<T>(
+    me: Participant,
+    protocol: Box<dyn Protocol<Output = T>>,
+    snapshot: ProtocolSnapshot,
+) -> Result<(T, Duration), ProtocolError> {
+    let simulator = Simulator::new(me, snapshot).ok_or_else(|| {
+        ProtocolError::Other(format!("no messages to replay for participant {me:?}"))
+    })?;
+    run_simulated_protocol_timed(me, protocol, simulator)
 }
 
 /// Like [`run_protocol()`], except for just two parties.
@@ -108,9 +142,17 @@ pub fn run_two_party_protocol<T0: std::fmt::Debug, T1: std::fmt::Debug>(
 fn run_protocol_common<T>(
     mut ps: Vec<(Participant, Box<dyn Protocol<Output = T>>)>,
     take_snapshots: bool,
-) -> Result<(Vec<(Participant, T)>, Option<ProtocolSnapshot>), ProtocolError> {
+) -> Result<
+    (
+        Vec<(Participant, T)>,
+        Option<ProtocolSnapshot>,
+        ProtocolMetrics,
+    ),
+    ProtocolError,
+> {
     let indices: HashMap<Participant, usize> =
         ps.iter().enumerate().map(|(i, (p, _))| (*p, i)).collect();
+    let mut metrics = ProtocolMetrics::new(&ps.iter().map(|(p, _)| *p).collect::<Vec<_>>());
 
     let mut protocol_snapshots = {
         if take_snapshots {
@@ -123,13 +165,23 @@ fn run_protocol_common<T>(
 
     let size = ps.len();
     let mut out = Vec::with_capacity(size);
+    // participants that already returned are not poked anymore,
+    // whatever their output is (e.g. None for non-coordinators)
+    let mut returned = vec![false; size];
     while out.len() < size {
+        let mut progress = false;
         for i in 0..size {
+            if returned[i] {
+                continue;
+            }
             while {
                 let action = ps[i].1.poke()?;
+                metrics.record_poke();
                 match action {
                     Action::Wait => false,
                     Action::SendMany(m) => {
+                        progress = true;
+                        metrics.record_send_many();
                         for j in 0..size {
                             if i == j {
                                 continue;
@@ -148,12 +200,16 @@ fn run_protocol_common<T>(
                                     })?;
                             }
 
+                            metrics.record_delivery(from, to, m.len());
                             ps[j].1.message(from, m.clone());
                         }
                         true
                     }
                     Action::SendPrivate(to, m) => {
+                        progress = true;
+                        metrics.record_send_private();
                         let from = ps[i].0;
+                        metrics.record_delivery(from, to, m.len());
                         if let Some(protocol_snapshots) = &mut protocol_snapshots {
                             // snapshot the message
                             protocol_snapshots
@@ -168,13 +224,78 @@ fn run_protocol_common<T>(
                         true
                     }
                     Action::Return(r) => {
+                        progress = true;
+                        returned[i] = true;
                         out.push((ps[i].0, r));
                         false
                     }
                 }
             } {}
         }
+        // a full pass without any message nor output cannot be followed by a different one
+        if !progress {
+            let participants =
+                ParticipantList::new(&ps.iter().map(|(p, _)| *p).collect::<Vec<_>>()).unwrap();
+            let mut done = ParticipantCounter::new(&participants);
+            for (p, _) in &out {
+                done.put(*p);
+            }
+            return Err(ProtocolError::Other(format!(
+                "protocol is stuck: {} participant(s) out of {size} never returned: {:?}",
+                size - out.len(),
+                done.missing()
+            )));
+        }
     }
     out.sort_by_key(|(p, _)| *p);
-    Ok((out, protocol_snapshots))
+    Ok((out, protocol_snapshots, metrics))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run_protocol, run_protocol_and_take_snapshots, run_single_participant_replay};
+    use crate::errors::ProtocolError;
+    use crate::participants::Participant;
+    use crate::protocol::Protocol;
+    use crate::test_utils::{generate_participants, GenProtocol, MockCryptoRng};
+    use crate::{keygen, KeygenOutput};
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_run_protocol_detects_stuck_protocols() {
+        let participants = generate_participants(3);
+        // the last participant never runs, so the others wait for it forever
+        let mut protocols: GenProtocol<KeygenOutput<Secp256K1Sha256>> = Vec::new();
+        for p in participants.iter().take(2) {
+            let rng = MockCryptoRng::seed_from_u64(u64::from(u32::from(*p)));
+            let protocol = keygen::<Secp256K1Sha256>(&participants, *p, 2, rng).unwrap();
+            protocols.push((*p, Box::new(protocol)));
+        }
+        let result = run_protocol(protocols);
+        let Err(ProtocolError::Other(message)) = result else {
+            panic!("the protocol should be stuck");
+        };
+        // the report names the participant that never ran
+        assert!(message.ends_with(&format!("{:?}", [participants[2]])));
+    }
+
+    #[test]
+    fn test_run_single_participant_replay() {
+        let participants = generate_participants(3);
+        let make_keygen =
+            |p: Participant| -> Box<dyn Protocol<Output = KeygenOutput<Secp256K1Sha256>>> {
+                let rng = MockCryptoRng::seed_from_u64(u64::from(u32::from(p)));
+                Box::new(keygen::<Secp256K1Sha256>(&participants, p, 2, rng).unwrap())
+            };
+        let protocols: GenProtocol<KeygenOutput<Secp256K1Sha256>> =
+            participants.iter().map(|p| (*p, make_keygen(*p))).collect();
+        let (outputs, snapshot) = run_protocol_and_take_snapshots(protocols).unwrap();
+
+        // with the same randomness, replaying the recorded messages gives the same output
+        let me = participants[0];
+        let (output, _) = run_single_participant_replay(me, make_keygen(me), snapshot).unwrap();
+        let expected = outputs.into_iter().find(|(p, _)| *p == me).unwrap().1;
+        assert_eq!(output, expected);
+    }
 }
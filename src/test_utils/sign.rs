@@ -1,6 +1,6 @@
 use crate::errors::{InitializationError, ProtocolError};
 use crate::participants::Participant;
-use crate::protocol::Protocol;
+use crate::protocol::{Protocol, RoleOutput};
 use crate::test_utils::{run_protocol, GenProtocol};
 use crate::{Ciphersuite, Element, Scalar};
 use std::error::Error;
@@ -46,16 +46,42 @@ where
     Ok(run_protocol(protocols)?)
 }
 
+/// The output of a protocol in which only the coordinator gets a result,
+/// either as an `Option` or as a [`RoleOutput`].
+pub trait CoordinatorOutput {
+    type Output;
+
+    fn into_coordinator_output(self) -> Option<Self::Output>;
+}
+
+impl<T> CoordinatorOutput for Option<T> {
+    type Output = T;
+
+    fn into_coordinator_output(self) -> Option<T> {
+        self
+    }
+}
+
+impl<T> CoordinatorOutput for RoleOutput<T> {
+    type Output = T;
+
+    fn into_coordinator_output(self) -> Option<T> {
+        RoleOutput::into_coordinator_output(self)
+    }
+}
+
 /// Checks that the list contains all None but one element
 /// and verifies such element belongs to the coordinator
-pub fn check_one_coordinator_output<ProtocolOutput: Clone>(
-    all_sigs: Vec<(Participant, Option<ProtocolOutput>)>,
+pub fn check_one_coordinator_output<O: CoordinatorOutput>(
+    all_sigs: Vec<(Participant, O)>,
     coordinator: Participant,
-) -> Result<ProtocolOutput, ProtocolError> {
-    let mut some_iter = all_sigs.into_iter().filter(|(_, sig)| sig.is_some());
+) -> Result<O::Output, ProtocolError> {
+    let mut some_iter = all_sigs
+        .into_iter()
+        .filter_map(|(p, sig)| sig.into_coordinator_output().map(|sig| (p, sig)));
 
     // test there is at least one not None element
-    let (p, c_opt) = some_iter
+    let (p, out) = some_iter
         .next()
         .ok_or(ProtocolError::MismatchCoordinatorOutput)?;
 
@@ -65,8 +91,6 @@ pub fn check_one_coordinator_output<ProtocolOutput: Clone>(
     }
 
     // test the participant is unique
-    let out = c_opt.ok_or(ProtocolError::MismatchCoordinatorOutput)?;
-
     if some_iter.next().is_some() {
         return Err(ProtocolError::MismatchCoordinatorOutput);
     }
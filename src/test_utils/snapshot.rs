@@ -188,6 +188,7 @@ mod test {
         KeygenOutput {
             private_share: SigningShare::new(private_share.0),
             public_key: verifying_key,
+            epoch: 0,
         }
     }
 
@@ -220,6 +221,7 @@ mod test {
                     PresignArguments {
                         keygen_out,
                         max_malicious: max_malicious.into(),
+                        active_security: true,
                     },
                     rng_p,
                 )
@@ -157,6 +157,7 @@ impl TestGenerators {
                             triple1: triple1s[participant].clone(),
                             keygen_out: keygens[participant].clone(),
                             threshold: self.threshold,
+                            record_provenance: false,
                         },
                     )
                     .unwrap(),
@@ -215,7 +216,9 @@ impl TestGenerators {
                         *participant,
                         derived_public_key,
                         rerandomized_presignature,
-                        msg_hash,
+                        presign_out.id,
+                        0,
+                        ecdsa::MessageHash::from_prehashed(msg_hash.to_bytes().into()),
                     )
                     .unwrap(),
                 ),
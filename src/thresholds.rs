@@ -1,6 +1,8 @@
 use derive_more::{From, Into};
 use serde::{Deserialize, Serialize};
 
+use crate::errors::InitializationError;
+
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, From, Into,
 )]
@@ -23,3 +25,153 @@ impl ReconstructionLowerBound {
         self.0
     }
 }
+
+/// The protocol families whose participant count constraints differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// Distributed key generation and resharing, relying on echo broadcast.
+    Dkg,
+    /// Robust ECDSA, where the threshold is the maximum number of malicious parties.
+    RobustEcdsa,
+    /// OT based ECDSA, where the threshold is the reconstruction lower bound.
+    OtBasedEcdsa,
+    /// FROST based EdDSA and RedJubJub, where the threshold is the reconstruction lower bound.
+    Frost,
+}
+
+/// Returns the smallest participant count `n` tolerating `max_malicious` faulty
+/// parties in the given scheme, together with the threshold to pass to its protocols.
+///
+/// That is `(3f+1, f+1)` for DKG, `(2f+1, f)` for robust ECDSA and `(f+1, f+1)`
+/// for OT based ECDSA and FROST.
+pub fn recommended_params(
+    scheme: Scheme,
+    max_malicious: usize,
+) -> Result<(usize, usize), InitializationError> {
+    if max_malicious == 0 {
+        return Err(InitializationError::BadParameters(
+            "max_malicious must be at least 1".to_string(),
+        ));
+    }
+    let overflow = || {
+        InitializationError::BadParameters(
+            "recommended participant count must be less than usize::MAX".to_string(),
+        )
+    };
+    let f_plus_1 = max_malicious.checked_add(1).ok_or_else(overflow)?;
+    match scheme {
+        Scheme::Dkg => {
+            let n = max_malicious
+                .checked_mul(3)
+                .and_then(|v| v.checked_add(1))
+                .ok_or_else(overflow)?;
+            Ok((n, f_plus_1))
+        }
+        Scheme::RobustEcdsa => {
+            let n = max_malicious
+                .checked_mul(2)
+                .and_then(|v| v.checked_add(1))
+                .ok_or_else(overflow)?;
+            Ok((n, max_malicious))
+        }
+        Scheme::OtBasedEcdsa | Scheme::Frost => Ok((f_plus_1, f_plus_1)),
+    }
+}
+
+/// Checks that `threshold` is the one the protocols of `scheme` expect
+/// from `participants` parties, following [`recommended_params`].
+///
+/// For DKG, OT based ECDSA and FROST the threshold is the reconstruction lower bound,
+/// between 2 and the participant count. For robust ECDSA it is the maximum number
+/// of malicious parties `f`, at least 1, and there must be exactly `2f+1` participants.
+pub fn validate_threshold(
+    scheme: Scheme,
+    participants: usize,
+    threshold: usize,
+) -> Result<(), InitializationError> {
+    match scheme {
+        Scheme::Dkg | Scheme::OtBasedEcdsa | Scheme::Frost => {
+            if threshold < 2 || threshold > participants {
+                let name = match scheme {
+                    Scheme::Dkg => "DKG",
+                    Scheme::OtBasedEcdsa => "OT based ECDSA",
+                    _ => "FROST",
+                };
+                return Err(InitializationError::BadParameters(format!(
+                    "{name} expects a reconstruction lower bound between 2 and the {participants} participants, got {threshold}"
+                )));
+            }
+        }
+        Scheme::RobustEcdsa => {
+            if threshold == 0 {
+                return Err(InitializationError::BadParameters(
+                    "robust ECDSA expects max_malicious to be at least 1".to_string(),
+                ));
+            }
+            if threshold.checked_mul(2).and_then(|v| v.checked_add(1)) != Some(participants) {
+                return Err(InitializationError::BadParameters(format!(
+                    "robust ECDSA expects exactly 2*max_malicious+1 participants to avoid split view attacks, got {participants} for max_malicious {threshold}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_recommended_params() {
+        assert_eq!(recommended_params(Scheme::Dkg, 1).unwrap(), (4, 2));
+        assert_eq!(recommended_params(Scheme::Dkg, 5).unwrap(), (16, 6));
+        assert_eq!(recommended_params(Scheme::RobustEcdsa, 1).unwrap(), (3, 1));
+        assert_eq!(recommended_params(Scheme::RobustEcdsa, 5).unwrap(), (11, 5));
+        assert_eq!(recommended_params(Scheme::OtBasedEcdsa, 1).unwrap(), (2, 2));
+        assert_eq!(recommended_params(Scheme::OtBasedEcdsa, 5).unwrap(), (6, 6));
+        assert_eq!(recommended_params(Scheme::Frost, 2).unwrap(), (3, 3));
+    }
+
+    #[test]
+    fn test_recommended_params_rejects_bad_inputs() {
+        for scheme in [
+            Scheme::Dkg,
+            Scheme::RobustEcdsa,
+            Scheme::OtBasedEcdsa,
+            Scheme::Frost,
+        ] {
+            assert!(recommended_params(scheme, 0).is_err());
+            assert!(recommended_params(scheme, usize::MAX).is_err());
+        }
+        assert!(recommended_params(Scheme::Dkg, usize::MAX / 3).is_err());
+        assert!(recommended_params(Scheme::RobustEcdsa, usize::MAX / 2).is_ok());
+    }
+
+    #[test]
+    fn test_validate_threshold() {
+        for scheme in [
+            Scheme::Dkg,
+            Scheme::RobustEcdsa,
+            Scheme::OtBasedEcdsa,
+            Scheme::Frost,
+        ] {
+            for max_malicious in [1, 2, 5] {
+                let (n, t) = recommended_params(scheme, max_malicious).unwrap();
+                assert!(validate_threshold(scheme, n, t).is_ok());
+            }
+        }
+        for scheme in [Scheme::Dkg, Scheme::OtBasedEcdsa] {
+            assert!(validate_threshold(scheme, 5, 1).is_err());
+            assert!(validate_threshold(scheme, 5, 6).is_err());
+            assert!(validate_threshold(scheme, 5, 3).is_ok());
+        }
+        // the reconstruction lower bound f+1 is not a valid robust ECDSA threshold
+        assert!(matches!(
+            validate_threshold(Scheme::RobustEcdsa, 5, 3),
+            Err(InitializationError::BadParameters(_))
+        ));
+        assert!(validate_threshold(Scheme::RobustEcdsa, 1, 0).is_err());
+        assert!(validate_threshold(Scheme::RobustEcdsa, 7, 2).is_err());
+    }
+}
@@ -7,7 +7,7 @@ use threshold_signatures::{
     confidential_key_derivation::{
         ciphersuite::{verify_signature, Field as _, G1Projective, Group as _},
         protocol::ckd,
-        AppId, CKDOutputOption,
+        AppId, CKDRoleOutput, CkdParams,
     },
     participants::Participant,
 };
@@ -36,7 +36,7 @@ fn test_ckd() {
     let public_key = keys.get(&participants[0]).unwrap().public_key;
     let coordinator = choose_coordinator_at_random(&participants);
 
-    let mut protocols: GenProtocol<CKDOutputOption> = Vec::with_capacity(participants.len());
+    let mut protocols: GenProtocol<CKDRoleOutput> = Vec::with_capacity(participants.len());
 
     for p in &participants {
         let key_pair = keys.get(p).unwrap();
@@ -48,6 +48,7 @@ fn test_ckd() {
             key_pair.clone(),
             app_id.clone(),
             app_pk,
+            CkdParams::default(),
             OsRng,
         )
         .unwrap();
@@ -58,19 +59,20 @@ fn test_ckd() {
     let result = run_protocol(protocols).unwrap();
 
     // test one single some for the coordinator
-    let mut some_iter = result.into_iter().filter(|(_, ckd)| ckd.is_some());
+    let mut some_iter = result
+        .into_iter()
+        .filter_map(|(_, ckd)| ckd.into_coordinator_output());
 
     let ckd = some_iter
         .next()
-        .map(|(_, c)| c.unwrap())
-        .expect("Expected exactly one Some(CKDCoordinatorOutput)");
+        .expect("Expected exactly one RoleOutput::Coordinator");
     assert!(
         some_iter.next().is_none(),
-        "More than one Some(CKDCoordinatorOutput)"
+        "More than one RoleOutput::Coordinator"
     );
 
     // compute msk . H(app_id)
-    let confidential_key = ckd.unmask(app_sk);
+    let confidential_key = ckd.unmask(&app_id, app_sk).unwrap();
     assert!(verify_signature(&public_key, &app_id, &confidential_key).is_ok());
 
     let participant_keys = keys.into_iter().collect::<Vec<_>>();
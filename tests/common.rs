@@ -130,6 +130,8 @@ where
         }
     }
 
+    // every old share is in the same epoch
+    let old_epoch = keys.first().map_or(0, |(_, k)| k.epoch);
     let protocols: GenProtocol<KeygenOutput<C>> = setup
         .iter()
         .map(|(p, out)| {
@@ -139,6 +141,7 @@ where
                     old_threshold,
                     out.0,
                     out.1,
+                    old_epoch,
                     new_participants,
                     new_threshold,
                     *p,
@@ -12,7 +12,7 @@ use threshold_signatures::{
     self,
     frost::eddsa::{
         sign::{sign_v1, sign_v2},
-        Ed25519Sha512, PresignOutput, SignatureOption,
+        Ed25519Sha512, PresignOutput, SignatureRoleOutput,
     },
     participants::Participant,
     test_utils::frost_run_presignature,
@@ -27,8 +27,8 @@ fn run_sign_v1(
     participants: &[(Participant, KeygenOutput)],
     coordinator: Participant,
     msg_hash: &[u8],
-) -> Vec<(Participant, SignatureOption)> {
-    let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+) -> Vec<(Participant, SignatureRoleOutput)> {
+    let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(participants.len());
 
     let participants_list: Vec<Participant> = participants.iter().map(|(p, _)| *p).collect();
     for (p, keygen_output) in participants {
@@ -38,6 +38,7 @@ fn run_sign_v1(
             *p,
             coordinator,
             keygen_output.clone(),
+            None,
             msg_hash.to_vec(),
             OsRng,
         )
@@ -55,8 +56,8 @@ fn run_sign_v2(
     coordinator: Participant,
     presig: &[(Participant, PresignOutput)],
     msg_hash: &[u8],
-) -> Vec<(Participant, SignatureOption)> {
-    let mut protocols: GenProtocol<SignatureOption> = Vec::with_capacity(participants.len());
+) -> Vec<(Participant, SignatureRoleOutput)> {
+    let mut protocols: GenProtocol<SignatureRoleOutput> = Vec::with_capacity(participants.len());
 
     let participants_list: Vec<Participant> = participants.iter().map(|(p, _)| *p).collect();
     for ((p, keygen_output), (participant_redundancy, presignature)) in
@@ -69,6 +70,7 @@ fn run_sign_v2(
             *p,
             coordinator,
             keygen_output.clone(),
+            None,
             presignature.clone(),
             msg_hash.to_vec(),
         )
@@ -100,11 +102,8 @@ fn test_run_sign_v1() {
 
     let signature = all_sigs
         .into_iter()
-        .filter(|(p, sig)| *p == coordinator && sig.is_some())
-        .collect::<Vec<_>>()
-        .first()
-        .unwrap()
-        .1
+        .filter(|(p, _)| *p == coordinator)
+        .find_map(|(_, sig)| sig.into_coordinator_output())
         .unwrap();
 
     assert!(public_key.verify(&msg_hash, &signature).is_ok());
@@ -174,11 +173,8 @@ fn test_run_sign_v2() {
 
     let signature = all_sigs
         .into_iter()
-        .filter(|(p, sig)| *p == coordinator && sig.is_some())
-        .collect::<Vec<_>>()
-        .first()
-        .unwrap()
-        .1
+        .filter(|(p, _)| *p == coordinator)
+        .find_map(|(_, sig)| sig.into_coordinator_output())
         .unwrap();
 
     assert!(public_key.verify(&msg_hash, &signature).is_ok());
@@ -13,7 +13,8 @@ use threshold_signatures::{
             presign::presign, sign::sign, PresignArguments, PresignOutput,
             RerandomizedPresignOutput,
         },
-        RerandomizationArguments, Secp256K1Sha256, Signature, SignatureOption,
+        MessageHash, PresignId, RerandomizationArguments, Secp256K1Sha256, Signature,
+        SignatureOption,
     },
     frost_secp256k1::VerifyingKey,
     participants::Participant,
@@ -45,6 +46,7 @@ fn run_presign(
             PresignArguments {
                 keygen_out,
                 max_malicious,
+                active_security: true,
             },
             OsRng,
         )
@@ -57,6 +59,7 @@ fn run_presign(
 
 fn run_sign(
     participants_presign: Vec<(Participant, RerandomizedPresignOutput)>,
+    presign_id: PresignId,
     max_malicious: MaxMalicious,
     coordinator: Participant,
     public_key: Element<C>,
@@ -78,7 +81,9 @@ fn run_sign(
             p,
             public_key.to_affine(),
             presignature,
-            msg_hash,
+            presign_id,
+            0,
+            MessageHash::from_prehashed(msg_hash.to_bytes().into()),
         )
         .unwrap();
 
@@ -132,6 +137,7 @@ fn run_sign_with_rerandomization(
     // run sign instantiation with the necessary arguments
     let all_sigs = run_sign(
         rerand_participants_presign,
+        participants_presign[0].1.id,
         max_malicious,
         coordinator,
         derived_pk,